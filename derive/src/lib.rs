@@ -18,12 +18,12 @@ pub fn derive_parser(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         visitor: &mut Visitor,
                     ) -> Result<(), harpi::Error>
                     where
-                        Visitor: harpi::ProtoVisitor {
-                        let (syntax,body) = harpi::header::parse_header(data)?;
-                        match syntax.value().as_ref() {
-                            #(<#parser>::SYNTAX => <#parser>::parse_with_syntax(body,syntax,visitor),)*
-                                _ => Err(harpi::Error::UndefinedParser(syntax.value().to_string()))
-                        }
+                        Visitor: harpi::ProtoVisitor<'a> {
+                        let (syntax,_) = harpi::header::parse_header(data)?;
+                        #(if <#parser as harpi::ProtoParser>::supports(&syntax) {
+                            return <#parser as harpi::ProtoParser>::parse(data,visitor);
+                        })*
+                        Err(harpi::Error::UndefinedParser(syntax.value().to_string()))
                     }
 
                     fn parse_with_syntax<'a, Visitor>(
@@ -32,17 +32,32 @@ pub fn derive_parser(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         visitor: &mut Visitor,
                     ) -> Result<(), harpi::Error>
                     where
-                        Visitor: harpi::ProtoVisitor {
-                        let (parsed_syntax,body) = harpi::header::parse_header(data)?;
-                        if syntax.value() != parsed_syntax.value() {
-                            return Err(harpi::Error::InvalidSyntax(syntax.value().to_string(),parsed_syntax.value().to_string()));
-                        }
-                        match syntax.value().as_ref() {
-                            #(<#parser>::SYNTAX => <#parser>::parse_with_syntax(body,syntax,visitor),)*
-                                _ => Err(harpi::Error::UndefinedParser(syntax.value().to_string()))
-                        }
+                        Visitor: harpi::ProtoVisitor<'a> {
+                        #(if <#parser as harpi::ProtoParser>::supports(&syntax) {
+                            return <#parser as harpi::ProtoParser>::parse_with_syntax(data,syntax,visitor);
+                        })*
+                        Err(harpi::Error::UndefinedParser(syntax.value().to_string()))
                     }
 
+                    fn parse_recovering<'a, Visitor>(
+                        data: &'a str,
+                        visitor: &mut Visitor,
+                    ) -> Vec<harpi::Diagnostic>
+                    where
+                        Visitor: harpi::ProtoVisitor<'a> {
+                        let syntax = match harpi::header::parse_header(data) {
+                            Ok((syntax, _)) => syntax,
+                            Err(error) => return vec![error.to_diagnostic()],
+                        };
+                        #(if <#parser as harpi::ProtoParser>::supports(&syntax) {
+                            return <#parser as harpi::ProtoParser>::parse_recovering(data,visitor);
+                        })*
+                        vec![harpi::Error::UndefinedParser(syntax.value().to_string()).to_diagnostic().with_span(*syntax.value_span())]
+                    }
+
+                    fn supports(syntax: &harpi::model::Syntax<'_>) -> bool {
+                        false #(|| <#parser as harpi::ProtoParser>::supports(syntax))*
+                    }
                 }
             }
             .into_token_stream()
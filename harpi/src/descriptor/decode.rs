@@ -0,0 +1,827 @@
+use super::{
+    Decode, DescriptorProto, EnumDescriptorProto, FEATURE_SET, FieldDescriptorProto, FieldType,
+    FileDescriptorProto, FileDescriptorSet, Label, MethodDescriptorProto, OptionKind, OptionTarget,
+    Options, Reader, ServiceDescriptorProto, SymbolVisibility, WireValue, edition_name,
+    index::Index, json_name, unzigzag,
+};
+use crate::{
+    Diagnostic, DiagnosticCode, Error, MAX_FIELD_NUMBER, join,
+    model::{
+        self, Aggregate, AggregateField, AggregateFieldName, AggregateValue, Constant, Enum,
+        EnumItem, Extend, ExtensionRange, Extensions, Field, GroupField, Ident, Import, MapField,
+        MapFieldKeyType, Message, MessageReference, NormalField, OneOfField, OneOfFieldItem,
+        OptionName, Package, Proto, ReservedData, ReservedItems, Service, ServiceRpc,
+        ServiceRpcField, Span, Syntax, Type, Visibility,
+    },
+};
+
+/// Decoded files, named by their import path.
+pub type DecodedFiles = Vec<(String, Proto<'static>)>;
+
+/// Converts descriptor protos back into files. Map fields, oneofs and proto3 `optional` fields
+/// are rebuilt from their synthetic forms, and custom options are named after the extensions
+/// declared by the set.
+#[derive(Debug)]
+pub struct DescriptorDecoder<'s> {
+    set: &'s FileDescriptorSet,
+    index: Index,
+}
+impl<'s> DescriptorDecoder<'s> {
+    pub fn new(set: &'s FileDescriptorSet) -> Self {
+        let mut index = Index::default();
+        for file in &set.file {
+            index.add_file(file);
+        }
+        Self { set, index }
+    }
+
+    /// Decodes every file of the set, named by their import path, along with warnings for the
+    /// options that could not be decoded.
+    pub fn decode(&self) -> Result<(DecodedFiles, Vec<Diagnostic>), Error> {
+        let mut files = Vec::new();
+        let mut diagnostics = Vec::new();
+        for file in &self.set.file {
+            let (proto, mut file_diagnostics) = self.decode_file(file)?;
+            files.push((file.name.clone().unwrap_or_default(), proto));
+            diagnostics.append(&mut file_diagnostics);
+        }
+        Ok((files, diagnostics))
+    }
+
+    /// Decodes a single file, resolving its custom options against the set.
+    pub fn decode_file(
+        &self,
+        file: &FileDescriptorProto,
+    ) -> Result<(Proto<'static>, Vec<Diagnostic>), Error> {
+        let mut decoder = FileDecoder {
+            index: &self.index,
+            file: file.name.as_deref().unwrap_or_default(),
+            syntax: match file.syntax.as_deref() {
+                Some("proto3") => FileSyntax::Proto3,
+                Some("editions") => FileSyntax::Editions,
+                _ => FileSyntax::Proto2,
+            },
+            diagnostics: Vec::new(),
+        };
+        let proto = decoder.file(file)?;
+        Ok((proto, decoder.diagnostics))
+    }
+}
+
+/// Decodes the bytes of a `FileDescriptorSet`.
+pub fn decode_set(bytes: &[u8]) -> Result<(DecodedFiles, Vec<Diagnostic>), Error> {
+    let set = FileDescriptorSet::decode(bytes)?;
+    DescriptorDecoder::new(&set).decode()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileSyntax {
+    Proto2,
+    Proto3,
+    Editions,
+}
+
+struct FileDecoder<'d> {
+    index: &'d Index,
+    file: &'d str,
+    syntax: FileSyntax,
+    diagnostics: Vec<Diagnostic>,
+}
+impl FileDecoder<'_> {
+    fn file(&mut self, file: &FileDescriptorProto) -> Result<Proto<'static>, Error> {
+        let mut builder = Proto::builder();
+
+        let mut syntax = Syntax::builder();
+        match self.syntax {
+            FileSyntax::Proto2 => syntax.set_value("proto2"),
+            FileSyntax::Proto3 => syntax.set_value("proto3"),
+            FileSyntax::Editions => {
+                let edition = file.edition.unwrap_or_default();
+                syntax.set_value(
+                    edition_name(edition).map_or_else(|| edition.to_string(), str::to_string),
+                );
+                syntax.set_edition(true);
+            }
+        }
+        builder.set_syntax(syntax.build());
+
+        let mut package = Package::builder();
+        package.set_value(file.package.clone().unwrap_or_default());
+        builder.set_package(package.build());
+
+        for (index, dependency) in file.dependency.iter().enumerate() {
+            let index = index as i32;
+            let mut import = Import::builder();
+            import.set_value(dependency.clone());
+            import.set_public(file.public_dependency.contains(&index));
+            import.set_weak(file.weak_dependency.contains(&index));
+            builder.with_import(import.build());
+        }
+        for dependency in &file.option_dependency {
+            let mut import = Import::builder();
+            import.set_value(dependency.clone());
+            import.set_option(true);
+            builder.with_import(import.build());
+        }
+
+        let package = file.package.as_deref().unwrap_or_default();
+        for option in self.options(OptionTarget::File, &file.options)? {
+            builder.with_option(option);
+        }
+        let groups = self.group_types(&file.extension);
+        for message in &file.message_type {
+            if !groups.contains(&join(package, name(&message.name))) {
+                builder.with_message(self.message(package, message)?);
+            }
+        }
+        for value in &file.enum_type {
+            builder.with_enum(self.enumeration(value)?);
+        }
+        for service in &file.service {
+            builder.with_service(self.service(service)?);
+        }
+        for extend in self.extends(package, &file.extension, &file.message_type)? {
+            builder.with_extend(extend);
+        }
+        Ok(builder.build())
+    }
+
+    fn message(
+        &mut self,
+        scope: &str,
+        message: &DescriptorProto,
+    ) -> Result<Message<'static>, Error> {
+        let full_name = join(scope, name(&message.name));
+        let mut builder = Message::builder();
+        builder.set_name(ident(&message.name));
+        builder.set_visibility(visibility(message.visibility));
+
+        // Oneofs are written where their first field is declared.
+        let mut declared = vec![false; message.oneof_decl.len()];
+        for field in &message.field {
+            let Some(index) = field
+                .oneof_index
+                .filter(|index| !is_synthetic(message, *index))
+            else {
+                builder.with_field(self.field(&full_name, field, &message.nested_type)?);
+                continue;
+            };
+            let Some(oneof) = message.oneof_decl.get(index as usize) else {
+                return Err(Error::DecodingDescriptor(format!(
+                    "field `{}` refers to missing oneof {index}",
+                    name(&field.name)
+                )));
+            };
+            if std::mem::replace(&mut declared[index as usize], true) {
+                continue;
+            }
+            let mut oneof_builder = OneOfField::builder();
+            oneof_builder.set_name(ident(&oneof.name));
+            for option in self.options(OptionTarget::Oneof, &oneof.options)? {
+                oneof_builder.with_option(option);
+            }
+            for item in message
+                .field
+                .iter()
+                .filter(|item| item.oneof_index == Some(index))
+            {
+                let mut item_builder = OneOfFieldItem::builder();
+                item_builder.set_ty(ty(item)?);
+                item_builder.set_name(ident(&item.name));
+                item_builder.set_number(item.number.unwrap_or_default() as u64);
+                for option in self.field_options(item)? {
+                    item_builder.with_option(option);
+                }
+                oneof_builder.with_field(item_builder.build()?);
+            }
+            builder.with_field(Field::OneOf(oneof_builder.build()));
+        }
+
+        let mut skipped = self.group_types(&message.field);
+        skipped.extend(self.group_types(&message.extension));
+        for nested in &message.nested_type {
+            let nested_name = join(&full_name, name(&nested.name));
+            if !is_map_entry(nested) && !skipped.contains(&nested_name) {
+                builder.with_message(self.message(&full_name, nested)?);
+            }
+        }
+        for value in &message.enum_type {
+            builder.with_enum(self.enumeration(value)?);
+        }
+        for extend in self.extends(&full_name, &message.extension, &message.nested_type)? {
+            builder.with_extend(extend);
+        }
+        for range in &message.extension_range {
+            let mut extensions = Extensions::builder();
+            extensions.with_range(ExtensionRange::new(
+                range.start.unwrap_or_default() as i64,
+                range_end(
+                    range.end.unwrap_or_default() as i64 - 1,
+                    MAX_FIELD_NUMBER as i64,
+                ),
+            ));
+            for option in self.options(OptionTarget::ExtensionRange, &range.options)? {
+                extensions.with_option(option);
+            }
+            builder.with_extensions(extensions.build());
+        }
+        let ranges = message.reserved_range.iter().map(|range| {
+            let end = range.end.unwrap_or_default() as i64 - 1;
+            (
+                range.start.unwrap_or_default() as i64,
+                range_end(end, MAX_FIELD_NUMBER as i64),
+            )
+        });
+        for reserved in reserved(ranges, &message.reserved_name) {
+            builder.with_reserved(reserved);
+        }
+        for option in self.options(OptionTarget::Message, &message.options)? {
+            builder.with_option(option);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// A field outside of a oneof. `nested` holds the types declared next to it, among which its
+    /// map entry or group type.
+    fn field(
+        &mut self,
+        scope: &str,
+        field: &FieldDescriptorProto,
+        nested: &[DescriptorProto],
+    ) -> Result<Field<'static>, Error> {
+        let label = field.label.unwrap_or(Label::Optional);
+        let number = field.number.unwrap_or_default() as u64;
+        let nested_type = field.type_name.as_deref().and_then(|type_name| {
+            nested
+                .iter()
+                .find(|nested| join(scope, name(&nested.name)) == type_name.trim_start_matches('.'))
+        });
+
+        if field.r#type == Some(FieldType::Group) && self.syntax != FileSyntax::Editions {
+            let Some(group) = nested_type else {
+                return Err(Error::DecodingDescriptor(format!(
+                    "missing group type of `{}`",
+                    name(&field.name)
+                )));
+            };
+            let mut builder = GroupField::builder();
+            builder.set_repeated(label == Label::Repeated);
+            builder.set_required(label == Label::Required);
+            builder.set_optional(label == Label::Optional && self.syntax == FileSyntax::Proto2);
+            builder.set_number(number);
+            builder.set_message(self.message(scope, group)?);
+            for option in self.field_options(field)? {
+                builder.with_option(option);
+            }
+            return Ok(Field::Group(builder.build()?));
+        }
+        if field.r#type == Some(FieldType::Group) {
+            // Editions have no groups: protoc writes delimited message fields as such.
+            let mut builder = NormalField::builder();
+            builder.set_repeated(label == Label::Repeated);
+            builder.set_required(label == Label::Required);
+            builder.set_ty(ty(field)?);
+            builder.set_name(ident(&field.name));
+            builder.set_number(number);
+            let options = self.field_options(field)?;
+            if !options
+                .iter()
+                .any(|option| option.name().to_string() == "features.message_encoding")
+            {
+                builder.with_option(option(
+                    OptionName::plain("features.message_encoding"),
+                    Constant::Ident(Ident::new(false, "DELIMITED")),
+                )?);
+            }
+            for option in options {
+                builder.with_option(option);
+            }
+            return Ok(Field::Normal(builder.build()?));
+        }
+
+        if let Some(entry) = nested_type.filter(|entry| is_map_entry(entry))
+            && label == Label::Repeated
+        {
+            let key = entry.field.iter().find(|field| field.number == Some(1));
+            let value = entry.field.iter().find(|field| field.number == Some(2));
+            let (Some(key), Some(value)) = (key, value) else {
+                return Err(Error::DecodingDescriptor(format!(
+                    "map entry `{}` must declare a key and a value",
+                    name(&entry.name)
+                )));
+            };
+            let mut builder = MapField::builder();
+            builder.set_key_ty(key_type(key)?);
+            builder.set_value_ty(ty(value)?);
+            builder.set_name(ident(&field.name));
+            builder.set_number(number);
+            for option in self.field_options(field)? {
+                builder.with_option(option);
+            }
+            return Ok(Field::Map(builder.build()?));
+        }
+
+        let mut builder = NormalField::builder();
+        builder.set_repeated(label == Label::Repeated);
+        builder.set_required(label == Label::Required);
+        builder.set_optional(match self.syntax {
+            FileSyntax::Proto2 => label == Label::Optional,
+            FileSyntax::Proto3 => field.proto3_optional == Some(true),
+            FileSyntax::Editions => false,
+        });
+        builder.set_ty(ty(field)?);
+        builder.set_name(ident(&field.name));
+        builder.set_number(number);
+        for option in self.field_options(field)? {
+            builder.with_option(option);
+        }
+        Ok(Field::Normal(builder.build()?))
+    }
+
+    /// Fully qualified names, without a leading dot, of the group types used by `fields`. Such
+    /// types are written inside their group field, except in editions which have no groups.
+    fn group_types(&self, fields: &[FieldDescriptorProto]) -> Vec<String> {
+        if self.syntax == FileSyntax::Editions {
+            return Vec::new();
+        }
+        fields
+            .iter()
+            .filter(|field| field.r#type == Some(FieldType::Group))
+            .filter_map(|field| field.type_name.as_deref())
+            .map(|name| name.trim_start_matches('.').to_string())
+            .collect()
+    }
+
+    /// Options of a field, including the `default` and `json_name` pseudo-options.
+    fn field_options(
+        &mut self,
+        field: &FieldDescriptorProto,
+    ) -> Result<Vec<model::Option<'static>>, Error> {
+        let mut options = Vec::new();
+        if let Some(value) = default_value(field) {
+            options.push(option(OptionName::plain("default"), value)?);
+        }
+        if let Some(json) = &field.json_name
+            && *json != json_name(name(&field.name))
+        {
+            options.push(option(
+                OptionName::plain("json_name"),
+                Constant::String(json.clone().into()),
+            )?);
+        }
+        options.extend(self.options(OptionTarget::Field, &field.options)?);
+        Ok(options)
+    }
+
+    /// Extension fields, grouped into an `extend` block per consecutive extendee.
+    fn extends(
+        &mut self,
+        scope: &str,
+        extensions: &[FieldDescriptorProto],
+        nested: &[DescriptorProto],
+    ) -> Result<Vec<Extend<'static>>, Error> {
+        let mut groups: Vec<(&str, Vec<&FieldDescriptorProto>)> = Vec::new();
+        for extension in extensions {
+            let extendee = extension.extendee.as_deref().unwrap_or_default();
+            match groups.last_mut() {
+                Some((last, fields)) if *last == extendee => fields.push(extension),
+                _ => groups.push((extendee, vec![extension])),
+            }
+        }
+        let mut extends = Vec::new();
+        for (extendee, fields) in groups {
+            let mut builder = Extend::builder();
+            builder.set_extendee(MessageReference::new(extendee.to_string()));
+            for field in fields {
+                builder.with_field(self.field(scope, field, nested)?);
+            }
+            extends.push(builder.build()?);
+        }
+        Ok(extends)
+    }
+
+    fn enumeration(&mut self, value: &EnumDescriptorProto) -> Result<Enum<'static>, Error> {
+        let mut builder = Enum::builder();
+        builder.set_name(ident(&value.name));
+        builder.set_visibility(visibility(value.visibility));
+        for item in &value.value {
+            let mut item_builder = EnumItem::builder();
+            item_builder.set_name(ident(&item.name));
+            item_builder.set_number(item.number.unwrap_or_default() as i64);
+            for option in self.options(OptionTarget::EnumValue, &item.options)? {
+                item_builder.with_option(option);
+            }
+            builder.with_field(item_builder.build());
+        }
+        let ranges = value.reserved_range.iter().map(|range| {
+            let end = range.end.unwrap_or_default() as i64;
+            (
+                range.start.unwrap_or_default() as i64,
+                range_end(end, i32::MAX as i64),
+            )
+        });
+        for reserved in reserved(ranges, &value.reserved_name) {
+            builder.with_reserved(reserved);
+        }
+        for option in self.options(OptionTarget::Enum, &value.options)? {
+            builder.with_option(option);
+        }
+        Ok(builder.build())
+    }
+
+    fn service(&mut self, service: &ServiceDescriptorProto) -> Result<Service<'static>, Error> {
+        let mut builder = Service::builder();
+        builder.set_name(ident(&service.name));
+        for method in &service.method {
+            builder.with_rpc(self.method(method)?);
+        }
+        for option in self.options(OptionTarget::Service, &service.options)? {
+            builder.with_option(option);
+        }
+        Ok(builder.build())
+    }
+
+    fn method(&mut self, method: &MethodDescriptorProto) -> Result<ServiceRpc<'static>, Error> {
+        let rpc_field = |ty: &std::option::Option<String>, stream: std::option::Option<bool>| {
+            let mut builder = ServiceRpcField::builder();
+            builder.set_value(MessageReference::new(ty.clone().unwrap_or_default()));
+            builder.set_stream(stream == Some(true));
+            builder.build().map_err(|_| Error::UndefinedParsingRoute)
+        };
+        let mut builder = ServiceRpc::builder();
+        builder.set_name(ident(&method.name));
+        builder.set_input(rpc_field(&method.input_type, method.client_streaming)?);
+        builder.set_output(rpc_field(&method.output_type, method.server_streaming)?);
+        for option in self.options(OptionTarget::Method, &method.options)? {
+            builder.with_option(option);
+        }
+        builder.build().map_err(|_| Error::UndefinedParsingRoute)
+    }
+
+    /// Decodes `options`, dropping with a warning those that cannot be named or decoded.
+    fn options(
+        &mut self,
+        target: OptionTarget,
+        options: &std::option::Option<Options>,
+    ) -> Result<Vec<model::Option<'static>>, Error> {
+        let mut decoded = Vec::new();
+        let Some(options) = options else {
+            return Ok(decoded);
+        };
+        for (number, value) in &options.fields {
+            let standard = target
+                .standard_options()
+                .iter()
+                .find(|option| option.number == *number);
+            if let Some(standard) = standard {
+                if standard.kind != OptionKind::Features {
+                    let value = standard_constant(standard.kind, value)?;
+                    decoded.push(option(OptionName::plain(standard.name), value)?);
+                    continue;
+                }
+                for (number, value) in value.as_message::<Options>()?.fields {
+                    match feature_by_number(number) {
+                        Some((name, kind)) => decoded.push(option(
+                            OptionName::plain(format!("features.{name}")),
+                            standard_constant(kind, &value)?,
+                        )?),
+                        None => self.report(
+                            DiagnosticCode::UnknownOption,
+                            format!("unknown feature {number}, dropped"),
+                        ),
+                    }
+                }
+            } else if let Some((full_name, extension)) = self
+                .index
+                .extension_of(target.message_name(), *number as i32)
+            {
+                match self.constant(extension, value) {
+                    Ok(value) => decoded.push(option(
+                        OptionName::extension(true, full_name.to_string()),
+                        value,
+                    )?),
+                    Err(message) => self.report(
+                        DiagnosticCode::InvalidOptionValue,
+                        format!("invalid value for option `.{full_name}`: {message}, dropped"),
+                    ),
+                }
+            } else {
+                self.report(
+                    DiagnosticCode::UnknownOption,
+                    format!(
+                        "unknown option {number} of `{}`, dropped",
+                        target.message_name()
+                    ),
+                );
+            }
+        }
+        Ok(decoded)
+    }
+
+    /// Decodes `value` as the field `field`, declared by a custom option or one of its messages.
+    fn constant(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &WireValue,
+    ) -> Result<Constant<'static>, String> {
+        let type_name = field
+            .type_name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches('.');
+        let mismatch = || format!("unexpected wire type {}", value.wire_type());
+        Ok(match (field.r#type, value) {
+            (Some(FieldType::Bool), WireValue::Varint(value)) => Constant::Bool(*value != 0),
+            (
+                Some(FieldType::Int32 | FieldType::Int64 | FieldType::UInt32),
+                WireValue::Varint(value),
+            ) => Constant::Int(*value as i64),
+            (Some(FieldType::UInt64), WireValue::Varint(value)) => Constant::Int(*value as i64),
+            (Some(FieldType::SInt32 | FieldType::SInt64), WireValue::Varint(value)) => {
+                Constant::Int(unzigzag(*value))
+            }
+            (Some(FieldType::Enum), WireValue::Varint(value)) => {
+                let number = *value as i32;
+                self.index
+                    .enums
+                    .get(type_name)
+                    .and_then(|values| values.iter().find(|item| item.number == Some(number)))
+                    .map_or(Constant::Int(number as i64), |item| {
+                        Constant::Ident(ident(&item.name))
+                    })
+            }
+            (Some(FieldType::Fixed32), WireValue::Fixed32(value)) => Constant::Int(*value as i64),
+            (Some(FieldType::SFixed32), WireValue::Fixed32(value)) => {
+                Constant::Int(*value as i32 as i64)
+            }
+            (Some(FieldType::Float), WireValue::Fixed32(value)) => {
+                Constant::Float(f32::from_bits(*value) as f64)
+            }
+            (Some(FieldType::Fixed64 | FieldType::SFixed64), WireValue::Fixed64(value)) => {
+                Constant::Int(*value as i64)
+            }
+            (Some(FieldType::Double), WireValue::Fixed64(value)) => {
+                Constant::Float(f64::from_bits(*value))
+            }
+            (Some(FieldType::String | FieldType::Bytes), WireValue::Bytes(value)) => {
+                Constant::String(String::from_utf8_lossy(value).into_owned().into())
+            }
+            (Some(FieldType::Message), WireValue::Bytes(value)) => {
+                Constant::Aggregate(self.aggregate(type_name, value)?)
+            }
+            (None, _) => return Err(format!("unresolved type `{type_name}`")),
+            _ => return Err(mismatch()),
+        })
+    }
+
+    fn aggregate(&self, type_name: &str, bytes: &[u8]) -> Result<Aggregate<'static>, String> {
+        let fields = self
+            .index
+            .messages
+            .get(type_name)
+            .ok_or_else(|| format!("unknown message `{type_name}`"))?;
+        let mut builder = Aggregate::builder();
+        for field in Reader::new(bytes) {
+            let (number, value) = field.map_err(|error| error.to_string())?;
+            let number = number as i32;
+            let (name, field) = match fields.iter().find(|field| field.number == Some(number)) {
+                Some(field) => (AggregateFieldName::Field(ident(&field.name)), field),
+                None => match self.index.extension_of(type_name, number) {
+                    Some((full_name, field)) => (
+                        AggregateFieldName::Extension(Ident::new(false, full_name.to_string())),
+                        field,
+                    ),
+                    None => return Err(format!("`{type_name}` has no field {number}")),
+                },
+            };
+            let mut field_builder = AggregateField::builder();
+            field_builder.set_name(name);
+            field_builder.set_value(AggregateValue::Constant(self.constant(field, &value)?));
+            builder.with_field(field_builder.build().map_err(|error| error.to_string())?);
+        }
+        Ok(builder.build())
+    }
+
+    fn report(&mut self, code: DiagnosticCode, message: String) {
+        let diagnostic = Diagnostic::warning(code, message, Span::default());
+        self.diagnostics.push(match self.file {
+            "" => diagnostic,
+            file => diagnostic.with_file(file),
+        });
+    }
+}
+
+fn name(name: &std::option::Option<String>) -> &str {
+    name.as_deref().unwrap_or_default()
+}
+
+fn ident(name: &std::option::Option<String>) -> Ident<'static> {
+    Ident::new(false, name.clone().unwrap_or_default())
+}
+
+fn option(
+    name: OptionName<'static>,
+    value: Constant<'static>,
+) -> Result<model::Option<'static>, Error> {
+    let mut builder = model::Option::builder();
+    builder.set_name(name);
+    builder.set_value(value);
+    Ok(builder.build()?)
+}
+
+fn visibility(visibility: std::option::Option<SymbolVisibility>) -> Visibility {
+    match visibility {
+        None => Visibility::Default,
+        Some(SymbolVisibility::Local) => Visibility::Local,
+        Some(SymbolVisibility::Export) => Visibility::Export,
+    }
+}
+
+/// Whether the oneof `index` was synthesized for a proto3 `optional` field.
+fn is_synthetic(message: &DescriptorProto, index: i32) -> bool {
+    let mut fields = message
+        .field
+        .iter()
+        .filter(|field| field.oneof_index == Some(index));
+    fields
+        .next()
+        .is_some_and(|field| field.proto3_optional == Some(true))
+        && fields.next().is_none()
+}
+
+fn is_map_entry(message: &DescriptorProto) -> bool {
+    let number = OptionTarget::Message
+        .standard_option("map_entry")
+        .map_or(7, |option| option.number);
+    message.options.as_ref().is_some_and(|options| {
+        options
+            .get(number)
+            .any(|value| matches!(value.as_bool(), Ok(true)))
+    })
+}
+
+/// Inclusive end of a range, written `max` when it reaches `max`.
+fn range_end(end: i64, max: i64) -> i64 {
+    if end >= max { i64::MAX } else { end }
+}
+
+fn reserved(
+    ranges: impl Iterator<Item = (i64, i64)>,
+    names: &[String],
+) -> Vec<ReservedItems<'static>> {
+    let mut reserved = Vec::new();
+    let mut range_items = ReservedItems::builder();
+    range_items.set_items(
+        ranges
+            .map(|(start, end)| ReservedData::Range(start, end))
+            .collect(),
+    );
+    let range_items = range_items.build();
+    if !range_items.items().is_empty() {
+        reserved.push(range_items);
+    }
+    if !names.is_empty() {
+        let mut name_items = ReservedItems::builder();
+        name_items.set_items(
+            names
+                .iter()
+                .map(|name| ReservedData::Field(Ident::new(false, name.clone())))
+                .collect(),
+        );
+        reserved.push(name_items.build());
+    }
+    reserved
+}
+
+fn ty(field: &FieldDescriptorProto) -> Result<Type<'static>, Error> {
+    Ok(match field.r#type {
+        Some(FieldType::Double) => Type::Double,
+        Some(FieldType::Float) => Type::Float,
+        Some(FieldType::Int32) => Type::Int32,
+        Some(FieldType::Int64) => Type::Int64,
+        Some(FieldType::UInt32) => Type::UInt32,
+        Some(FieldType::UInt64) => Type::UInt64,
+        Some(FieldType::SInt32) => Type::SInt32,
+        Some(FieldType::SInt64) => Type::SInt64,
+        Some(FieldType::Fixed32) => Type::Fixed32,
+        Some(FieldType::Fixed64) => Type::Fixed64,
+        Some(FieldType::SFixed32) => Type::SFixed32,
+        Some(FieldType::SFixed64) => Type::SFixed64,
+        Some(FieldType::Bool) => Type::Bool,
+        Some(FieldType::String) => Type::String,
+        Some(FieldType::Bytes) => Type::Bytes,
+        Some(FieldType::Message | FieldType::Enum | FieldType::Group) | None => {
+            match &field.type_name {
+                Some(type_name) => Type::Reference(type_name.clone().into()),
+                None => {
+                    return Err(Error::DecodingDescriptor(format!(
+                        "field `{}` has no type",
+                        name(&field.name)
+                    )));
+                }
+            }
+        }
+    })
+}
+
+fn key_type(field: &FieldDescriptorProto) -> Result<MapFieldKeyType, Error> {
+    Ok(match field.r#type {
+        Some(FieldType::Int32) => MapFieldKeyType::Int32,
+        Some(FieldType::Int64) => MapFieldKeyType::Int64,
+        Some(FieldType::UInt32) => MapFieldKeyType::UInt32,
+        Some(FieldType::UInt64) => MapFieldKeyType::UInt64,
+        Some(FieldType::SInt32) => MapFieldKeyType::SInt32,
+        Some(FieldType::SInt64) => MapFieldKeyType::SInt64,
+        Some(FieldType::Fixed32) => MapFieldKeyType::Fixed32,
+        Some(FieldType::Fixed64) => MapFieldKeyType::Fixed64,
+        Some(FieldType::SFixed32) => MapFieldKeyType::SFixed32,
+        Some(FieldType::SFixed64) => MapFieldKeyType::SFixed64,
+        Some(FieldType::Bool) => MapFieldKeyType::Bool,
+        Some(FieldType::String) => MapFieldKeyType::String,
+        ty => {
+            return Err(Error::DecodingDescriptor(format!(
+                "invalid map key type {ty:?}"
+            )));
+        }
+    })
+}
+
+fn feature_by_number(number: u32) -> std::option::Option<(&'static str, OptionKind)> {
+    FEATURE_SET
+        .iter()
+        .find(|feature| feature.number == number)
+        .map(|feature| (feature.name, feature.kind))
+}
+
+fn standard_constant(kind: OptionKind, value: &WireValue) -> Result<Constant<'static>, Error> {
+    Ok(match kind {
+        OptionKind::Bool => Constant::Bool(value.as_bool()?),
+        OptionKind::String => Constant::String(value.as_string()?.into()),
+        OptionKind::Enum(values) => {
+            let number = value.as_int32()?;
+            match values.iter().find(|(_, value)| *value == number) {
+                Some((name, _)) => Constant::Ident(Ident::new(false, *name)),
+                None => Constant::Int(number as i64),
+            }
+        }
+        OptionKind::Features => {
+            return Err(Error::DecodingDescriptor(
+                "features cannot be nested".to_string(),
+            ));
+        }
+    })
+}
+
+/// The `default` pseudo-option of a field, parsed from its textual `default_value`.
+fn default_value(field: &FieldDescriptorProto) -> std::option::Option<Constant<'static>> {
+    let value = field.default_value.as_deref()?;
+    Some(match field.r#type {
+        Some(FieldType::String) => Constant::String(value.to_string().into()),
+        Some(FieldType::Bytes) => Constant::String(c_unescape(value).into()),
+        Some(FieldType::Bool) => Constant::Bool(value == "true"),
+        Some(FieldType::Float | FieldType::Double) => match value.parse::<f64>() {
+            Ok(number) => Constant::Float(number),
+            Err(_) => Constant::Ident(Ident::new(false, value.to_string())),
+        },
+        Some(FieldType::Enum | FieldType::Message | FieldType::Group) | None => {
+            Constant::Ident(Ident::new(false, value.to_string()))
+        }
+        Some(_) => match value.parse::<i64>() {
+            Ok(number) => Constant::Int(number),
+            Err(_) => Constant::Int(value.parse::<u64>().ok()? as i64),
+        },
+    })
+}
+
+/// Reverses the C escaping protoc applies to `bytes` default values.
+fn c_unescape(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut chars = value.bytes().peekable();
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        match chars.next() {
+            Some(b'n') => bytes.push(b'\n'),
+            Some(b'r') => bytes.push(b'\r'),
+            Some(b't') => bytes.push(b'\t'),
+            Some(digit @ b'0'..=b'7') => {
+                let mut number = (digit - b'0') as u32;
+                for _ in 0..2 {
+                    match chars.peek() {
+                        Some(digit @ b'0'..=b'7') => {
+                            number = number * 8 + (digit - b'0') as u32;
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                bytes.push(number as u8);
+            }
+            Some(other) => bytes.push(other),
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,940 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use super::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, ExtensionRange,
+    FieldDescriptorProto, FieldType, FileDescriptorProto, FileDescriptorSet, Label,
+    MethodDescriptorProto, OneofDescriptorProto, OptionKind, OptionTarget, Options, ReservedRange,
+    ServiceDescriptorProto, SymbolVisibility, WireValue, bool_constant, edition_number,
+    enum_constant, feature, index::Index, string_constant, zigzag,
+};
+use crate::{
+    Diagnostic, DiagnosticCode, Linked, Linker, MAX_FIELD_NUMBER, Resolution, Symbol, SymbolKind,
+    descriptor::Encode,
+    join,
+    model::{
+        Aggregate, AggregateField, AggregateFieldName, AggregateValue, Constant, Enum, Extend,
+        Extensions, Field, GroupField, Ident, MapField, MapFieldKeyType, Message, Option,
+        OptionNamePart, Proto, ReservedData, ReservedItems, Service, Span, Type, Visibility,
+    },
+    parent,
+};
+
+/// Converts a set of files into descriptor protos. Type names are resolved with a [`Linker`], and
+/// custom options are resolved against the extensions declared by the files, so files declaring
+/// options must be part of the set.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorEncoder<'p, 'a> {
+    files: Vec<(&'p str, &'p Proto<'a>)>,
+    imports: HashMap<&'p str, Vec<&'p str>>,
+}
+impl<'p, 'a> DescriptorEncoder<'p, 'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file, named by its import path. Files should come after the files they import.
+    pub fn with_file(mut self, file: &'p str, proto: &'p Proto<'a>) -> Self {
+        self.files.push((file, proto));
+        self
+    }
+
+    /// See [`Linker::with_imports`].
+    pub fn with_imports(mut self, file: &'p str, imports: Vec<&'p str>) -> Self {
+        self.imports.insert(file, imports);
+        self
+    }
+
+    /// Encodes every file, along with the diagnostics of linking them and of resolving their
+    /// options.
+    pub fn encode(&self) -> (FileDescriptorSet, Vec<Diagnostic>) {
+        let mut linker = Linker::new();
+        for (file, proto) in &self.files {
+            linker = linker.with_file(file, proto);
+        }
+        for (file, imports) in &self.imports {
+            linker = linker.with_imports(file, imports.clone());
+        }
+        let linked = linker.link();
+
+        // Options are encoded once every declaration is known, so that custom options can be
+        // resolved regardless of where their extension is declared.
+        let mut index = Index::default();
+        for (file, proto) in &self.files {
+            let mut encoder = FileEncoder::new(&linked, None, file, proto);
+            index.add_file(&encoder.file(proto));
+        }
+
+        let mut diagnostics = linked.diagnostics().clone();
+        let mut set = FileDescriptorSet::default();
+        for (file, proto) in &self.files {
+            let mut encoder = FileEncoder::new(&linked, Some(&index), file, proto);
+            set.file.push(encoder.file(proto));
+            diagnostics.append(&mut encoder.diagnostics);
+        }
+        (set, diagnostics)
+    }
+}
+
+/// Encodes a single file named `file`.
+pub fn encode_file(file: &str, proto: &Proto<'_>) -> (FileDescriptorProto, Vec<Diagnostic>) {
+    let (set, diagnostics) = DescriptorEncoder::new().with_file(file, proto).encode();
+    (set.file.into_iter().next().unwrap_or_default(), diagnostics)
+}
+
+/// The JSON name protoc derives from a field name: underscores are removed and the letter
+/// following each one is capitalized.
+pub fn json_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut capitalize = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            result.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Name of the message synthesized for the map field `name`, such as `TagsEntry` for `tags`.
+pub fn map_entry_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 5);
+    let mut capitalize = true;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize = true;
+        } else if capitalize {
+            result.extend(c.to_uppercase());
+            capitalize = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result.push_str("Entry");
+    result
+}
+
+struct FileEncoder<'e> {
+    linked: &'e Linked,
+    /// Unset while collecting declarations, in which case options are skipped.
+    index: std::option::Option<&'e Index>,
+    file: &'e str,
+    proto3: bool,
+    diagnostics: Vec<Diagnostic>,
+}
+impl<'e> FileEncoder<'e> {
+    fn new(
+        linked: &'e Linked,
+        index: std::option::Option<&'e Index>,
+        file: &'e str,
+        proto: &Proto<'_>,
+    ) -> Self {
+        Self {
+            linked,
+            index,
+            file,
+            proto3: !*proto.syntax().edition() && proto.syntax().value() == "proto3",
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn file(&mut self, proto: &Proto<'_>) -> FileDescriptorProto {
+        let package = proto.package().value();
+        let mut descriptor = FileDescriptorProto {
+            name: (!self.file.is_empty()).then(|| self.file.to_string()),
+            package: (!package.is_empty()).then(|| package.to_string()),
+            ..Default::default()
+        };
+        if *proto.syntax().edition() {
+            descriptor.syntax = Some("editions".to_string());
+            descriptor.edition = edition_number(proto.syntax().value());
+        } else if self.proto3 {
+            descriptor.syntax = Some("proto3".to_string());
+        }
+        for import in proto.imports().iter() {
+            if *import.option() {
+                descriptor
+                    .option_dependency
+                    .push(import.value().to_string());
+                continue;
+            }
+            let index = descriptor.dependency.len() as i32;
+            if *import.public() {
+                descriptor.public_dependency.push(index);
+            }
+            if *import.weak() {
+                descriptor.weak_dependency.push(index);
+            }
+            descriptor.dependency.push(import.value().to_string());
+        }
+        for message in proto.messages().iter() {
+            descriptor.message_type.push(self.message(package, message));
+        }
+        for value in proto.enums().iter() {
+            descriptor.enum_type.push(self.enumeration(package, value));
+        }
+        for service in proto.services().iter() {
+            descriptor.service.push(self.service(package, service));
+        }
+        for extend in proto.extends().iter() {
+            self.extend(
+                package,
+                extend,
+                &mut descriptor.extension,
+                &mut descriptor.message_type,
+            );
+        }
+        descriptor.options = self.options(OptionTarget::File, package, proto.options());
+        descriptor
+    }
+
+    fn message(&mut self, scope: &str, message: &Message<'_>) -> DescriptorProto {
+        let full_name = join(scope, message.name().value());
+        let mut descriptor = DescriptorProto {
+            name: Some(message.name().value().to_string()),
+            visibility: visibility(*message.visibility()),
+            ..Default::default()
+        };
+        // Fields declared `optional` in proto3, each getting a synthetic oneof after the real ones.
+        let mut synthetic = Vec::new();
+        for field in message.fields().iter() {
+            match field {
+                Field::Normal(field) => {
+                    let mut field_descriptor = self.field(
+                        &full_name,
+                        field.name(),
+                        (*field.number(), *field.number_span()),
+                        self.field_type(&full_name, field.ty()),
+                        field.options(),
+                    );
+                    field_descriptor.label = Some(label(*field.repeated(), *field.required()));
+                    if self.proto3 && *field.optional() {
+                        field_descriptor.proto3_optional = Some(true);
+                        synthetic.push(descriptor.field.len());
+                    }
+                    descriptor.field.push(field_descriptor);
+                }
+                Field::OneOf(oneof) => {
+                    let index = descriptor.oneof_decl.len() as i32;
+                    descriptor.oneof_decl.push(OneofDescriptorProto {
+                        name: Some(oneof.name().value().to_string()),
+                        options: self.options(OptionTarget::Oneof, &full_name, oneof.options()),
+                    });
+                    for item in oneof.fields().iter() {
+                        let mut field_descriptor = self.field(
+                            &full_name,
+                            item.name(),
+                            (*item.number(), *item.number_span()),
+                            self.field_type(&full_name, item.ty()),
+                            item.options(),
+                        );
+                        field_descriptor.label = Some(Label::Optional);
+                        field_descriptor.oneof_index = Some(index);
+                        descriptor.field.push(field_descriptor);
+                    }
+                }
+                Field::Map(field) => {
+                    let entry = self.map_entry(&full_name, field);
+                    let type_name = join(&full_name, entry.name.as_deref().unwrap_or_default());
+                    let mut field_descriptor = self.field(
+                        &full_name,
+                        field.name(),
+                        (*field.number(), *field.number_span()),
+                        (Some(FieldType::Message), Some(format!(".{type_name}"))),
+                        field.options(),
+                    );
+                    field_descriptor.label = Some(Label::Repeated);
+                    descriptor.field.push(field_descriptor);
+                    descriptor.nested_type.push(entry);
+                }
+                Field::Group(group) => {
+                    let field_descriptor = self.group(&full_name, group);
+                    descriptor.field.push(field_descriptor);
+                    descriptor
+                        .nested_type
+                        .push(self.message(&full_name, group.message()));
+                }
+            }
+        }
+        for index in synthetic {
+            let field = &mut descriptor.field[index];
+            field.oneof_index = Some(descriptor.oneof_decl.len() as i32);
+            descriptor.oneof_decl.push(OneofDescriptorProto {
+                name: Some(format!("_{}", field.name.as_deref().unwrap_or_default())),
+                options: None,
+            });
+        }
+
+        for nested in message.messages().iter() {
+            descriptor
+                .nested_type
+                .push(self.message(&full_name, nested));
+        }
+        for value in message.enums().iter() {
+            descriptor
+                .enum_type
+                .push(self.enumeration(&full_name, value));
+        }
+        for extend in message.extends().iter() {
+            self.extend(
+                &full_name,
+                extend,
+                &mut descriptor.extension,
+                &mut descriptor.nested_type,
+            );
+        }
+        for extensions in message.extensions().iter() {
+            descriptor
+                .extension_range
+                .extend(self.extension_ranges(&full_name, extensions));
+        }
+        let (ranges, names) = self.reserved(message.reserved(), MAX_FIELD_NUMBER as i64, 1);
+        descriptor.reserved_range = ranges;
+        descriptor.reserved_name = names;
+        descriptor.options = self.options(OptionTarget::Message, &full_name, message.options());
+        descriptor
+    }
+
+    /// A field without its label, which depends on where it is declared.
+    fn field(
+        &mut self,
+        scope: &str,
+        name: &Ident<'_>,
+        (number, number_span): (u64, Span),
+        (r#type, type_name): (std::option::Option<FieldType>, std::option::Option<String>),
+        options: &[Option<'_>],
+    ) -> FieldDescriptorProto {
+        let option = |name: &str| {
+            options
+                .iter()
+                .find(|option| option.name().is(name))
+                .map(|option| option.value())
+        };
+        let json = match option("json_name") {
+            Some(Constant::String(value)) => value.to_string(),
+            _ => json_name(name.value()),
+        };
+        FieldDescriptorProto {
+            name: Some(name.value().to_string()),
+            number: self.number(number, number_span),
+            r#type,
+            type_name,
+            default_value: option("default").and_then(|value| default_value(value, r#type)),
+            json_name: Some(json),
+            options: self.options(OptionTarget::Field, scope, options),
+            ..Default::default()
+        }
+    }
+
+    /// Declaration named `name` in `scope`, as the linker resolves it.
+    fn resolve(&self, scope: &str, name: &str) -> std::option::Option<&'e Symbol> {
+        match self.linked.symbols().resolve(scope, name) {
+            Resolution::Resolved(symbol) => Some(symbol),
+            _ => None,
+        }
+    }
+
+    fn field_type(
+        &self,
+        scope: &str,
+        ty: &Type<'_>,
+    ) -> (std::option::Option<FieldType>, std::option::Option<String>) {
+        let scalar = match ty {
+            Type::Double => FieldType::Double,
+            Type::Float => FieldType::Float,
+            Type::Int32 => FieldType::Int32,
+            Type::Int64 => FieldType::Int64,
+            Type::UInt32 => FieldType::UInt32,
+            Type::UInt64 => FieldType::UInt64,
+            Type::SInt32 => FieldType::SInt32,
+            Type::SInt64 => FieldType::SInt64,
+            Type::Fixed32 => FieldType::Fixed32,
+            Type::Fixed64 => FieldType::Fixed64,
+            Type::SFixed32 => FieldType::SFixed32,
+            Type::SFixed64 => FieldType::SFixed64,
+            Type::Bool => FieldType::Bool,
+            Type::String => FieldType::String,
+            Type::Bytes => FieldType::Bytes,
+            Type::Reference(name) => {
+                return match self.resolve(scope, name) {
+                    Some(symbol) => (
+                        match symbol.kind() {
+                            SymbolKind::Enum => Some(FieldType::Enum),
+                            _ => Some(FieldType::Message),
+                        },
+                        Some(format!(".{}", symbol.full_name())),
+                    ),
+                    None => (None, Some(name.to_string())),
+                };
+            }
+        };
+        (Some(scalar), None)
+    }
+
+    fn group(&mut self, scope: &str, group: &GroupField<'_>) -> FieldDescriptorProto {
+        let name = group.name().value();
+        FieldDescriptorProto {
+            name: Some(name.to_lowercase()),
+            number: self.number(*group.number(), *group.number_span()),
+            label: Some(label(*group.repeated(), *group.required())),
+            r#type: Some(FieldType::Group),
+            type_name: Some(format!(".{}", join(scope, name))),
+            json_name: Some(json_name(&name.to_lowercase())),
+            options: self.options(OptionTarget::Field, scope, group.options()),
+            ..Default::default()
+        }
+    }
+
+    fn map_entry(&self, scope: &str, field: &MapField<'_>) -> DescriptorProto {
+        let key = FieldDescriptorProto {
+            name: Some("key".to_string()),
+            number: Some(1),
+            label: Some(Label::Optional),
+            r#type: Some(key_type(*field.key_ty())),
+            json_name: Some("key".to_string()),
+            ..Default::default()
+        };
+        let (r#type, type_name) = self.field_type(scope, field.value_ty());
+        let value = FieldDescriptorProto {
+            name: Some("value".to_string()),
+            number: Some(2),
+            label: Some(Label::Optional),
+            r#type,
+            type_name,
+            json_name: Some("value".to_string()),
+            ..Default::default()
+        };
+        let mut options = Options::default();
+        options.push(
+            OptionTarget::Message
+                .standard_option("map_entry")
+                .map_or(7, |option| option.number),
+            WireValue::Varint(1),
+        );
+        DescriptorProto {
+            name: Some(map_entry_name(field.name().value())),
+            field: vec![key, value],
+            options: Some(options),
+            ..Default::default()
+        }
+    }
+
+    fn extend(
+        &mut self,
+        scope: &str,
+        extend: &Extend<'_>,
+        extensions: &mut Vec<FieldDescriptorProto>,
+        messages: &mut Vec<DescriptorProto>,
+    ) {
+        let extendee = match self.resolve(scope, extend.extendee().value()) {
+            Some(symbol) => format!(".{}", symbol.full_name()),
+            None => extend.extendee().value().to_string(),
+        };
+        for field in extend.fields().iter() {
+            let mut descriptor = match field {
+                Field::Normal(field) => {
+                    let mut descriptor = self.field(
+                        scope,
+                        field.name(),
+                        (*field.number(), *field.number_span()),
+                        self.field_type(scope, field.ty()),
+                        field.options(),
+                    );
+                    descriptor.label = Some(label(*field.repeated(), *field.required()));
+                    if self.proto3 && *field.optional() {
+                        descriptor.proto3_optional = Some(true);
+                    }
+                    descriptor
+                }
+                Field::Group(group) => {
+                    messages.push(self.message(scope, group.message()));
+                    self.group(scope, group)
+                }
+                Field::OneOf(_) | Field::Map(_) => continue,
+            };
+            descriptor.extendee = Some(extendee.clone());
+            extensions.push(descriptor);
+        }
+    }
+
+    fn extension_ranges(
+        &mut self,
+        scope: &str,
+        extensions: &Extensions<'_>,
+    ) -> Vec<ExtensionRange> {
+        let options = self.options(OptionTarget::ExtensionRange, scope, extensions.options());
+        extensions
+            .ranges()
+            .iter()
+            .map(|range| ExtensionRange {
+                start: self.number(*range.start(), *range.span()),
+                end: self.number(
+                    range
+                        .end()
+                        .min(&(MAX_FIELD_NUMBER as i64))
+                        .saturating_add(1),
+                    *range.span(),
+                ),
+                options: options.clone(),
+            })
+            .collect()
+    }
+
+    fn enumeration(&mut self, scope: &str, value: &Enum<'_>) -> EnumDescriptorProto {
+        let full_name = join(scope, value.name().value());
+        let values = value
+            .fields()
+            .iter()
+            .map(|item| EnumValueDescriptorProto {
+                name: Some(item.name().value().to_string()),
+                number: self.number(*item.number(), *item.number_span()),
+                options: self.options(OptionTarget::EnumValue, &full_name, item.options()),
+            })
+            .collect();
+        let (reserved_range, reserved_name) = self.reserved(value.reserved(), i32::MAX as i64, 0);
+        EnumDescriptorProto {
+            name: Some(value.name().value().to_string()),
+            value: values,
+            options: self.options(OptionTarget::Enum, &full_name, value.options()),
+            reserved_range,
+            reserved_name,
+            visibility: visibility(*value.visibility()),
+        }
+    }
+
+    fn service(&mut self, scope: &str, service: &Service<'_>) -> ServiceDescriptorProto {
+        let full_name = join(scope, service.name().value());
+        let method = service
+            .rpcs()
+            .iter()
+            .map(|rpc| {
+                let [input_type, output_type] = [rpc.input(), rpc.output()].map(|field| {
+                    let value = field.value();
+                    Some(match self.resolve(scope, value.value()) {
+                        Some(symbol) => format!(".{}", symbol.full_name()),
+                        None => value.value().to_string(),
+                    })
+                });
+                MethodDescriptorProto {
+                    name: Some(rpc.name().value().to_string()),
+                    input_type,
+                    output_type,
+                    options: self.options(OptionTarget::Method, &full_name, rpc.options()),
+                    client_streaming: rpc.input().stream().then_some(true),
+                    server_streaming: rpc.output().stream().then_some(true),
+                }
+            })
+            .collect();
+        ServiceDescriptorProto {
+            name: Some(service.name().value().to_string()),
+            method,
+            options: self.options(OptionTarget::Service, &full_name, service.options()),
+        }
+    }
+
+    /// Encodes `options`, reporting those that cannot be resolved or whose value does not fit.
+    fn options(
+        &mut self,
+        target: OptionTarget,
+        scope: &str,
+        options: &[Option<'_>],
+    ) -> std::option::Option<Options> {
+        let index = self.index?;
+        let mut encoded = Options::default();
+        let mut features = Options::default();
+        for option in options {
+            let name = option.name();
+            if target == OptionTarget::Field && (name.is("default") || name.is("json_name")) {
+                continue;
+            }
+            let result = if let Some(name) = option.feature() {
+                match feature(name) {
+                    Some(feature) => encode_standard(feature.kind, option.value())
+                        .map(|value| features.push(feature.number, value)),
+                    None => {
+                        self.report_unknown(option, format!("unknown feature `{name}`"));
+                        continue;
+                    }
+                }
+            } else if let [OptionNamePart::Ident(standard)] = &name.parts()[..]
+                && let Some(standard) = target.standard_option(standard.value())
+            {
+                encode_standard(standard.kind, option.value())
+                    .map(|value| encoded.push(standard.number, value))
+            } else if let Some((OptionNamePart::Extension(extension), path)) =
+                name.parts().split_first()
+                && let Some(extension) = index.extension(scope, extension)
+            {
+                let extendee = extension.extendee.as_deref().unwrap_or_default();
+                if extendee.trim_start_matches('.') != target.message_name() {
+                    self.report_unknown(
+                        option,
+                        format!(
+                            "option `{name}` extends `{extendee}`, not `{}`",
+                            target.message_name()
+                        ),
+                    );
+                    continue;
+                }
+                index
+                    .value(extension, &nested(path, option.value()))
+                    .map(|value| encoded.push(extension.number.unwrap_or_default() as u32, value))
+            } else {
+                self.report_unknown(
+                    option,
+                    format!("unknown option `{name}` for `{}`", target.message_name()),
+                );
+                continue;
+            };
+            if let Err(message) = result {
+                self.report(Diagnostic::warning(
+                    DiagnosticCode::InvalidOptionValue,
+                    format!("invalid value for option `{name}`: {message}"),
+                    *option.value_span(),
+                ));
+            }
+        }
+        if !features.is_empty() {
+            let number = target
+                .standard_option("features")
+                .map_or(50, |option| option.number);
+            encoded.push(number, WireValue::Bytes(features.encode_to_vec()));
+        }
+        (!encoded.is_empty()).then_some(encoded)
+    }
+
+    /// Reserved ranges, with `end` offset by `exclusive` and `max` clamped to `max`, and reserved
+    /// names.
+    fn reserved(
+        &mut self,
+        reserved: &[ReservedItems<'_>],
+        max: i64,
+        exclusive: i64,
+    ) -> (Vec<ReservedRange>, Vec<String>) {
+        let mut ranges = Vec::new();
+        let mut names = Vec::new();
+        for items in reserved {
+            for data in items.items().iter() {
+                match data {
+                    ReservedData::Range(start, end) => ranges.push(ReservedRange {
+                        start: self.number(*start, *items.span()),
+                        end: self.number((*end).min(max) + exclusive, *items.span()),
+                    }),
+                    ReservedData::Field(name) => names.push(name.value().to_string()),
+                }
+            }
+        }
+        (ranges, names)
+    }
+
+    /// `number` as the 32-bit number of descriptors, reported at `span` when it does not fit.
+    fn number<N>(&mut self, number: N, span: Span) -> std::option::Option<i32>
+    where
+        N: Copy + std::fmt::Display + TryInto<i32>,
+    {
+        let converted = number.try_into().ok();
+        if converted.is_none() {
+            self.report(Diagnostic::error(
+                DiagnosticCode::FieldNumberOutOfRange,
+                format!("number {number} does not fit in 32 bits"),
+                span,
+            ));
+        }
+        converted
+    }
+
+    fn report_unknown(&mut self, option: &Option<'_>, message: String) {
+        self.report(Diagnostic::warning(
+            DiagnosticCode::UnknownOption,
+            message,
+            *option.name().span(),
+        ));
+    }
+
+    fn report(&mut self, diagnostic: Diagnostic) {
+        let diagnostic = match self.file {
+            "" => diagnostic,
+            file => diagnostic.with_file(file),
+        };
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+impl Index {
+    /// Extension named `name` as written in `scope`, searched from the innermost scope outwards.
+    fn extension(
+        &self,
+        scope: &str,
+        name: &Ident<'_>,
+    ) -> std::option::Option<&FieldDescriptorProto> {
+        if name.relative() {
+            return self.extensions.get(name.value());
+        }
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            if let Some(extension) = self.extensions.get(&join(current, name.value())) {
+                return Some(extension);
+            }
+            scope = parent(current);
+        }
+        None
+    }
+
+    /// Encodes `value` as the field `field`.
+    fn value(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &Constant<'_>,
+    ) -> Result<WireValue, String> {
+        let Some(ty) = field.r#type else {
+            return Err("the type of the option is unresolved".to_string());
+        };
+        let type_name = field
+            .type_name
+            .as_deref()
+            .unwrap_or_default()
+            .trim_start_matches('.');
+        Ok(match ty {
+            FieldType::Bool => WireValue::Varint(bool_constant(value)? as u64),
+            FieldType::Int32 => WireValue::Varint(i64::from(int32(value)?) as u64),
+            FieldType::Int64 => WireValue::Varint(integer(value)? as u64),
+            FieldType::UInt32 => WireValue::Varint(u64::from(uint32(value)?)),
+            FieldType::UInt64 => match integer(value)? {
+                value if value < 0 => return Err(format!("{value} is negative")),
+                value => WireValue::Varint(value as u64),
+            },
+            FieldType::SInt32 => WireValue::Varint(zigzag(int32(value)?.into())),
+            FieldType::SInt64 => WireValue::Varint(zigzag(integer(value)?)),
+            FieldType::Fixed32 => WireValue::Fixed32(uint32(value)?),
+            FieldType::SFixed32 => WireValue::Fixed32(int32(value)?.cast_unsigned()),
+            FieldType::Fixed64 | FieldType::SFixed64 => WireValue::Fixed64(integer(value)? as u64),
+            FieldType::Float => WireValue::Fixed32((float(value)? as f32).to_bits()),
+            FieldType::Double => WireValue::Fixed64(float(value)?.to_bits()),
+            FieldType::String | FieldType::Bytes => {
+                WireValue::Bytes(string_constant(value)?.as_bytes().to_vec())
+            }
+            FieldType::Enum => match value {
+                Constant::Ident(ident) => {
+                    let number = self
+                        .enums
+                        .get(type_name)
+                        .and_then(|values| {
+                            values
+                                .iter()
+                                .find(|item| item.name.as_deref() == Some(ident.value()))
+                        })
+                        .and_then(|item| item.number)
+                        .ok_or_else(|| format!("`{ident}` is not a value of `{type_name}`"))?;
+                    WireValue::Varint(number as i64 as u64)
+                }
+                value => WireValue::Varint(integer(value)? as u64),
+            },
+            FieldType::Message => match value {
+                Constant::Aggregate(aggregate) => {
+                    WireValue::Bytes(self.aggregate(type_name, aggregate)?.encode_to_vec())
+                }
+                _ => return Err(format!("expected a `{type_name}` message literal")),
+            },
+            FieldType::Group => return Err("groups are not supported as options".to_string()),
+        })
+    }
+
+    fn aggregate(&self, type_name: &str, aggregate: &Aggregate<'_>) -> Result<Options, String> {
+        let fields = self
+            .messages
+            .get(type_name)
+            .ok_or_else(|| format!("unknown message `{type_name}`"))?;
+        let mut encoded = Options::default();
+        for field in aggregate.fields().iter() {
+            let descriptor = match field.name() {
+                AggregateFieldName::Field(name) => fields.iter().find(|field| {
+                    field.name.as_deref() == Some(name.value())
+                        || (field.r#type == Some(FieldType::Group)
+                            && field.name.as_deref() == Some(&name.value().to_lowercase()))
+                }),
+                AggregateFieldName::Extension(name) => {
+                    self.extensions.get(name.value().trim_start_matches('.'))
+                }
+            }
+            .ok_or_else(|| {
+                let (AggregateFieldName::Field(name) | AggregateFieldName::Extension(name)) =
+                    field.name();
+                format!("`{type_name}` has no field `{name}`")
+            })?;
+            self.aggregate_value(descriptor, field.value(), &mut encoded)?;
+        }
+        Ok(encoded)
+    }
+
+    fn aggregate_value(
+        &self,
+        field: &FieldDescriptorProto,
+        value: &AggregateValue<'_>,
+        encoded: &mut Options,
+    ) -> Result<(), String> {
+        match value {
+            AggregateValue::Constant(value) => encoded.push(
+                field.number.unwrap_or_default() as u32,
+                self.value(field, value)?,
+            ),
+            AggregateValue::List(values) => {
+                for value in values.iter() {
+                    self.aggregate_value(field, value, encoded)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value set on an extension by an option such as `(ext).a.b = value`: the message literal
+/// `{ a { b: value } }`, or `value` itself when the name has no `path` after the extension.
+fn nested<'a>(path: &[OptionNamePart<'a>], value: &Constant<'a>) -> Constant<'a> {
+    path.iter().rev().fold(value.clone(), |value, part| {
+        let name = match part {
+            OptionNamePart::Ident(ident) => AggregateFieldName::Field(ident.clone()),
+            OptionNamePart::Extension(ident) => AggregateFieldName::Extension(ident.clone()),
+        };
+        Constant::Aggregate(Aggregate {
+            fields: Cow::Owned(vec![AggregateField {
+                name,
+                value: AggregateValue::Constant(value),
+                span: Span::default(),
+            }]),
+            span: Span::default(),
+        })
+    })
+}
+
+fn encode_standard(kind: OptionKind, value: &Constant<'_>) -> Result<WireValue, String> {
+    Ok(match kind {
+        OptionKind::Bool => WireValue::Varint(bool_constant(value)? as u64),
+        OptionKind::String => WireValue::Bytes(string_constant(value)?.as_bytes().to_vec()),
+        OptionKind::Enum(values) => {
+            let (_, number) = enum_constant(values, value)?;
+            WireValue::Varint(i64::from(number) as u64)
+        }
+        OptionKind::Features => match value {
+            Constant::Aggregate(aggregate) => {
+                let mut features = Options::default();
+                for field in aggregate.fields().iter() {
+                    let (AggregateFieldName::Field(name) | AggregateFieldName::Extension(name)) =
+                        field.name();
+                    let feature =
+                        feature(name.value()).ok_or_else(|| format!("unknown feature `{name}`"))?;
+                    let AggregateValue::Constant(value) = field.value() else {
+                        return Err(format!("feature `{name}` is not repeated"));
+                    };
+                    features.push(feature.number, encode_standard(feature.kind, value)?);
+                }
+                WireValue::Bytes(features.encode_to_vec())
+            }
+            _ => return Err("expected a `google.protobuf.FeatureSet` message literal".to_string()),
+        },
+    })
+}
+
+fn integer(value: &Constant<'_>) -> Result<i64, String> {
+    match value {
+        Constant::Int(value) => Ok(*value),
+        _ => Err("expected an integer".to_string()),
+    }
+}
+
+fn int32(value: &Constant<'_>) -> Result<i32, String> {
+    let value = integer(value)?;
+    i32::try_from(value).map_err(|_| format!("{value} is out of range for int32"))
+}
+
+fn uint32(value: &Constant<'_>) -> Result<u32, String> {
+    let value = integer(value)?;
+    u32::try_from(value).map_err(|_| format!("{value} is out of range for uint32"))
+}
+
+fn float(value: &Constant<'_>) -> Result<f64, String> {
+    match value {
+        Constant::Float(value) => Ok(*value),
+        Constant::Int(value) => Ok(*value as f64),
+        Constant::Ident(value) if value.value() == "inf" => Ok(f64::INFINITY),
+        Constant::Ident(value) if value.value() == "nan" => Ok(f64::NAN),
+        _ => Err("expected a number".to_string()),
+    }
+}
+
+/// The `default_value` of a field, as protoc writes it: enum values by name, bytes C-escaped.
+fn default_value(
+    value: &Constant<'_>,
+    ty: std::option::Option<FieldType>,
+) -> std::option::Option<String> {
+    Some(match value {
+        Constant::Ident(value) => value.value().to_string(),
+        Constant::Int(value) => value.to_string(),
+        Constant::Float(value) if value.is_nan() => "nan".to_string(),
+        Constant::Float(value) if value.is_infinite() => if value.is_sign_positive() {
+            "inf"
+        } else {
+            "-inf"
+        }
+        .to_string(),
+        Constant::Float(value) => value.to_string(),
+        Constant::Bool(value) => value.to_string(),
+        Constant::String(value) if ty == Some(FieldType::Bytes) => c_escape(value.as_bytes()),
+        Constant::String(value) => value.to_string(),
+        Constant::Aggregate(_) => return None,
+    })
+}
+
+fn c_escape(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len());
+    for byte in bytes {
+        match byte {
+            b'\n' => result.push_str("\\n"),
+            b'\r' => result.push_str("\\r"),
+            b'\t' => result.push_str("\\t"),
+            b'"' => result.push_str("\\\""),
+            b'\'' => result.push_str("\\'"),
+            b'\\' => result.push_str("\\\\"),
+            0x20..=0x7e => result.push(*byte as char),
+            _ => result.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    result
+}
+
+fn label(repeated: bool, required: bool) -> Label {
+    if repeated {
+        Label::Repeated
+    } else if required {
+        Label::Required
+    } else {
+        Label::Optional
+    }
+}
+
+fn key_type(ty: MapFieldKeyType) -> FieldType {
+    match ty {
+        MapFieldKeyType::Int32 => FieldType::Int32,
+        MapFieldKeyType::Int64 => FieldType::Int64,
+        MapFieldKeyType::UInt32 => FieldType::UInt32,
+        MapFieldKeyType::UInt64 => FieldType::UInt64,
+        MapFieldKeyType::SInt32 => FieldType::SInt32,
+        MapFieldKeyType::SInt64 => FieldType::SInt64,
+        MapFieldKeyType::Fixed32 => FieldType::Fixed32,
+        MapFieldKeyType::Fixed64 => FieldType::Fixed64,
+        MapFieldKeyType::SFixed32 => FieldType::SFixed32,
+        MapFieldKeyType::SFixed64 => FieldType::SFixed64,
+        MapFieldKeyType::Bool => FieldType::Bool,
+        MapFieldKeyType::String => FieldType::String,
+    }
+}
+
+fn visibility(visibility: Visibility) -> std::option::Option<SymbolVisibility> {
+    match visibility {
+        Visibility::Default => None,
+        Visibility::Local => Some(SymbolVisibility::Local),
+        Visibility::Export => Some(SymbolVisibility::Export),
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use super::{
+    DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto,
+};
+use crate::join;
+
+/// Fields, enum values and extensions declared by a set of files, by fully qualified name.
+#[derive(Debug, Default)]
+pub(super) struct Index {
+    pub(super) messages: HashMap<String, Vec<FieldDescriptorProto>>,
+    pub(super) enums: HashMap<String, Vec<EnumValueDescriptorProto>>,
+    pub(super) extensions: HashMap<String, FieldDescriptorProto>,
+}
+impl Index {
+    pub(super) fn add_file(&mut self, file: &FileDescriptorProto) {
+        let package = file.package.as_deref().unwrap_or_default();
+        self.add_declarations(
+            package,
+            &file.message_type,
+            &file.enum_type,
+            &file.extension,
+        );
+    }
+
+    fn add_declarations(
+        &mut self,
+        scope: &str,
+        messages: &[DescriptorProto],
+        enums: &[EnumDescriptorProto],
+        extensions: &[FieldDescriptorProto],
+    ) {
+        for message in messages {
+            let full_name = join(scope, message.name.as_deref().unwrap_or_default());
+            self.add_declarations(
+                &full_name,
+                &message.nested_type,
+                &message.enum_type,
+                &message.extension,
+            );
+            self.messages.insert(full_name, message.field.clone());
+        }
+        for value in enums {
+            let full_name = join(scope, value.name.as_deref().unwrap_or_default());
+            self.enums.insert(full_name, value.value.clone());
+        }
+        for extension in extensions {
+            let full_name = join(scope, extension.name.as_deref().unwrap_or_default());
+            self.extensions.insert(full_name, extension.clone());
+        }
+    }
+
+    /// Extension of `extendee` with the field number `number`, along with its fully qualified
+    /// name.
+    pub(super) fn extension_of(
+        &self,
+        extendee: &str,
+        number: i32,
+    ) -> Option<(&str, &FieldDescriptorProto)> {
+        self.extensions
+            .iter()
+            .find(|(_, extension)| {
+                extension.number == Some(number)
+                    && extension
+                        .extendee
+                        .as_deref()
+                        .is_some_and(|name| name.trim_start_matches('.') == extendee)
+            })
+            .map(|(name, extension)| (name.as_str(), extension))
+    }
+}
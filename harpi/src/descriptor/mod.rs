@@ -0,0 +1,573 @@
+//! Descriptor protos, as defined by `google/protobuf/descriptor.proto`, and their binary encoding.
+//!
+//! [`DescriptorEncoder`] converts parsed files into a [`FileDescriptorSet`], the format consumed by
+//! reflection libraries and `protoc` plugins, and [`DescriptorDecoder`] converts them back.
+
+mod decode;
+mod encode;
+mod index;
+mod options;
+mod wire;
+
+pub use decode::*;
+pub use encode::*;
+pub use options::*;
+pub use wire::*;
+
+use crate::Error;
+
+/// Edition numbers, as stored in [`FileDescriptorProto::edition`].
+pub const EDITION_PROTO2: i32 = 998;
+pub const EDITION_PROTO3: i32 = 999;
+pub const EDITION_2023: i32 = 1000;
+pub const EDITION_2024: i32 = 1001;
+
+/// Number of the edition named `name`, such as `2023`.
+pub fn edition_number(name: &str) -> Option<i32> {
+    match name {
+        "proto2" => Some(EDITION_PROTO2),
+        "proto3" => Some(EDITION_PROTO3),
+        "2023" => Some(EDITION_2023),
+        "2024" => Some(EDITION_2024),
+        _ => None,
+    }
+}
+
+/// Name of the edition numbered `number`, the inverse of [`edition_number`].
+pub fn edition_name(number: i32) -> Option<&'static str> {
+    match number {
+        EDITION_PROTO2 => Some("proto2"),
+        EDITION_PROTO3 => Some("proto3"),
+        EDITION_2023 => Some("2023"),
+        EDITION_2024 => Some("2024"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileDescriptorSet {
+    pub file: Vec<FileDescriptorProto>,
+}
+impl Encode for FileDescriptorSet {
+    fn encode(&self, writer: &mut Writer) {
+        writer.messages(1, &self.file);
+    }
+}
+impl Decode for FileDescriptorSet {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        if number == 1 {
+            self.file.push(value.as_message()?);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileDescriptorProto {
+    pub name: Option<String>,
+    pub package: Option<String>,
+    pub dependency: Vec<String>,
+    pub public_dependency: Vec<i32>,
+    pub weak_dependency: Vec<i32>,
+    /// Files imported with `import option`, since edition 2024.
+    pub option_dependency: Vec<String>,
+    pub message_type: Vec<DescriptorProto>,
+    pub enum_type: Vec<EnumDescriptorProto>,
+    pub service: Vec<ServiceDescriptorProto>,
+    pub extension: Vec<FieldDescriptorProto>,
+    pub options: Option<Options>,
+    /// `proto3` or `editions`. Unset for proto2.
+    pub syntax: Option<String>,
+    pub edition: Option<i32>,
+}
+impl Encode for FileDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.optional_string(2, &self.package);
+        writer.strings(3, &self.dependency);
+        writer.messages(4, &self.message_type);
+        writer.messages(5, &self.enum_type);
+        writer.messages(6, &self.service);
+        writer.messages(7, &self.extension);
+        writer.optional_message(8, &self.options);
+        for index in &self.public_dependency {
+            writer.int32(10, *index);
+        }
+        for index in &self.weak_dependency {
+            writer.int32(11, *index);
+        }
+        writer.optional_string(12, &self.syntax);
+        writer.optional_int32(14, self.edition);
+        writer.strings(15, &self.option_dependency);
+    }
+}
+impl Decode for FileDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.package = Some(value.as_string()?),
+            3 => self.dependency.push(value.as_string()?),
+            4 => self.message_type.push(value.as_message()?),
+            5 => self.enum_type.push(value.as_message()?),
+            6 => self.service.push(value.as_message()?),
+            7 => self.extension.push(value.as_message()?),
+            8 => merge_options(&mut self.options, &value)?,
+            10 => self.public_dependency.extend(value.as_int32s()?),
+            11 => self.weak_dependency.extend(value.as_int32s()?),
+            12 => self.syntax = Some(value.as_string()?),
+            14 => self.edition = Some(value.as_int32()?),
+            15 => self.option_dependency.push(value.as_string()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Describes a message type.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DescriptorProto {
+    pub name: Option<String>,
+    pub field: Vec<FieldDescriptorProto>,
+    pub extension: Vec<FieldDescriptorProto>,
+    pub nested_type: Vec<DescriptorProto>,
+    pub enum_type: Vec<EnumDescriptorProto>,
+    pub extension_range: Vec<ExtensionRange>,
+    pub oneof_decl: Vec<OneofDescriptorProto>,
+    pub options: Option<Options>,
+    pub reserved_range: Vec<ReservedRange>,
+    pub reserved_name: Vec<String>,
+    pub visibility: Option<SymbolVisibility>,
+}
+impl Encode for DescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.messages(2, &self.field);
+        writer.messages(3, &self.nested_type);
+        writer.messages(4, &self.enum_type);
+        writer.messages(5, &self.extension_range);
+        writer.messages(6, &self.extension);
+        writer.optional_message(7, &self.options);
+        writer.messages(8, &self.oneof_decl);
+        writer.messages(9, &self.reserved_range);
+        writer.strings(10, &self.reserved_name);
+        writer.optional_int32(11, self.visibility.map(SymbolVisibility::number));
+    }
+}
+impl Decode for DescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.field.push(value.as_message()?),
+            3 => self.nested_type.push(value.as_message()?),
+            4 => self.enum_type.push(value.as_message()?),
+            5 => self.extension_range.push(value.as_message()?),
+            6 => self.extension.push(value.as_message()?),
+            7 => merge_options(&mut self.options, &value)?,
+            8 => self.oneof_decl.push(value.as_message()?),
+            9 => self.reserved_range.push(value.as_message()?),
+            10 => self.reserved_name.push(value.as_string()?),
+            11 => self.visibility = SymbolVisibility::from_number(value.as_int32()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Extension numbers of a message, with `end` exclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtensionRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+    pub options: Option<Options>,
+}
+impl Encode for ExtensionRange {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_int32(1, self.start);
+        writer.optional_int32(2, self.end);
+        writer.optional_message(3, &self.options);
+    }
+}
+impl Decode for ExtensionRange {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.start = Some(value.as_int32()?),
+            2 => self.end = Some(value.as_int32()?),
+            3 => merge_options(&mut self.options, &value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Reserved numbers of a message, with `end` exclusive, or of an enum, with `end` inclusive.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReservedRange {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+}
+impl Encode for ReservedRange {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_int32(1, self.start);
+        writer.optional_int32(2, self.end);
+    }
+}
+impl Decode for ReservedRange {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.start = Some(value.as_int32()?),
+            2 => self.end = Some(value.as_int32()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FieldDescriptorProto {
+    pub name: Option<String>,
+    pub number: Option<i32>,
+    pub label: Option<Label>,
+    /// Unset when `type_name` could not be resolved.
+    pub r#type: Option<FieldType>,
+    /// Fully qualified with a leading dot once resolved, such as `.foo.Bar`.
+    pub type_name: Option<String>,
+    pub extendee: Option<String>,
+    pub default_value: Option<String>,
+    pub oneof_index: Option<i32>,
+    pub json_name: Option<String>,
+    pub options: Option<Options>,
+    pub proto3_optional: Option<bool>,
+}
+impl Encode for FieldDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.optional_string(2, &self.extendee);
+        writer.optional_int32(3, self.number);
+        writer.optional_int32(4, self.label.map(Label::number));
+        writer.optional_int32(5, self.r#type.map(FieldType::number));
+        writer.optional_string(6, &self.type_name);
+        writer.optional_string(7, &self.default_value);
+        writer.optional_message(8, &self.options);
+        writer.optional_int32(9, self.oneof_index);
+        writer.optional_string(10, &self.json_name);
+        writer.optional_bool(17, self.proto3_optional);
+    }
+}
+impl Decode for FieldDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.extendee = Some(value.as_string()?),
+            3 => self.number = Some(value.as_int32()?),
+            4 => self.label = Some(enum_value(Label::from_number, &value, "label")?),
+            5 => self.r#type = Some(enum_value(FieldType::from_number, &value, "type")?),
+            6 => self.type_name = Some(value.as_string()?),
+            7 => self.default_value = Some(value.as_string()?),
+            8 => merge_options(&mut self.options, &value)?,
+            9 => self.oneof_index = Some(value.as_int32()?),
+            10 => self.json_name = Some(value.as_string()?),
+            17 => self.proto3_optional = Some(value.as_bool()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Label {
+    Optional,
+    Required,
+    Repeated,
+}
+impl Label {
+    pub fn number(self) -> i32 {
+        match self {
+            Label::Optional => 1,
+            Label::Required => 2,
+            Label::Repeated => 3,
+        }
+    }
+
+    pub fn from_number(number: i32) -> Option<Self> {
+        match number {
+            1 => Some(Label::Optional),
+            2 => Some(Label::Required),
+            3 => Some(Label::Repeated),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldType {
+    Double,
+    Float,
+    Int64,
+    UInt64,
+    Int32,
+    Fixed64,
+    Fixed32,
+    Bool,
+    String,
+    Group,
+    Message,
+    Bytes,
+    UInt32,
+    Enum,
+    SFixed32,
+    SFixed64,
+    SInt32,
+    SInt64,
+}
+impl FieldType {
+    const ALL: [FieldType; 18] = [
+        FieldType::Double,
+        FieldType::Float,
+        FieldType::Int64,
+        FieldType::UInt64,
+        FieldType::Int32,
+        FieldType::Fixed64,
+        FieldType::Fixed32,
+        FieldType::Bool,
+        FieldType::String,
+        FieldType::Group,
+        FieldType::Message,
+        FieldType::Bytes,
+        FieldType::UInt32,
+        FieldType::Enum,
+        FieldType::SFixed32,
+        FieldType::SFixed64,
+        FieldType::SInt32,
+        FieldType::SInt64,
+    ];
+
+    pub fn number(self) -> i32 {
+        Self::ALL
+            .iter()
+            .position(|ty| *ty == self)
+            .unwrap_or_default() as i32
+            + 1
+    }
+
+    pub fn from_number(number: i32) -> Option<Self> {
+        Self::ALL
+            .get(usize::try_from(number).ok()?.checked_sub(1)?)
+            .copied()
+    }
+}
+
+/// Visibility of a message or enum declared with `export` or `local`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolVisibility {
+    Local,
+    Export,
+}
+impl SymbolVisibility {
+    pub fn number(self) -> i32 {
+        match self {
+            SymbolVisibility::Local => 1,
+            SymbolVisibility::Export => 2,
+        }
+    }
+
+    pub fn from_number(number: i32) -> Option<Self> {
+        match number {
+            1 => Some(SymbolVisibility::Local),
+            2 => Some(SymbolVisibility::Export),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OneofDescriptorProto {
+    pub name: Option<String>,
+    pub options: Option<Options>,
+}
+impl Encode for OneofDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.optional_message(2, &self.options);
+    }
+}
+impl Decode for OneofDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => merge_options(&mut self.options, &value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnumDescriptorProto {
+    pub name: Option<String>,
+    pub value: Vec<EnumValueDescriptorProto>,
+    pub options: Option<Options>,
+    pub reserved_range: Vec<ReservedRange>,
+    pub reserved_name: Vec<String>,
+    pub visibility: Option<SymbolVisibility>,
+}
+impl Encode for EnumDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.messages(2, &self.value);
+        writer.optional_message(3, &self.options);
+        writer.messages(4, &self.reserved_range);
+        writer.strings(5, &self.reserved_name);
+        writer.optional_int32(6, self.visibility.map(SymbolVisibility::number));
+    }
+}
+impl Decode for EnumDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.value.push(value.as_message()?),
+            3 => merge_options(&mut self.options, &value)?,
+            4 => self.reserved_range.push(value.as_message()?),
+            5 => self.reserved_name.push(value.as_string()?),
+            6 => self.visibility = SymbolVisibility::from_number(value.as_int32()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnumValueDescriptorProto {
+    pub name: Option<String>,
+    pub number: Option<i32>,
+    pub options: Option<Options>,
+}
+impl Encode for EnumValueDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.optional_int32(2, self.number);
+        writer.optional_message(3, &self.options);
+    }
+}
+impl Decode for EnumValueDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.number = Some(value.as_int32()?),
+            3 => merge_options(&mut self.options, &value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ServiceDescriptorProto {
+    pub name: Option<String>,
+    pub method: Vec<MethodDescriptorProto>,
+    pub options: Option<Options>,
+}
+impl Encode for ServiceDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.messages(2, &self.method);
+        writer.optional_message(3, &self.options);
+    }
+}
+impl Decode for ServiceDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.method.push(value.as_message()?),
+            3 => merge_options(&mut self.options, &value)?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MethodDescriptorProto {
+    pub name: Option<String>,
+    pub input_type: Option<String>,
+    pub output_type: Option<String>,
+    pub options: Option<Options>,
+    pub client_streaming: Option<bool>,
+    pub server_streaming: Option<bool>,
+}
+impl Encode for MethodDescriptorProto {
+    fn encode(&self, writer: &mut Writer) {
+        writer.optional_string(1, &self.name);
+        writer.optional_string(2, &self.input_type);
+        writer.optional_string(3, &self.output_type);
+        writer.optional_message(4, &self.options);
+        writer.optional_bool(5, self.client_streaming);
+        writer.optional_bool(6, self.server_streaming);
+    }
+}
+impl Decode for MethodDescriptorProto {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        match number {
+            1 => self.name = Some(value.as_string()?),
+            2 => self.input_type = Some(value.as_string()?),
+            3 => self.output_type = Some(value.as_string()?),
+            4 => merge_options(&mut self.options, &value)?,
+            5 => self.client_streaming = Some(value.as_bool()?),
+            6 => self.server_streaming = Some(value.as_bool()?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Contents of one of the `*Options` messages, kept as raw fields so that custom options survive
+/// without knowing their declaration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    pub fields: Vec<(u32, WireValue)>,
+}
+impl Options {
+    /// Values of the field `number`, in order.
+    pub fn get(&self, number: u32) -> impl Iterator<Item = &WireValue> {
+        self.fields
+            .iter()
+            .filter(move |(field, _)| *field == number)
+            .map(|(_, value)| value)
+    }
+
+    pub fn push(&mut self, number: u32, value: WireValue) {
+        self.fields.push((number, value));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+impl Encode for Options {
+    fn encode(&self, writer: &mut Writer) {
+        for (number, value) in &self.fields {
+            writer.value(*number, value);
+        }
+    }
+}
+impl Decode for Options {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error> {
+        self.fields.push((number, value));
+        Ok(())
+    }
+}
+
+/// Merges an occurrence of an options field, as repeated occurrences of a message field are merged.
+fn merge_options(options: &mut Option<Options>, value: &WireValue) -> Result<(), Error> {
+    let value = value.as_message::<Options>()?;
+    options.get_or_insert_default().fields.extend(value.fields);
+    Ok(())
+}
+
+fn enum_value<T>(
+    from_number: fn(i32) -> Option<T>,
+    value: &WireValue,
+    name: &str,
+) -> Result<T, Error> {
+    let number = value.as_int32()?;
+    from_number(number).ok_or_else(|| Error::DecodingDescriptor(format!("unknown {name} {number}")))
+}
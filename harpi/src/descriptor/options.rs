@@ -0,0 +1,260 @@
+use crate::model::Constant;
+
+/// The kind of declaration an option is set on, each with its own `google.protobuf.*Options`
+/// message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionTarget {
+    File,
+    Message,
+    Field,
+    Oneof,
+    Enum,
+    EnumValue,
+    Service,
+    Method,
+    ExtensionRange,
+}
+impl OptionTarget {
+    /// Fully qualified name of the options message, without a leading dot.
+    pub fn message_name(self) -> &'static str {
+        match self {
+            OptionTarget::File => "google.protobuf.FileOptions",
+            OptionTarget::Message => "google.protobuf.MessageOptions",
+            OptionTarget::Field => "google.protobuf.FieldOptions",
+            OptionTarget::Oneof => "google.protobuf.OneofOptions",
+            OptionTarget::Enum => "google.protobuf.EnumOptions",
+            OptionTarget::EnumValue => "google.protobuf.EnumValueOptions",
+            OptionTarget::Service => "google.protobuf.ServiceOptions",
+            OptionTarget::Method => "google.protobuf.MethodOptions",
+            OptionTarget::ExtensionRange => "google.protobuf.ExtensionRangeOptions",
+        }
+    }
+
+    /// Options declared by the options message itself.
+    pub fn standard_options(self) -> &'static [StandardOption] {
+        match self {
+            OptionTarget::File => FILE_OPTIONS,
+            OptionTarget::Message => MESSAGE_OPTIONS,
+            OptionTarget::Field => FIELD_OPTIONS,
+            OptionTarget::Oneof => ONEOF_OPTIONS,
+            OptionTarget::Enum => ENUM_OPTIONS,
+            OptionTarget::EnumValue => ENUM_VALUE_OPTIONS,
+            OptionTarget::Service => SERVICE_OPTIONS,
+            OptionTarget::Method => METHOD_OPTIONS,
+            OptionTarget::ExtensionRange => EXTENSION_RANGE_OPTIONS,
+        }
+    }
+
+    pub fn standard_option(self, name: &str) -> Option<&'static StandardOption> {
+        self.standard_options()
+            .iter()
+            .find(|option| option.name == name)
+    }
+}
+
+/// A field of one of the `google.protobuf.*Options` messages or of `google.protobuf.FeatureSet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandardOption {
+    pub name: &'static str,
+    pub number: u32,
+    pub kind: OptionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionKind {
+    Bool,
+    String,
+    /// An enum, with the number of each of its values.
+    Enum(&'static [(&'static str, i32)]),
+    /// The `features` field, holding a `google.protobuf.FeatureSet`.
+    Features,
+}
+
+const fn option(name: &'static str, number: u32, kind: OptionKind) -> StandardOption {
+    StandardOption { name, number, kind }
+}
+
+pub(crate) const OPTIMIZE_MODE: &[(&str, i32)] =
+    &[("SPEED", 1), ("CODE_SIZE", 2), ("LITE_RUNTIME", 3)];
+const CTYPE: &[(&str, i32)] = &[("STRING", 0), ("CORD", 1), ("STRING_PIECE", 2)];
+const JSTYPE: &[(&str, i32)] = &[("JS_NORMAL", 0), ("JS_STRING", 1), ("JS_NUMBER", 2)];
+const RETENTION: &[(&str, i32)] = &[
+    ("RETENTION_UNKNOWN", 0),
+    ("RETENTION_RUNTIME", 1),
+    ("RETENTION_SOURCE", 2),
+];
+const TARGET_TYPE: &[(&str, i32)] = &[
+    ("TARGET_TYPE_UNKNOWN", 0),
+    ("TARGET_TYPE_FILE", 1),
+    ("TARGET_TYPE_EXTENSION_RANGE", 2),
+    ("TARGET_TYPE_MESSAGE", 3),
+    ("TARGET_TYPE_FIELD", 4),
+    ("TARGET_TYPE_ONEOF", 5),
+    ("TARGET_TYPE_ENUM", 6),
+    ("TARGET_TYPE_ENUM_ENTRY", 7),
+    ("TARGET_TYPE_SERVICE", 8),
+    ("TARGET_TYPE_METHOD", 9),
+];
+pub(crate) const IDEMPOTENCY_LEVEL: &[(&str, i32)] = &[
+    ("IDEMPOTENCY_UNKNOWN", 0),
+    ("NO_SIDE_EFFECTS", 1),
+    ("IDEMPOTENT", 2),
+];
+const VERIFICATION_STATE: &[(&str, i32)] = &[("DECLARATION", 0), ("UNVERIFIED", 1)];
+
+pub const FILE_OPTIONS: &[StandardOption] = &[
+    option("java_package", 1, OptionKind::String),
+    option("java_outer_classname", 8, OptionKind::String),
+    option("optimize_for", 9, OptionKind::Enum(OPTIMIZE_MODE)),
+    option("java_multiple_files", 10, OptionKind::Bool),
+    option("go_package", 11, OptionKind::String),
+    option("cc_generic_services", 16, OptionKind::Bool),
+    option("java_generic_services", 17, OptionKind::Bool),
+    option("py_generic_services", 18, OptionKind::Bool),
+    option("java_generate_equals_and_hash", 20, OptionKind::Bool),
+    option("deprecated", 23, OptionKind::Bool),
+    option("java_string_check_utf8", 27, OptionKind::Bool),
+    option("cc_enable_arenas", 31, OptionKind::Bool),
+    option("objc_class_prefix", 36, OptionKind::String),
+    option("csharp_namespace", 37, OptionKind::String),
+    option("swift_prefix", 39, OptionKind::String),
+    option("php_class_prefix", 40, OptionKind::String),
+    option("php_namespace", 41, OptionKind::String),
+    option("php_metadata_namespace", 44, OptionKind::String),
+    option("ruby_package", 45, OptionKind::String),
+    option("features", 50, OptionKind::Features),
+];
+pub const MESSAGE_OPTIONS: &[StandardOption] = &[
+    option("message_set_wire_format", 1, OptionKind::Bool),
+    option("no_standard_descriptor_accessor", 2, OptionKind::Bool),
+    option("deprecated", 3, OptionKind::Bool),
+    option("map_entry", 7, OptionKind::Bool),
+    option("features", 12, OptionKind::Features),
+];
+pub const FIELD_OPTIONS: &[StandardOption] = &[
+    option("ctype", 1, OptionKind::Enum(CTYPE)),
+    option("packed", 2, OptionKind::Bool),
+    option("deprecated", 3, OptionKind::Bool),
+    option("lazy", 5, OptionKind::Bool),
+    option("jstype", 6, OptionKind::Enum(JSTYPE)),
+    option("weak", 10, OptionKind::Bool),
+    option("unverified_lazy", 15, OptionKind::Bool),
+    option("debug_redact", 16, OptionKind::Bool),
+    option("retention", 17, OptionKind::Enum(RETENTION)),
+    option("targets", 19, OptionKind::Enum(TARGET_TYPE)),
+    option("features", 21, OptionKind::Features),
+];
+pub const ONEOF_OPTIONS: &[StandardOption] = &[option("features", 1, OptionKind::Features)];
+pub const ENUM_OPTIONS: &[StandardOption] = &[
+    option("allow_alias", 2, OptionKind::Bool),
+    option("deprecated", 3, OptionKind::Bool),
+    option("features", 7, OptionKind::Features),
+];
+pub const ENUM_VALUE_OPTIONS: &[StandardOption] = &[
+    option("deprecated", 1, OptionKind::Bool),
+    option("features", 2, OptionKind::Features),
+    option("debug_redact", 3, OptionKind::Bool),
+];
+pub const SERVICE_OPTIONS: &[StandardOption] = &[
+    option("deprecated", 33, OptionKind::Bool),
+    option("features", 34, OptionKind::Features),
+];
+pub const METHOD_OPTIONS: &[StandardOption] = &[
+    option("deprecated", 33, OptionKind::Bool),
+    option("idempotency_level", 34, OptionKind::Enum(IDEMPOTENCY_LEVEL)),
+    option("features", 35, OptionKind::Features),
+];
+pub const EXTENSION_RANGE_OPTIONS: &[StandardOption] = &[
+    option("verification", 3, OptionKind::Enum(VERIFICATION_STATE)),
+    option("features", 50, OptionKind::Features),
+];
+
+/// Fields of `google.protobuf.FeatureSet`, set with `option features.<name> = ...;`.
+pub const FEATURE_SET: &[StandardOption] = &[
+    option(
+        "field_presence",
+        1,
+        OptionKind::Enum(&[
+            ("FIELD_PRESENCE_UNKNOWN", 0),
+            ("EXPLICIT", 1),
+            ("IMPLICIT", 2),
+            ("LEGACY_REQUIRED", 3),
+        ]),
+    ),
+    option(
+        "enum_type",
+        2,
+        OptionKind::Enum(&[("ENUM_TYPE_UNKNOWN", 0), ("OPEN", 1), ("CLOSED", 2)]),
+    ),
+    option(
+        "repeated_field_encoding",
+        3,
+        OptionKind::Enum(&[
+            ("REPEATED_FIELD_ENCODING_UNKNOWN", 0),
+            ("PACKED", 1),
+            ("EXPANDED", 2),
+        ]),
+    ),
+    option(
+        "utf8_validation",
+        4,
+        OptionKind::Enum(&[("UTF8_VALIDATION_UNKNOWN", 0), ("VERIFY", 2), ("NONE", 3)]),
+    ),
+    option(
+        "message_encoding",
+        5,
+        OptionKind::Enum(&[
+            ("MESSAGE_ENCODING_UNKNOWN", 0),
+            ("LENGTH_PREFIXED", 1),
+            ("DELIMITED", 2),
+        ]),
+    ),
+    option(
+        "json_format",
+        6,
+        OptionKind::Enum(&[
+            ("JSON_FORMAT_UNKNOWN", 0),
+            ("ALLOW", 1),
+            ("LEGACY_BEST_EFFORT", 2),
+        ]),
+    ),
+];
+
+pub fn feature(name: &str) -> Option<&'static StandardOption> {
+    FEATURE_SET.iter().find(|feature| feature.name == name)
+}
+
+/// The value of a bool option, written `true` or `false`.
+pub(crate) fn bool_constant(value: &Constant<'_>) -> Result<bool, String> {
+    match value {
+        Constant::Bool(value) => Ok(*value),
+        Constant::Ident(value) if value.value() == "true" => Ok(true),
+        Constant::Ident(value) if value.value() == "false" => Ok(false),
+        _ => Err("expected `true` or `false`".to_string()),
+    }
+}
+
+pub(crate) fn string_constant<'c>(value: &'c Constant<'_>) -> Result<&'c str, String> {
+    match value {
+        Constant::String(value) => Ok(value),
+        _ => Err("expected a string".to_string()),
+    }
+}
+
+/// The entry of `values` named by the value of an enum option.
+pub(crate) fn enum_constant(
+    values: &'static [(&'static str, i32)],
+    value: &Constant<'_>,
+) -> Result<(&'static str, i32), String> {
+    let entry = match value {
+        Constant::Ident(ident) if !ident.relative() => values
+            .iter()
+            .find(|(name, _)| *name == ident.value())
+            .copied(),
+        _ => None,
+    };
+    entry.ok_or_else(|| {
+        let names = values.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        format!("expected one of {}", names.join(", "))
+    })
+}
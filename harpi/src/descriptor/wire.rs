@@ -0,0 +1,287 @@
+use crate::Error;
+
+/// A field value as found on the wire, before it is interpreted with its declared type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WireValue {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(Vec<u8>),
+    Fixed32(u32),
+}
+impl WireValue {
+    pub fn wire_type(&self) -> u32 {
+        match self {
+            WireValue::Varint(_) => 0,
+            WireValue::Fixed64(_) => 1,
+            WireValue::Bytes(_) => 2,
+            WireValue::Fixed32(_) => 5,
+        }
+    }
+
+    pub fn as_varint(&self) -> Result<u64, Error> {
+        match self {
+            WireValue::Varint(value) => Ok(*value),
+            value => Err(unexpected("varint", value)),
+        }
+    }
+
+    pub fn as_int32(&self) -> Result<i32, Error> {
+        Ok(self.as_varint()? as i32)
+    }
+
+    pub fn as_bool(&self) -> Result<bool, Error> {
+        Ok(self.as_varint()? != 0)
+    }
+
+    pub fn as_bytes(&self) -> Result<&[u8], Error> {
+        match self {
+            WireValue::Bytes(value) => Ok(value),
+            value => Err(unexpected("length-delimited", value)),
+        }
+    }
+
+    pub fn as_string(&self) -> Result<String, Error> {
+        String::from_utf8(self.as_bytes()?.to_vec())
+            .map_err(|_| Error::DecodingDescriptor("string is not valid UTF-8".to_string()))
+    }
+
+    pub fn as_message<T: Decode>(&self) -> Result<T, Error> {
+        T::decode(self.as_bytes()?)
+    }
+
+    /// Values of a repeated `int32` field, either packed or not.
+    pub fn as_int32s(&self) -> Result<Vec<i32>, Error> {
+        match self {
+            WireValue::Bytes(bytes) => {
+                let mut reader = Reader::new(bytes);
+                let mut values = Vec::new();
+                while !reader.bytes.is_empty() {
+                    values.push(reader.raw_varint()? as i32);
+                }
+                Ok(values)
+            }
+            value => Ok(vec![value.as_int32()?]),
+        }
+    }
+}
+
+fn unexpected(expected: &str, value: &WireValue) -> Error {
+    Error::DecodingDescriptor(format!(
+        "expected a {expected} value, found wire type {}",
+        value.wire_type()
+    ))
+}
+
+/// Types serialized with the protobuf binary wire format.
+pub trait Encode {
+    fn encode(&self, writer: &mut Writer);
+
+    fn encode_to_vec(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+        self.encode(&mut writer);
+        writer.into_bytes()
+    }
+}
+
+/// Types deserialized from the protobuf binary wire format. Unknown fields are ignored.
+pub trait Decode: Default {
+    fn merge(&mut self, number: u32, value: WireValue) -> Result<(), Error>;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let mut result = Self::default();
+        for field in Reader::new(bytes) {
+            let (number, value) = field?;
+            result.merge(number, value)?;
+        }
+        Ok(result)
+    }
+}
+
+/// Reads the fields of an encoded message, in order.
+#[derive(Debug, Clone)]
+pub struct Reader<'b> {
+    bytes: &'b [u8],
+}
+impl<'b> Reader<'b> {
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'b [u8], Error> {
+        if self.bytes.len() < len {
+            return Err(Error::DecodingDescriptor(
+                "unexpected end of input".to_string(),
+            ));
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    fn raw_varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err(Error::DecodingDescriptor("varint is too long".to_string()))
+    }
+
+    fn field(&mut self) -> Result<(u32, WireValue), Error> {
+        let tag = self.raw_varint()?;
+        let number = (tag >> 3) as u32;
+        let value = match tag & 7 {
+            0 => WireValue::Varint(self.raw_varint()?),
+            1 => WireValue::Fixed64(u64::from_le_bytes(
+                self.take(8)?.try_into().unwrap_or_default(),
+            )),
+            2 => {
+                let len = self.raw_varint()? as usize;
+                WireValue::Bytes(self.take(len)?.to_vec())
+            }
+            5 => WireValue::Fixed32(u32::from_le_bytes(
+                self.take(4)?.try_into().unwrap_or_default(),
+            )),
+            wire_type => {
+                return Err(Error::DecodingDescriptor(format!(
+                    "unsupported wire type {wire_type} for field {number}"
+                )));
+            }
+        };
+        if number == 0 {
+            return Err(Error::DecodingDescriptor("field number 0".to_string()));
+        }
+        Ok((number, value))
+    }
+}
+impl Iterator for Reader<'_> {
+    type Item = Result<(u32, WireValue), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let field = self.field();
+        if field.is_err() {
+            self.bytes = &[];
+        }
+        Some(field)
+    }
+}
+
+/// Appends protobuf fields to a buffer.
+#[derive(Debug, Clone, Default)]
+pub struct Writer {
+    buffer: Vec<u8>,
+}
+impl Writer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
+
+    fn tag(&mut self, number: u32, wire_type: u32) {
+        self.raw_varint(((number as u64) << 3) | wire_type as u64);
+    }
+
+    pub fn value(&mut self, number: u32, value: &WireValue) {
+        self.tag(number, value.wire_type());
+        match value {
+            WireValue::Varint(value) => self.raw_varint(*value),
+            WireValue::Fixed64(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+            WireValue::Fixed32(value) => self.buffer.extend_from_slice(&value.to_le_bytes()),
+            WireValue::Bytes(value) => {
+                self.raw_varint(value.len() as u64);
+                self.buffer.extend_from_slice(value);
+            }
+        }
+    }
+
+    pub fn varint(&mut self, number: u32, value: u64) {
+        self.tag(number, 0);
+        self.raw_varint(value);
+    }
+
+    /// Writes an `int32` or enum value. Negative values are sign-extended to ten bytes.
+    pub fn int32(&mut self, number: u32, value: i32) {
+        self.varint(number, value as i64 as u64);
+    }
+
+    pub fn bool(&mut self, number: u32, value: bool) {
+        self.varint(number, value as u64);
+    }
+
+    pub fn bytes(&mut self, number: u32, value: &[u8]) {
+        self.tag(number, 2);
+        self.raw_varint(value.len() as u64);
+        self.buffer.extend_from_slice(value);
+    }
+
+    pub fn string(&mut self, number: u32, value: &str) {
+        self.bytes(number, value.as_bytes());
+    }
+
+    pub fn message(&mut self, number: u32, value: &impl Encode) {
+        self.bytes(number, &value.encode_to_vec());
+    }
+
+    pub fn optional_string(&mut self, number: u32, value: &Option<String>) {
+        if let Some(value) = value {
+            self.string(number, value);
+        }
+    }
+
+    pub fn optional_int32(&mut self, number: u32, value: Option<i32>) {
+        if let Some(value) = value {
+            self.int32(number, value);
+        }
+    }
+
+    pub fn optional_bool(&mut self, number: u32, value: Option<bool>) {
+        if let Some(value) = value {
+            self.bool(number, value);
+        }
+    }
+
+    pub fn optional_message(&mut self, number: u32, value: &Option<impl Encode>) {
+        if let Some(value) = value {
+            self.message(number, value);
+        }
+    }
+
+    pub fn messages(&mut self, number: u32, values: &[impl Encode]) {
+        for value in values {
+            self.message(number, value);
+        }
+    }
+
+    pub fn strings(&mut self, number: u32, values: &[String]) {
+        for value in values {
+            self.string(number, value);
+        }
+    }
+}
+
+/// Zigzag encoding used by `sint32` and `sint64`.
+pub fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Inverse of [`zigzag`].
+pub fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
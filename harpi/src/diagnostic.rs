@@ -0,0 +1,490 @@
+use std::fmt::{Display, Write};
+
+use getter::Getter;
+use pest::{
+    RuleType,
+    error::{ErrorVariant, InputLocation, LineColLocation},
+};
+
+use crate::{
+    Error,
+    model::{Position, Span},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+/// Stable identifier of a kind of diagnostic. Codes are never reused once published.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DiagnosticCode {
+    /// A failure inside the parser that should not be reachable.
+    Internal,
+    /// The source does not match the grammar.
+    Syntax,
+    /// No parser handles the declared syntax or edition.
+    UnsupportedSyntax,
+    /// The file declares more than one package.
+    DuplicatePackage,
+    /// A numeric literal is out of range or malformed.
+    InvalidLiteral,
+    /// A declaration is missing one of its required parts.
+    IncompleteDeclaration,
+    /// An imported file cannot be found.
+    MissingImport,
+    /// Files import each other in a cycle.
+    ImportCycle,
+    /// A file exists but cannot be read.
+    UnreadableFile,
+    /// A type name does not match any visible declaration.
+    UnresolvedReference,
+    /// A type name matches several declarations.
+    AmbiguousReference,
+    /// A type name refers to a declaration of the wrong kind, such as an enum used as an rpc input.
+    InvalidReference,
+    /// Two fields of a message share a number.
+    DuplicateFieldNumber,
+    /// A field number is in the range reserved for the protobuf implementation.
+    ReservedFieldNumber,
+    /// A field number is zero or above `2^29 - 1`.
+    FieldNumberOutOfRange,
+    /// A field or enum value uses a name or number declared `reserved`.
+    ReservedFieldCollision,
+    /// The first value of an open enum is not zero.
+    EnumFirstValueNotZero,
+    /// Two values of an enum share a number without `allow_alias`.
+    DuplicateEnumValue,
+    /// The `required` label is used where it is not allowed.
+    RequiredMisuse,
+    /// An option name matches neither a standard option nor a known extension.
+    UnknownOption,
+    /// An option value does not match the type of the option.
+    InvalidOptionValue,
+    /// Descriptor bytes are malformed.
+    InvalidDescriptor,
+    /// A visitor stopped the parse with an error.
+    Visitor,
+}
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticCode::Internal => "E0000",
+            DiagnosticCode::Syntax => "E0001",
+            DiagnosticCode::UnsupportedSyntax => "E0002",
+            DiagnosticCode::DuplicatePackage => "E0003",
+            DiagnosticCode::InvalidLiteral => "E0004",
+            DiagnosticCode::IncompleteDeclaration => "E0005",
+            DiagnosticCode::MissingImport => "E0006",
+            DiagnosticCode::ImportCycle => "E0007",
+            DiagnosticCode::UnreadableFile => "E0008",
+            DiagnosticCode::UnresolvedReference => "E0009",
+            DiagnosticCode::AmbiguousReference => "E0010",
+            DiagnosticCode::InvalidReference => "E0011",
+            DiagnosticCode::DuplicateFieldNumber => "E0012",
+            DiagnosticCode::ReservedFieldNumber => "E0013",
+            DiagnosticCode::FieldNumberOutOfRange => "E0014",
+            DiagnosticCode::ReservedFieldCollision => "E0015",
+            DiagnosticCode::EnumFirstValueNotZero => "E0016",
+            DiagnosticCode::DuplicateEnumValue => "E0017",
+            DiagnosticCode::RequiredMisuse => "E0018",
+            DiagnosticCode::UnknownOption => "E0019",
+            DiagnosticCode::InvalidOptionValue => "E0020",
+            DiagnosticCode::InvalidDescriptor => "E0021",
+            DiagnosticCode::Visitor => "E0022",
+        }
+    }
+}
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A message about a location in a source file, such as a syntax error. Diagnostics without a
+/// known location keep the default, empty span.
+#[derive(Debug, Clone, Getter)]
+pub struct Diagnostic {
+    severity: Severity,
+    code: DiagnosticCode,
+    message: String,
+    file: Option<String>,
+    span: Span,
+    notes: Vec<String>,
+}
+impl Diagnostic {
+    pub fn new(
+        severity: Severity,
+        code: DiagnosticCode,
+        message: impl Into<String>,
+        span: Span,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            file: None,
+            span,
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error(code: DiagnosticCode, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: DiagnosticCode, message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Warning, code, message, span)
+    }
+
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = Some(file.into());
+        self
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Renders the diagnostic with the annotated lines of `source`, without colors.
+    pub fn render(&self, source: &str) -> String {
+        Renderer::plain().render(self, source)
+    }
+
+    /// Renders the diagnostic with the annotated lines of `source`, using ANSI colors.
+    pub fn render_colored(&self, source: &str) -> String {
+        Renderer::colored().render(self, source)
+    }
+
+    pub(crate) fn from_pest<R: RuleType>(error: &pest::error::Error<R>) -> Self {
+        let (start, end) = match error.location {
+            InputLocation::Pos(offset) => (offset, offset),
+            InputLocation::Span(span) => span,
+        };
+        let span = match error.line_col {
+            LineColLocation::Pos((line, column)) => {
+                let start = Position::new(start, line, column);
+                Span::new(start, start)
+            }
+            LineColLocation::Span((line, column), (end_line, end_column)) => Span::new(
+                Position::new(start, line, column),
+                Position::new(end, end_line, end_column),
+            ),
+        };
+        let message = match &error.variant {
+            ErrorVariant::ParsingError {
+                positives,
+                negatives,
+            } => {
+                if !positives.is_empty() {
+                    format!("expected {}", describe_rules(positives))
+                } else if !negatives.is_empty() {
+                    format!("unexpected {}", describe_rules(negatives))
+                } else {
+                    "unexpected input".to_string()
+                }
+            }
+            ErrorVariant::CustomError { message } => message.clone(),
+        };
+        Self::error(DiagnosticCode::Syntax, message, span)
+    }
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        if self.span != Span::default() {
+            let start = self.span.start();
+            write!(f, "{}:{}: ", start.line(), start.column())?;
+        } else if self.file.is_some() {
+            f.write_str(" ")?;
+        }
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity.as_str(),
+            self.code,
+            self.message
+        )
+    }
+}
+impl std::error::Error for Diagnostic {}
+impl From<&Error> for Diagnostic {
+    fn from(value: &Error) -> Self {
+        let span = Span::default();
+        match value {
+            Error::ParsingSyntax(error) => Diagnostic::from_pest(error),
+            Error::ParsingProto3(error) => Diagnostic::from_pest(error),
+            Error::ParsingProto2(error) => Diagnostic::from_pest(error),
+            Error::ParsingEditions(error) => Diagnostic::from_pest(error),
+            Error::UndefinedParser(syntax) => Diagnostic::error(
+                DiagnosticCode::UnsupportedSyntax,
+                format!("no parser supports syntax `{syntax}`"),
+                span,
+            ),
+            Error::InvalidSyntax(expected, found) => Diagnostic::error(
+                DiagnosticCode::UnsupportedSyntax,
+                format!("expected syntax `{expected}`, found `{found}`"),
+                span,
+            ),
+            Error::DuplicatePackage(first, second) => Diagnostic::error(
+                DiagnosticCode::DuplicatePackage,
+                format!("package `{second}` declared after package `{first}`"),
+                span,
+            )
+            .with_note("a file may declare at most one package"),
+            Error::MissingImport(chain) => {
+                let path = chain.last().map_or("", String::as_str);
+                let diagnostic = Diagnostic::error(
+                    DiagnosticCode::MissingImport,
+                    format!("file `{path}` not found"),
+                    span,
+                );
+                match chain.len() {
+                    0 | 1 => diagnostic,
+                    _ => diagnostic.with_note(format!("imported through {}", chain.join(" -> "))),
+                }
+            }
+            Error::ImportCycle(chain) => Diagnostic::error(
+                DiagnosticCode::ImportCycle,
+                format!("import cycle {}", chain.join(" -> ")),
+                span,
+            ),
+            Error::LoadingFile(path, error) => Diagnostic::error(
+                DiagnosticCode::UnreadableFile,
+                format!("failed to read `{path}`: {error}"),
+                span,
+            ),
+            Error::ParsingFile(path, error) => error.to_diagnostic().with_file(path.clone()),
+            Error::DecodingDescriptor(message) => Diagnostic::error(
+                DiagnosticCode::InvalidDescriptor,
+                format!("invalid descriptor: {message}"),
+                span,
+            ),
+            Error::Visitor(error) => {
+                Diagnostic::error(DiagnosticCode::Visitor, error.to_string(), span)
+            }
+            Error::ParsingLiteralInt(_) | Error::ParsingLiteralFloat(_) => {
+                Diagnostic::error(DiagnosticCode::InvalidLiteral, value.to_string(), span)
+            }
+            Error::ParsingOption(_)
+            | Error::ParsingNormalField(_)
+            | Error::ParsingOneOfFieldItemError(_)
+            | Error::ParsingMapField(_)
+            | Error::ParsingMessage(_)
+            | Error::ParsingAggregateField(_)
+            | Error::ParsingGroupField(_)
+            | Error::ParsingExtend(_) => Diagnostic::error(
+                DiagnosticCode::IncompleteDeclaration,
+                value.to_string(),
+                span,
+            ),
+            Error::Unknown | Error::UndefinedParsingRoute => {
+                Diagnostic::error(DiagnosticCode::Internal, "internal parser error", span)
+                    .with_note(value.to_string())
+            }
+        }
+    }
+}
+impl From<Error> for Diagnostic {
+    fn from(value: Error) -> Self {
+        Diagnostic::from(&value)
+    }
+}
+
+/// Renders diagnostics as compiler-style annotated source snippets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renderer {
+    colored: bool,
+}
+impl Renderer {
+    pub fn plain() -> Self {
+        Self { colored: false }
+    }
+
+    pub fn colored() -> Self {
+        Self { colored: true }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic, source: &str) -> String {
+        let mut output = String::new();
+        let _ = self.write(&mut output, diagnostic, source);
+        output
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.colored {
+            format!("\x1b[{style}m{text}\x1b[0m")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn write(
+        &self,
+        output: &mut String,
+        diagnostic: &Diagnostic,
+        source: &str,
+    ) -> std::fmt::Result {
+        let accent = match diagnostic.severity {
+            Severity::Error => "1;31",
+            Severity::Warning => "1;33",
+            Severity::Note => "1;32",
+        };
+        let gutter = "1;34";
+        let header = format!("{}[{}]", diagnostic.severity.as_str(), diagnostic.code);
+        writeln!(
+            output,
+            "{}{}",
+            self.paint(accent, &header),
+            self.paint("1", &format!(": {}", diagnostic.message))
+        )?;
+
+        let span = diagnostic.span;
+        let lines = source.lines().collect::<Vec<_>>();
+        let located = span != Span::default() && span.start().line() <= lines.len();
+        let first = span.start().line();
+        let last = span.end().line().clamp(first, lines.len().max(first));
+        let width = last.to_string().len();
+        let padding = " ".repeat(width);
+
+        let file = diagnostic.file.as_deref().unwrap_or("<input>");
+        if located {
+            writeln!(
+                output,
+                "{padding}{} {file}:{}:{}",
+                self.paint(gutter, "-->"),
+                first,
+                span.start().column()
+            )?;
+        } else {
+            writeln!(output, "{padding}{} {file}", self.paint(gutter, "-->"))?;
+        }
+
+        if located {
+            let bar = self.paint(gutter, "|");
+            writeln!(output, "{padding} {bar}")?;
+            for line in first..=last {
+                let text = lines[line - 1].trim_end_matches('\r');
+                let length = text.chars().count();
+                let from = if line == first {
+                    span.start().column() - 1
+                } else {
+                    0
+                };
+                let to = if line == span.end().line() {
+                    span.end().column() - 1
+                } else {
+                    length
+                };
+                let marks = to.saturating_sub(from).max(1);
+                let number = self.paint(gutter, &format!("{line:>width$}"));
+                writeln!(output, "{number} {bar} {text}")?;
+                writeln!(
+                    output,
+                    "{padding} {bar} {}{}",
+                    " ".repeat(from),
+                    self.paint(accent, &"^".repeat(marks))
+                )?;
+            }
+        }
+
+        if !diagnostic.notes.is_empty() {
+            writeln!(output, "{padding} {}", self.paint(gutter, "|"))?;
+        }
+        for note in &diagnostic.notes {
+            writeln!(
+                output,
+                "{padding} {} {}: {note}",
+                self.paint(gutter, "="),
+                self.paint("1", "note")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn describe_rules<R: RuleType>(rules: &[R]) -> String {
+    let mut names: Vec<String> = Vec::new();
+    for rule in rules {
+        let name = describe_rule(&format!("{rule:?}"));
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    match names.as_slice() {
+        [] => String::new(),
+        [name] => name.clone(),
+        [rest @ .., last] => format!("{} or {last}", rest.join(", ")),
+    }
+}
+
+/// Human-readable name of a grammar rule, so internal names such as `STRING_LIT_CONTENT` never
+/// reach the user.
+fn describe_rule(rule: &str) -> String {
+    if let Some(keyword) = rule.strip_prefix("keyword_") {
+        return format!("`{keyword}`");
+    }
+    let name = match rule {
+        "EOI" => "end of file",
+        "IDENT" | "FULL_IDENT" | "BRACED_FULL_IDENT" | "enum_name" | "message_name"
+        | "service_name" | "rpc_name" | "group_name" | "field_name" | "str_field_name" => {
+            "identifier"
+        }
+        "user_type" | "message_type" | "enum_type" | "extend_type" | "type" | "key_type" => {
+            "type name"
+        }
+        "option_name" | "option_name_part" => "option name",
+        "CONSTANT" | "AGGREGATE_VALUE" => "constant",
+        "AGGREGATE" | "AGGREGATE_FIELDS" => "message literal",
+        "AGGREGATE_FIELD" | "AGGREGATE_FIELD_NAME" | "AGGREGATE_EXTENSION_NAME" => {
+            "message literal field"
+        }
+        "AGGREGATE_LIST" => "list",
+        "syntax" | "syntax_proto2" | "syntax_proto3" | "syntax_edition" => "syntax declaration",
+        "field" | "field_option" | "field_options" => "field",
+        "one_of" | "one_of_field" => "oneof",
+        "map_field" => "map field",
+        "enum_field" | "enum_value_option" => "enum value",
+        "rpc" | "rpc_field" | "rpc_input" | "rpc_output" => "rpc",
+        "ranges" | "range" => "range",
+        "field_names" | "str_field_names" => "field names",
+        "COMMENT"
+        | "COMMENT_LINE"
+        | "COMMENT_BLOCK"
+        | "COMMENT_LINE_INNER"
+        | "COMMENT_BLOCK_INNER" => "comment",
+        rule if rule.starts_with("STRING_LIT") || rule.starts_with("AGGREGATE_STRING") => {
+            "string literal"
+        }
+        rule if rule.contains("FLOAT") => "number",
+        rule if rule.contains("INT_LIT")
+            || rule.contains("DECIMAL")
+            || rule.contains("OCTAL")
+            || rule.contains("HEX") =>
+        {
+            "integer"
+        }
+        rule => return rule.to_lowercase().replace('_', " "),
+    };
+    name.to_string()
+}
@@ -0,0 +1,165 @@
+use std::borrow::Cow;
+
+use crate::{
+    Linked,
+    fold::{self, Fold},
+    link,
+    model::{
+        Aggregate, AggregateField, Enum, EnumItem, Extend, ExtensionRange, Extensions, GroupField,
+        Ident, Import, MapField, Message, MessageReference, NormalField, OneOfField,
+        OneOfFieldItem, Option, OptionName, Package, Proto, ReservedItems, Service, ServiceRpc,
+        ServiceRpcField, Span, Syntax, Type,
+    },
+};
+
+/// Whether `a` and `b` declare the same schema. Unlike `==`, this ignores comments, spans, the
+/// order of options within a declaration, and how type names are written: names resolving to
+/// the same declaration are equal, so `.shop.Order` matches `Order` inside package `shop`.
+/// Each file is linked on its own, names it cannot resolve are compared as written.
+pub fn equivalent(a: &Proto<'_>, b: &Proto<'_>) -> bool {
+    canonicalize(a, &link(a), "") == canonicalize(b, &link(b), "")
+}
+
+/// The form of `proto` compared by [`equivalent`]: comments removed, spans reset, options sorted,
+/// and type names resolved by `linked` replaced with their fully qualified name, with a leading
+/// dot. `file` is the name `proto` was given when linking.
+pub fn canonicalize(proto: &Proto<'_>, linked: &Linked, file: &str) -> Proto<'static> {
+    Canonical { linked, file }.fold_proto(proto.clone().into_owned())
+}
+
+struct Canonical<'l> {
+    linked: &'l Linked,
+    file: &'l str,
+}
+impl Canonical<'_> {
+    fn resolve<'a>(&self, name: Cow<'a, str>, span: Span) -> Cow<'a, str> {
+        if span == Span::default() {
+            return name;
+        }
+        match self.linked.target(self.file, span) {
+            Some(symbol) => Cow::Owned(format!(".{}", symbol.full_name())),
+            None => name,
+        }
+    }
+
+    fn resolve_type<'a>(&self, ty: Type<'a>, span: Span) -> Type<'a> {
+        match ty {
+            Type::Reference(name) => Type::Reference(self.resolve(name, span)),
+            ty => ty,
+        }
+    }
+}
+
+/// Runs the default fold, then resets the listed spans and drops the comments.
+macro_rules! declarations {
+    ($a:lifetime; $($method:ident($ty:ident) { $($span:ident),* })*) => {
+        $(fn $method(&mut self, node: $ty<$a>) -> $ty<$a> {
+            let mut node = fold::$method(self, node);
+            $(node.$span = Span::default();)*
+            node.comments = Cow::default();
+            node.trailing_comments = Cow::default();
+            node.detached_comments = Cow::default();
+            node
+        })*
+    };
+}
+
+impl<'a> Fold<'a> for Canonical<'_> {
+    declarations! {
+        'a;
+        fold_syntax(Syntax) { value_span, span }
+        fold_package(Package) { value_span, span }
+        fold_import(Import) { value_span, span }
+        fold_option(Option) { value_span, span }
+        fold_service(Service) { span }
+        fold_service_rpc(ServiceRpc) { span }
+        fold_message(Message) { span }
+        fold_enum(Enum) { span }
+        fold_enum_item(EnumItem) { number_span, span }
+        fold_reserved_items(ReservedItems) { span }
+        fold_extensions(Extensions) { span }
+        fold_extend(Extend) { span }
+        fold_group_field(GroupField) { number_span, span }
+        fold_one_of_field(OneOfField) { span }
+    }
+
+    fn fold_normal_field(&mut self, mut node: NormalField<'a>) -> NormalField<'a> {
+        node.ty = self.resolve_type(node.ty, node.ty_span);
+        node.ty_span = Span::default();
+        node.number_span = Span::default();
+        node.span = Span::default();
+        node.comments = Cow::default();
+        node.trailing_comments = Cow::default();
+        node.detached_comments = Cow::default();
+        fold::fold_normal_field(self, node)
+    }
+
+    fn fold_one_of_field_item(&mut self, mut node: OneOfFieldItem<'a>) -> OneOfFieldItem<'a> {
+        node.ty = self.resolve_type(node.ty, node.ty_span);
+        node.ty_span = Span::default();
+        node.number_span = Span::default();
+        node.span = Span::default();
+        node.comments = Cow::default();
+        node.trailing_comments = Cow::default();
+        node.detached_comments = Cow::default();
+        fold::fold_one_of_field_item(self, node)
+    }
+
+    fn fold_map_field(&mut self, mut node: MapField<'a>) -> MapField<'a> {
+        node.value_ty = self.resolve_type(node.value_ty, node.value_ty_span);
+        node.key_ty_span = Span::default();
+        node.value_ty_span = Span::default();
+        node.number_span = Span::default();
+        node.span = Span::default();
+        node.comments = Cow::default();
+        node.trailing_comments = Cow::default();
+        node.detached_comments = Cow::default();
+        fold::fold_map_field(self, node)
+    }
+
+    fn fold_message_reference(&mut self, node: MessageReference<'a>) -> MessageReference<'a> {
+        MessageReference::new(self.resolve(node.0, node.1))
+    }
+
+    fn fold_service_rpc_field(&mut self, node: ServiceRpcField<'a>) -> ServiceRpcField<'a> {
+        let mut node = fold::fold_service_rpc_field(self, node);
+        node.span = Span::default();
+        node
+    }
+
+    /// Options are sorted by name once canonical. The sort is stable, so that an option set
+    /// several times keeps its values in order.
+    fn fold_options(&mut self, node: Vec<Option<'a>>) -> Vec<Option<'a>> {
+        let mut options = fold::fold_options(self, node);
+        options.sort_by_key(|option| option.name().to_string());
+        options
+    }
+
+    fn fold_option_name(&mut self, node: OptionName<'a>) -> OptionName<'a> {
+        let mut node = fold::fold_option_name(self, node);
+        node.span = Span::default();
+        node
+    }
+
+    fn fold_extension_range(&mut self, mut node: ExtensionRange) -> ExtensionRange {
+        node.span = Span::default();
+        node
+    }
+
+    fn fold_aggregate(&mut self, node: Aggregate<'a>) -> Aggregate<'a> {
+        let mut node = fold::fold_aggregate(self, node);
+        node.span = Span::default();
+        node
+    }
+
+    fn fold_aggregate_field(&mut self, node: AggregateField<'a>) -> AggregateField<'a> {
+        let mut node = fold::fold_aggregate_field(self, node);
+        node.span = Span::default();
+        node
+    }
+
+    fn fold_ident(&mut self, mut node: Ident<'a>) -> Ident<'a> {
+        node.span = Span::default();
+        node
+    }
+}
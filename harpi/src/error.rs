@@ -6,6 +6,10 @@ pub enum Error {
     ParsingSyntax(#[from] pest::error::Error<crate::syntax::proto3::Rule>),
     #[error(transparent)]
     ParsingProto3(#[from] pest::error::Error<crate::syntax::header::Rule>),
+    #[error(transparent)]
+    ParsingProto2(#[from] pest::error::Error<crate::syntax::proto2::Rule>),
+    #[error(transparent)]
+    ParsingEditions(#[from] pest::error::Error<crate::syntax::editions::Rule>),
     #[error("Unknown error")]
     Unknown,
     #[error("path should be unreachable")]
@@ -14,6 +18,21 @@ pub enum Error {
     UndefinedParser(String),
     #[error("parser {0} was expected, found {1}")]
     InvalidSyntax(String, String),
+    #[error("package {0} is already declared, found {1}")]
+    DuplicatePackage(String, String),
+    #[error("file {} not found, imported through {}", .0.last().map_or("", String::as_str), .0.join(" -> "))]
+    MissingImport(Vec<String>),
+    #[error("import cycle {}", .0.join(" -> "))]
+    ImportCycle(Vec<String>),
+    #[error("failed to read {0}: {1}")]
+    LoadingFile(String, std::io::Error),
+    #[error("{0}: {1}")]
+    ParsingFile(String, Box<Error>),
+    #[error("invalid descriptor: {0}")]
+    DecodingDescriptor(String),
+    /// Raised by a [`TryProtoVisitor`](crate::TryProtoVisitor), which stopped the parse.
+    #[error(transparent)]
+    Visitor(Box<dyn std::error::Error + Send + Sync>),
     #[error(transparent)]
     ParsingLiteralInt(#[from] ParseIntError),
     #[error(transparent)]
@@ -28,4 +47,24 @@ pub enum Error {
     ParsingMapField(#[from] crate::model::MapFieldBuilderError),
     #[error(transparent)]
     ParsingMessage(#[from] crate::model::MessageBuilderError),
+    #[error(transparent)]
+    ParsingAggregateField(#[from] crate::model::AggregateFieldBuilderError),
+    #[error(transparent)]
+    ParsingGroupField(#[from] crate::model::GroupFieldBuilderError),
+    #[error(transparent)]
+    ParsingExtend(#[from] crate::model::ExtendBuilderError),
+}
+impl Error {
+    /// Describes this error as a [`Diagnostic`](crate::Diagnostic), with its location when known.
+    pub fn to_diagnostic(&self) -> crate::Diagnostic {
+        crate::Diagnostic::from(self)
+    }
+
+    /// The error raised by a visitor, if this is one of type `E`.
+    pub fn visitor_error<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        match self {
+            Error::Visitor(error) => error.downcast_ref(),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,125 @@
+//! Owned transformation of a model, in the style of `syn::fold`.
+//!
+//! Each method of [`Fold`] defaults to the free function of the same name, which folds the
+//! children of the node and returns it. Overriding a method replaces the node with whatever it
+//! returns.
+
+use std::borrow::Cow;
+
+use crate::model::{
+    Aggregate, AggregateField, AggregateFieldName, AggregateValue, Comment, Constant, Enum,
+    EnumItem, Extend, ExtensionRange, Extensions, Field, GroupField, Ident, Import, MapField,
+    MapFieldKeyType, Message, MessageReference, NormalField, OneOfField, OneOfFieldItem, Option,
+    OptionName, OptionNamePart, Package, Proto, ReservedData, ReservedItems, Service, ServiceRpc,
+    ServiceRpcField, Syntax, Type, Visibility,
+};
+
+/// Generates [`Fold`] and its free functions from [`crate::traverse::traversal`].
+macro_rules! fold {
+    (@field $f:ident, $node:ident, options) => {
+        $node.options = Cow::Owned($f.fold_options($node.options.into_owned()));
+    };
+    (@field $f:ident, $node:ident, $field:ident) => {
+        $node.$field = Foldable::fold($node.$field, $f);
+    };
+    (
+        $(struct $struct:ident $(<$struct_lt:lifetime>)? (
+            $visit_struct:ident, $visit_struct_mut:ident, $fold_struct:ident $(,)?
+        ) {
+            $($field:ident),* $(,)?
+        })*
+        $(enum $enum:ident $(<$enum_lt:lifetime>)? (
+            $visit_enum:ident, $visit_enum_mut:ident, $fold_enum:ident $(,)?
+        ) {
+            $($variant:ident($($payload:ident),*)),* $(,)?
+        })*
+        $(leaf $leaf:ident $(<$leaf_lt:lifetime>)? (
+            $visit_leaf:ident, $visit_leaf_mut:ident, $fold_leaf:ident $(,)?
+        );)*
+        $(value $value:ty;)*
+    ) => {
+        /// Rebuilds every node of a model, taking it by value.
+        pub trait Fold<'a> {
+            /// Folds the options of a declaration, one at a time through [`Fold::fold_option`].
+            /// Overriding it allows adding or removing options.
+            fn fold_options(&mut self, node: Vec<Option<'a>>) -> Vec<Option<'a>> {
+                fold_options(self, node)
+            }
+            $(fn $fold_struct(&mut self, node: $struct$(<$struct_lt>)?) -> $struct$(<$struct_lt>)? {
+                $fold_struct(self, node)
+            })*
+            $(fn $fold_enum(&mut self, node: $enum$(<$enum_lt>)?) -> $enum$(<$enum_lt>)? {
+                $fold_enum(self, node)
+            })*
+            $(fn $fold_leaf(&mut self, node: $leaf$(<$leaf_lt>)?) -> $leaf$(<$leaf_lt>)? {
+                node
+            })*
+        }
+
+        $(pub fn $fold_struct<'a, F: Fold<'a> + ?Sized>(
+            f: &mut F,
+            mut node: $struct$(<$struct_lt>)?,
+        ) -> $struct$(<$struct_lt>)? {
+            $(fold!(@field f, node, $field);)*
+            node
+        }
+
+        impl<'a> Foldable<'a> for $struct$(<$struct_lt>)? {
+            fn fold<F: Fold<'a> + ?Sized>(self, f: &mut F) -> Self {
+                f.$fold_struct(self)
+            }
+        })*
+
+        $(pub fn $fold_enum<'a, F: Fold<'a> + ?Sized>(
+            f: &mut F,
+            node: $enum$(<$enum_lt>)?,
+        ) -> $enum$(<$enum_lt>)? {
+            match node {
+                $($enum::$variant($($payload),*) => {
+                    $enum::$variant($(Foldable::fold($payload, f)),*)
+                })*
+            }
+        }
+
+        impl<'a> Foldable<'a> for $enum$(<$enum_lt>)? {
+            fn fold<F: Fold<'a> + ?Sized>(self, f: &mut F) -> Self {
+                f.$fold_enum(self)
+            }
+        })*
+
+        $(impl<'a> Foldable<'a> for $leaf$(<$leaf_lt>)? {
+            fn fold<F: Fold<'a> + ?Sized>(self, f: &mut F) -> Self {
+                f.$fold_leaf(self)
+            }
+        })*
+
+        $(impl<'a> Foldable<'a> for $value {
+            fn fold<F: Fold<'a> + ?Sized>(self, _: &mut F) -> Self {
+                self
+            }
+        })*
+    };
+}
+crate::traverse::traversal!(fold);
+
+/// A field folded through the [`Fold`] method for its type.
+trait Foldable<'a>: Sized {
+    fn fold<F: Fold<'a> + ?Sized>(self, f: &mut F) -> Self;
+}
+
+impl<'a, T: Foldable<'a> + Clone> Foldable<'a> for Cow<'a, [T]> {
+    fn fold<F: Fold<'a> + ?Sized>(self, f: &mut F) -> Self {
+        Cow::Owned(
+            self.into_owned()
+                .into_iter()
+                .map(|item| item.fold(f))
+                .collect(),
+        )
+    }
+}
+
+pub fn fold_options<'a, F: Fold<'a> + ?Sized>(f: &mut F, node: Vec<Option<'a>>) -> Vec<Option<'a>> {
+    node.into_iter()
+        .map(|option| f.fold_option(option))
+        .collect()
+}
@@ -1,10 +1,30 @@
+extern crate self as harpi;
+pub mod descriptor;
+mod diagnostic;
+pub use diagnostic::*;
+mod equivalence;
+pub use equivalence::*;
 mod error;
+pub mod fold;
+mod linker;
+mod loader;
+pub use linker::*;
+pub use loader::*;
 pub mod model;
 pub(crate) mod parser;
 pub use error::*;
 pub use parser::*;
+mod pool;
+pub use pool::*;
+mod printer;
+pub use printer::*;
 mod syntax;
 pub use syntax::*;
+mod traverse;
+mod validate;
+pub use validate::*;
+pub mod visit;
+pub mod visit_mut;
 mod visitor;
 pub use derive::*;
 pub use visitor::*;
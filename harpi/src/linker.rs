@@ -0,0 +1,646 @@
+use std::collections::HashMap;
+
+use getter::Getter;
+
+use crate::{
+    Diagnostic, DiagnosticCode,
+    model::{Constant, Enum, Extend, Field, Ident, Message, OptionNamePart, Proto, Span, Type},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Package,
+    Message,
+    Enum,
+    /// Enum values are scoped like protobuf does, as siblings of their enum.
+    EnumValue,
+    Service,
+    Extension,
+}
+impl SymbolKind {
+    /// Whether names can be looked up inside symbols of this kind.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(self, SymbolKind::Package | SymbolKind::Message)
+    }
+
+    pub fn is_type(&self) -> bool {
+        matches!(self, SymbolKind::Message | SymbolKind::Enum)
+    }
+}
+
+/// A named declaration, identified by its fully qualified name without a leading dot.
+#[derive(Debug, Clone, PartialEq, Eq, Getter)]
+pub struct Symbol {
+    full_name: String,
+    kind: SymbolKind,
+    file: String,
+    span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'s> {
+    Resolved(&'s Symbol),
+    Unresolved,
+    /// The name matches several declarations, or the innermost scope matching its first part does
+    /// not declare the rest of it while an outer scope does.
+    Ambiguous(Vec<&'s Symbol>),
+}
+
+/// Every symbol declared by a set of files, indexed by fully qualified name.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<String, Vec<Symbol>>,
+    /// Enum declaring each enum value, by full name.
+    enums: HashMap<String, String>,
+    /// Scope and type name of each extension of a named type, by full name.
+    extensions: HashMap<String, (String, String)>,
+}
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, file: &str, proto: &Proto<'_>) -> Self {
+        self.add_file(file, proto);
+        self
+    }
+
+    pub fn add_file(&mut self, file: &str, proto: &Proto<'_>) {
+        let package = proto.package().value();
+        let mut prefix = String::new();
+        for part in package.split('.').filter(|part| !part.is_empty()) {
+            prefix = join(&prefix, part);
+            self.insert(
+                &prefix,
+                SymbolKind::Package,
+                file,
+                *proto.package().value_span(),
+            );
+        }
+        for message in proto.messages().iter() {
+            self.add_message(file, package, message);
+        }
+        for value in proto.enums().iter() {
+            self.add_enum(file, package, value);
+        }
+        for service in proto.services().iter() {
+            self.insert_ident(package, service.name(), SymbolKind::Service, file);
+        }
+        for extend in proto.extends().iter() {
+            self.add_extend(file, package, extend);
+        }
+    }
+
+    fn add_message(&mut self, file: &str, scope: &str, message: &Message<'_>) {
+        let full_name = join(scope, message.name().value());
+        self.insert(&full_name, SymbolKind::Message, file, message.name().span());
+        for nested in message.messages().iter() {
+            self.add_message(file, &full_name, nested);
+        }
+        for field in message.fields().iter() {
+            if let Field::Group(group) = field {
+                self.add_message(file, &full_name, group.message());
+            }
+        }
+        for value in message.enums().iter() {
+            self.add_enum(file, &full_name, value);
+        }
+        for extend in message.extends().iter() {
+            self.add_extend(file, &full_name, extend);
+        }
+    }
+
+    fn add_enum(&mut self, file: &str, scope: &str, value: &Enum<'_>) {
+        let full_name = join(scope, value.name().value());
+        self.insert(&full_name, SymbolKind::Enum, file, value.name().span());
+        for item in value.fields().iter() {
+            let name = join(scope, item.name().value());
+            self.insert(&name, SymbolKind::EnumValue, file, item.name().span());
+            self.enums.insert(name, full_name.clone());
+        }
+    }
+
+    fn add_extend(&mut self, file: &str, scope: &str, extend: &Extend<'_>) {
+        for field in extend.fields().iter() {
+            match field {
+                Field::Normal(field) => {
+                    if let Type::Reference(ty) = field.ty() {
+                        self.extensions.insert(
+                            join(scope, field.name().value()),
+                            (scope.to_string(), ty.to_string()),
+                        );
+                    }
+                    self.insert_ident(scope, field.name(), SymbolKind::Extension, file)
+                }
+                Field::Group(group) => {
+                    let name = group.name().value().to_lowercase();
+                    self.insert(
+                        &join(scope, &name),
+                        SymbolKind::Extension,
+                        file,
+                        group.name().span(),
+                    );
+                    self.add_message(file, scope, group.message());
+                }
+                Field::OneOf(_) | Field::Map(_) => {}
+            }
+        }
+    }
+
+    fn insert_ident(&mut self, scope: &str, name: &Ident<'_>, kind: SymbolKind, file: &str) {
+        self.insert(&join(scope, name.value()), kind, file, name.span());
+    }
+
+    fn insert(&mut self, full_name: &str, kind: SymbolKind, file: &str, span: Span) {
+        let symbols = self.symbols.entry(full_name.to_string()).or_default();
+        // Packages are declared once per file, but describe a single namespace.
+        if kind == SymbolKind::Package && symbols.iter().any(|symbol| symbol.kind == kind) {
+            return;
+        }
+        symbols.push(Symbol {
+            full_name: full_name.to_string(),
+            kind,
+            file: file.to_string(),
+            span,
+        });
+    }
+
+    /// Symbols declared with the fully qualified `full_name`.
+    pub fn get(&self, full_name: &str) -> &[Symbol] {
+        self.symbols
+            .get(full_name.trim_start_matches('.'))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.values().flatten()
+    }
+
+    /// Resolves a type name as written in `scope`, the fully qualified name of the message or
+    /// package it appears in. Names starting with `.` are fully qualified. Otherwise, scopes are
+    /// searched from the innermost outwards, following protobuf scoping rules.
+    pub fn resolve(&self, scope: &str, name: &str) -> Resolution<'_> {
+        if let Some(full_name) = name.strip_prefix('.') {
+            return self.exact(full_name);
+        }
+        let (first, rest) = match name.split_once('.') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        let mut scope = Some(scope.trim_start_matches('.'));
+        while let Some(current) = scope {
+            let candidate = join(current, first);
+            let found = self.get(&candidate).iter().any(|symbol| match rest {
+                Some(_) => symbol.kind.is_aggregate(),
+                None => symbol.kind.is_type(),
+            });
+            if found {
+                let Some(rest) = rest else {
+                    return self.exact(&candidate);
+                };
+                return match self.exact(&join(&candidate, rest)) {
+                    Resolution::Unresolved => {
+                        match parent(current).map(|outer| self.resolve(outer, name)) {
+                            Some(Resolution::Resolved(symbol)) => {
+                                Resolution::Ambiguous(vec![symbol])
+                            }
+                            _ => Resolution::Unresolved,
+                        }
+                    }
+                    resolution => resolution,
+                };
+            }
+            scope = parent(current);
+        }
+        Resolution::Unresolved
+    }
+
+    /// The value `name` of the enum with the fully qualified name `enumeration`.
+    pub fn enum_value(&self, enumeration: &str, name: &str) -> Option<&Symbol> {
+        let enumeration = enumeration.trim_start_matches('.');
+        let full_name = join(parent(enumeration)?, name);
+        if self.enums.get(&full_name)? != enumeration {
+            return None;
+        }
+        self.get(&full_name)
+            .iter()
+            .find(|symbol| symbol.kind == SymbolKind::EnumValue)
+    }
+
+    /// Enum type of the extension `name` written in an option name in `scope`, when the extension
+    /// and its type both resolve. Extensions are searched from the innermost scope outwards.
+    fn extension_enum(&self, scope: &str, name: &Ident<'_>) -> Option<&Symbol> {
+        let (extension_scope, ty) = if name.relative() {
+            self.extensions.get(name.value().trim_start_matches('.'))?
+        } else {
+            let mut scope = Some(scope);
+            loop {
+                let current = scope?;
+                if let Some(extension) = self.extensions.get(&join(current, name.value())) {
+                    break extension;
+                }
+                scope = parent(current);
+            }
+        };
+        match self.resolve(extension_scope, ty) {
+            Resolution::Resolved(symbol) if symbol.kind == SymbolKind::Enum => Some(symbol),
+            _ => None,
+        }
+    }
+
+    /// Resolves `name`, honoring [`Ident::relative`] as a leading dot.
+    pub fn resolve_ident(&self, scope: &str, name: &Ident<'_>) -> Resolution<'_> {
+        if name.relative() {
+            self.exact(name.value().trim_start_matches('.'))
+        } else {
+            self.resolve(scope, name.value())
+        }
+    }
+
+    fn exact(&self, full_name: &str) -> Resolution<'_> {
+        match self.get(full_name) {
+            [] => Resolution::Unresolved,
+            [symbol] => Resolution::Resolved(symbol),
+            symbols => Resolution::Ambiguous(symbols.iter().collect()),
+        }
+    }
+}
+
+/// A type name, or an enum value set as a field default or option, written in a file along with
+/// the declaration it refers to.
+#[derive(Debug, Clone, Getter)]
+pub struct LinkedReference {
+    file: String,
+    scope: String,
+    name: String,
+    span: Span,
+    target: Option<Symbol>,
+}
+
+/// Result of linking a set of files: the symbols they declare and every reference they contain,
+/// resolved when possible.
+#[derive(Debug, Clone, Default)]
+pub struct Linked {
+    symbols: SymbolTable,
+    references: Vec<LinkedReference>,
+    /// Index of the reference at each span of each file, or `None` when several share it.
+    spans: HashMap<(String, Span), Option<usize>>,
+    diagnostics: Vec<Diagnostic>,
+}
+impl Linked {
+    pub fn symbols(&self) -> &SymbolTable {
+        &self.symbols
+    }
+
+    pub fn references(&self) -> &Vec<LinkedReference> {
+        &self.references
+    }
+
+    pub fn diagnostics(&self) -> &Vec<Diagnostic> {
+        &self.diagnostics
+    }
+
+    /// The reference written at `span` in `file`. Spans tell references apart, so when several
+    /// references of a file share a span, as in models built by hand with default spans, none of
+    /// them is returned: look for them in [`Linked::references`] instead.
+    pub fn reference(&self, file: &str, span: Span) -> Option<&LinkedReference> {
+        let index = (*self.spans.get(&(file.to_string(), span))?)?;
+        Some(&self.references[index])
+    }
+
+    /// Declaration referred to by the name written at `span` in `file`.
+    pub fn target(&self, file: &str, span: Span) -> Option<&Symbol> {
+        self.reference(file, span)?.target.as_ref()
+    }
+
+    fn push(&mut self, reference: LinkedReference) {
+        let key = (reference.file.clone(), reference.span);
+        let index = self.references.len();
+        self.spans
+            .entry(key)
+            .and_modify(|index| *index = None)
+            .or_insert(Some(index));
+        self.references.push(reference);
+    }
+}
+
+/// Resolves the type names of a set of files against each other. Files only see the files
+/// registered as their imports with [`Linker::with_imports`]; without any, every file sees every
+/// other file.
+#[derive(Debug, Clone, Default)]
+pub struct Linker<'p, 'a> {
+    pub(crate) files: Vec<(&'p str, &'p Proto<'a>)>,
+    imports: HashMap<&'p str, Vec<&'p str>>,
+}
+impl<'p, 'a> Linker<'p, 'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, file: &'p str, proto: &'p Proto<'a>) -> Self {
+        self.files.push((file, proto));
+        self
+    }
+
+    /// Restricts the files visible from `file`, such as those returned by
+    /// [`SourceSet::visible_imports`](crate::SourceSet::visible_imports).
+    pub fn with_imports(mut self, file: &'p str, imports: Vec<&'p str>) -> Self {
+        self.imports.insert(file, imports);
+        self
+    }
+
+    pub fn link(&self) -> Linked {
+        let mut symbols = SymbolTable::new();
+        for (file, proto) in &self.files {
+            symbols.add_file(file, proto);
+        }
+        let mut linked = Linked {
+            symbols,
+            ..Default::default()
+        };
+        for (file, proto) in &self.files {
+            let mut context = Context {
+                linked: &mut linked,
+                file,
+                imports: self.imports.get(file),
+            };
+            context.link_proto(proto);
+        }
+        linked
+    }
+}
+
+/// Links a single file.
+pub fn link(proto: &Proto<'_>) -> Linked {
+    Linker::new().with_file("", proto).link()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    Type,
+    Message,
+}
+
+struct Context<'l, 'p> {
+    linked: &'l mut Linked,
+    file: &'p str,
+    imports: Option<&'p Vec<&'p str>>,
+}
+impl Context<'_, '_> {
+    fn link_proto(&mut self, proto: &Proto<'_>) {
+        let package = proto.package().value();
+        self.link_options(package, proto.options(), None);
+        for message in proto.messages().iter() {
+            self.link_message(package, message);
+        }
+        for value in proto.enums().iter() {
+            self.link_enum(package, value);
+        }
+        for extend in proto.extends().iter() {
+            self.link_extend(package, extend);
+        }
+        for service in proto.services().iter() {
+            let full_name = join(package, service.name().value());
+            self.link_options(&full_name, service.options(), None);
+            for rpc in service.rpcs().iter() {
+                for field in [rpc.input(), rpc.output()] {
+                    let value = field.value();
+                    self.link(package, value.value(), *value.span(), Expected::Message);
+                }
+                self.link_options(&full_name, rpc.options(), None);
+            }
+        }
+    }
+
+    fn link_message(&mut self, scope: &str, message: &Message<'_>) {
+        let full_name = join(scope, message.name().value());
+        self.link_options(&full_name, message.options(), None);
+        self.link_fields(&full_name, message.fields());
+        for nested in message.messages().iter() {
+            self.link_message(&full_name, nested);
+        }
+        for value in message.enums().iter() {
+            self.link_enum(&full_name, value);
+        }
+        for extensions in message.extensions().iter() {
+            self.link_options(&full_name, extensions.options(), None);
+        }
+        for extend in message.extends().iter() {
+            self.link_extend(&full_name, extend);
+        }
+    }
+
+    fn link_enum(&mut self, scope: &str, value: &Enum<'_>) {
+        let full_name = join(scope, value.name().value());
+        self.link_options(&full_name, value.options(), None);
+        for item in value.fields().iter() {
+            self.link_options(&full_name, item.options(), None);
+        }
+    }
+
+    fn link_extend(&mut self, scope: &str, extend: &Extend<'_>) {
+        let extendee = extend.extendee();
+        self.link(scope, extendee.value(), *extendee.span(), Expected::Message);
+        self.link_fields(scope, extend.fields());
+    }
+
+    fn link_fields(&mut self, scope: &str, fields: &[Field<'_>]) {
+        for field in fields {
+            match field {
+                Field::Normal(field) => {
+                    let ty = self.link_type(scope, field.ty(), *field.ty_span());
+                    self.link_options(scope, field.options(), ty.as_ref());
+                }
+                Field::OneOf(field) => {
+                    self.link_options(scope, field.options(), None);
+                    for item in field.fields().iter() {
+                        let ty = self.link_type(scope, item.ty(), *item.ty_span());
+                        self.link_options(scope, item.options(), ty.as_ref());
+                    }
+                }
+                Field::Map(field) => {
+                    self.link_type(scope, field.value_ty(), *field.value_ty_span());
+                    self.link_options(scope, field.options(), None);
+                }
+                Field::Group(group) => {
+                    self.link_options(scope, group.options(), None);
+                    self.link_message(scope, group.message());
+                }
+            }
+        }
+    }
+
+    fn link_type(&mut self, scope: &str, ty: &Type<'_>, span: Span) -> Option<Symbol> {
+        match ty {
+            Type::Reference(name) => self.link(scope, name, span, Expected::Type),
+            _ => None,
+        }
+    }
+
+    /// Links the enum values set by `options` in `scope`: the `default` of a field of type `ty`,
+    /// and the custom options whose extension is of an enum type.
+    fn link_options(
+        &mut self,
+        scope: &str,
+        options: &[crate::model::Option<'_>],
+        ty: Option<&Symbol>,
+    ) {
+        for option in options {
+            let Constant::Ident(value) = option.value() else {
+                continue;
+            };
+            let enumeration = match &option.name().parts()[..] {
+                _ if option.name().is("default") => {
+                    ty.filter(|symbol| symbol.kind == SymbolKind::Enum).cloned()
+                }
+                [OptionNamePart::Extension(extension)] => self
+                    .linked
+                    .symbols
+                    .extension_enum(scope, extension)
+                    .cloned(),
+                _ => None,
+            };
+            if let Some(enumeration) = enumeration {
+                self.link_value(scope, &enumeration, value, *option.value_span());
+            }
+        }
+    }
+
+    fn link_value(&mut self, scope: &str, enumeration: &Symbol, value: &Ident<'_>, span: Span) {
+        let target = self
+            .linked
+            .symbols
+            .enum_value(&enumeration.full_name, value.value())
+            .cloned();
+        let diagnostic = target.is_none().then(|| {
+            Diagnostic::error(
+                DiagnosticCode::UnresolvedReference,
+                format!(
+                    "`{value}` is not a value of enum `.{}`",
+                    enumeration.full_name
+                ),
+                span,
+            )
+        });
+        self.push(scope, value.value(), span, target, diagnostic);
+    }
+
+    fn link(&mut self, scope: &str, name: &str, span: Span, expected: Expected) -> Option<Symbol> {
+        let resolution = self.linked.symbols.resolve(scope, name);
+        let (target, diagnostic) = match resolution {
+            Resolution::Resolved(symbol) => {
+                let symbol = symbol.clone();
+                let diagnostic = self.check(name, &symbol, span, expected);
+                (Some(symbol), diagnostic)
+            }
+            Resolution::Unresolved => (
+                None,
+                Some(Diagnostic::error(
+                    DiagnosticCode::UnresolvedReference,
+                    format!("cannot find type `{name}` in scope `{scope}`"),
+                    span,
+                )),
+            ),
+            Resolution::Ambiguous(symbols) => {
+                let diagnostic = symbols.iter().fold(
+                    Diagnostic::error(
+                        DiagnosticCode::AmbiguousReference,
+                        format!("type `{name}` is ambiguous in scope `{scope}`"),
+                        span,
+                    ),
+                    |diagnostic, symbol| {
+                        diagnostic.with_note(format!(
+                            "could refer to `.{}` declared in `{}`",
+                            symbol.full_name, symbol.file
+                        ))
+                    },
+                );
+                (None, Some(diagnostic))
+            }
+        };
+        self.push(scope, name, span, target.clone(), diagnostic);
+        target
+    }
+
+    fn push(
+        &mut self,
+        scope: &str,
+        name: &str,
+        span: Span,
+        target: Option<Symbol>,
+        diagnostic: Option<Diagnostic>,
+    ) {
+        if let Some(diagnostic) = diagnostic {
+            let diagnostic = match self.file {
+                "" => diagnostic,
+                file => diagnostic.with_file(file),
+            };
+            self.linked.diagnostics.push(diagnostic);
+        }
+        self.linked.push(LinkedReference {
+            file: self.file.to_string(),
+            scope: scope.to_string(),
+            name: name.to_string(),
+            span,
+            target,
+        });
+    }
+
+    fn check(
+        &self,
+        name: &str,
+        symbol: &Symbol,
+        span: Span,
+        expected: Expected,
+    ) -> Option<Diagnostic> {
+        let visible = symbol.file == self.file
+            || symbol.kind == SymbolKind::Package
+            || self
+                .imports
+                .is_none_or(|imports| imports.contains(&symbol.file.as_str()));
+        if !visible {
+            return Some(
+                Diagnostic::error(
+                    DiagnosticCode::UnresolvedReference,
+                    format!("type `{name}` is not imported"),
+                    span,
+                )
+                .with_note(format!(
+                    "`.{}` is declared in `{}`",
+                    symbol.full_name, symbol.file
+                )),
+            );
+        }
+        let valid = match expected {
+            Expected::Type => symbol.kind.is_type(),
+            Expected::Message => symbol.kind == SymbolKind::Message,
+        };
+        (!valid).then(|| {
+            let expected = match expected {
+                Expected::Type => "a message or enum",
+                Expected::Message => "a message",
+            };
+            Diagnostic::error(
+                DiagnosticCode::InvalidReference,
+                format!("`{name}` is not {expected}"),
+                span,
+            )
+        })
+    }
+}
+
+pub(crate) fn join(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+pub(crate) fn parent(scope: &str) -> Option<&str> {
+    match scope.rsplit_once('.') {
+        Some((parent, _)) => Some(parent),
+        None if scope.is_empty() => None,
+        None => Some(""),
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use getter::Getter;
+
+use crate::{Error, model::Proto, parse_file};
+
+/// Source of the contents of `.proto` files, looked up by import path such as
+/// `google/protobuf/descriptor.proto`. Missing files are reported with [`ErrorKind::NotFound`].
+pub trait SourceProvider {
+    fn load(&self, path: &str) -> io::Result<String>;
+}
+impl<T: SourceProvider + ?Sized> SourceProvider for &T {
+    fn load(&self, path: &str) -> io::Result<String> {
+        (**self).load(path)
+    }
+}
+
+/// Loads files from a list of include roots, like `protoc -I`. Roots are searched in order and the
+/// first match wins.
+#[derive(Debug, Clone, Default)]
+pub struct IncludePaths {
+    roots: Vec<PathBuf>,
+}
+impl IncludePaths {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.roots.push(root.into());
+        self
+    }
+}
+impl<P: Into<PathBuf>> FromIterator<P> for IncludePaths {
+    fn from_iter<T: IntoIterator<Item = P>>(iter: T) -> Self {
+        Self {
+            roots: iter.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+impl SourceProvider for IncludePaths {
+    fn load(&self, path: &str) -> io::Result<String> {
+        for root in &self.roots {
+            match std::fs::read_to_string(root.join(path)) {
+                Err(error) if error.kind() == ErrorKind::NotFound => continue,
+                result => return result,
+            }
+        }
+        Err(ErrorKind::NotFound.into())
+    }
+}
+
+/// Files held in memory, such as sources embedded with `include_str!`.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource {
+    files: HashMap<String, String>,
+}
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_file(mut self, path: impl Into<String>, source: impl Into<String>) -> Self {
+        self.files.insert(path.into(), source.into());
+        self
+    }
+}
+impl<P: Into<String>, S: Into<String>> FromIterator<(P, S)> for MemorySource {
+    fn from_iter<T: IntoIterator<Item = (P, S)>>(iter: T) -> Self {
+        Self {
+            files: iter
+                .into_iter()
+                .map(|(path, source)| (path.into(), source.into()))
+                .collect(),
+        }
+    }
+}
+impl SourceProvider for MemorySource {
+    fn load(&self, path: &str) -> io::Result<String> {
+        self.files
+            .get(path)
+            .cloned()
+            .ok_or_else(|| ErrorKind::NotFound.into())
+    }
+}
+
+/// An `import` statement resolved to a loaded file.
+#[derive(Debug, Clone, Getter)]
+pub struct SourceImport {
+    path: String,
+    public: bool,
+    weak: bool,
+}
+
+/// A loaded file along with the files it imports. Files are parsed once, while loading.
+#[derive(Debug, Clone, Getter)]
+pub struct SourceFile {
+    path: String,
+    source: String,
+    proto: Proto<'static>,
+    imports: Vec<SourceImport>,
+}
+
+/// Every file reachable from the loaded roots, each loaded once. Files come after the files they
+/// import.
+#[derive(Debug, Clone, Default)]
+pub struct SourceSet {
+    files: Vec<SourceFile>,
+    index: HashMap<String, usize>,
+}
+impl SourceSet {
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    pub fn get(&self, path: &str) -> Option<&SourceFile> {
+        self.index.get(path).map(|index| &self.files[*index])
+    }
+
+    /// Paths of the files whose declarations `path` can see: its direct imports, plus the files
+    /// they re-export through `import public`, transitively.
+    pub fn visible_imports(&self, path: &str) -> Vec<&str> {
+        let mut visible = Vec::new();
+        let mut pending = self
+            .get(path)
+            .map(|file| file.imports.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        pending.reverse();
+        while let Some(import) = pending.pop() {
+            if visible.contains(&import.path.as_str()) {
+                continue;
+            }
+            visible.push(import.path.as_str());
+            if let Some(file) = self.get(&import.path) {
+                pending.extend(file.imports.iter().rev().filter(|import| import.public));
+            }
+        }
+        visible
+    }
+
+    /// Every parsed file with its path, in the same order as [`SourceSet::files`].
+    pub fn protos(&self) -> impl Iterator<Item = (&str, &Proto<'static>)> {
+        self.files
+            .iter()
+            .map(|file| (file.path.as_str(), &file.proto))
+    }
+
+    fn push(&mut self, file: SourceFile) {
+        self.index.insert(file.path.clone(), self.files.len());
+        self.files.push(file);
+    }
+}
+
+/// Loads `.proto` files and, transitively, everything they import.
+#[derive(Debug, Clone)]
+pub struct Loader<P> {
+    provider: P,
+}
+impl<P: SourceProvider> Loader<P> {
+    pub fn new(provider: P) -> Self {
+        Self { provider }
+    }
+
+    pub fn load(&self, path: &str) -> Result<SourceSet, Error> {
+        self.load_all([path])
+    }
+
+    pub fn load_all<'p>(
+        &self,
+        paths: impl IntoIterator<Item = &'p str>,
+    ) -> Result<SourceSet, Error> {
+        let mut set = SourceSet::default();
+        let mut chain = Vec::new();
+        for path in paths {
+            self.visit(path, &mut chain, &mut set)?;
+        }
+        Ok(set)
+    }
+
+    fn visit(&self, path: &str, chain: &mut Vec<String>, set: &mut SourceSet) -> Result<(), Error> {
+        if let Some(position) = chain.iter().position(|file| file == path) {
+            let mut cycle = chain[position..].to_vec();
+            cycle.push(path.to_string());
+            return Err(Error::ImportCycle(cycle));
+        }
+        if set.get(path).is_some() {
+            return Ok(());
+        }
+        let source = match self.provider.load(path) {
+            Ok(source) => source,
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                let mut chain = chain.clone();
+                chain.push(path.to_string());
+                return Err(Error::MissingImport(chain));
+            }
+            Err(error) => return Err(Error::LoadingFile(path.to_string(), error)),
+        };
+        let proto = parse_file(&source)
+            .map_err(|error| Error::ParsingFile(path.to_string(), error.into()))?
+            .into_owned();
+        let imports = proto
+            .imports()
+            .iter()
+            .map(|import| SourceImport {
+                path: import.value().to_string(),
+                public: *import.public(),
+                weak: *import.weak(),
+            })
+            .collect::<Vec<_>>();
+
+        chain.push(path.to_string());
+        for import in &imports {
+            self.visit(&import.path, chain, set)?;
+        }
+        chain.pop();
+        set.push(SourceFile {
+            path: path.to_string(),
+            source,
+            proto,
+            imports,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use super::{
+    Comment, CommentKind, Enum, EnumItem, Extend, Extensions, Field, GroupField, Import, MapField,
+    Message, NormalField, OneOfField, OneOfFieldItem, Option, Package, ReservedItems, Service,
+    ServiceRpc, Syntax,
+};
+
+impl<'a> Comment<'a> {
+    /// Whether the comment starts with a doc marker: `///` for line comments, `/**` for blocks.
+    pub fn is_doc(&self) -> bool {
+        let value = self.value();
+        match self.kind() {
+            CommentKind::Line => value.starts_with('/') && !value.starts_with("//"),
+            CommentKind::Block => value.starts_with('*') && !value.starts_with("**"),
+        }
+    }
+
+    /// Whether this is a line comment on the line right after `previous`, also a line comment, so
+    /// that both read as one paragraph. Comments without a span are taken as adjacent.
+    pub fn continues(&self, previous: &Comment<'_>) -> bool {
+        *self.kind() == CommentKind::Line
+            && *previous.kind() == CommentKind::Line
+            && self.span().start().line() <= previous.span().end().line() + 1
+    }
+
+    /// The comment text without its markers, block gutters and common indentation.
+    pub fn text(&self) -> String {
+        normalize(self.lines())
+    }
+
+    /// Lines of the comment without the doc marker and, for block comments, without the `*`
+    /// gutter of continuation lines.
+    fn lines(&self) -> Vec<&str> {
+        let value = self.value().as_ref();
+        let value = if self.is_doc() { &value[1..] } else { value };
+        let lines = value
+            .split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line))
+            .collect::<Vec<_>>();
+        if *self.kind() == CommentKind::Line {
+            return lines;
+        }
+        let gutter = lines
+            .iter()
+            .skip(1)
+            .filter(|line| !line.trim().is_empty())
+            .all(|line| line.trim_start().starts_with('*'));
+        if !gutter {
+            return lines;
+        }
+        let mut lines = lines.into_iter();
+        lines
+            .next()
+            .into_iter()
+            .chain(lines.map(|line| {
+                let line = line.trim_start();
+                line.strip_prefix('*').unwrap_or(line)
+            }))
+            .collect()
+    }
+}
+
+/// Merges adjacent line comments and returns the text of each paragraph, normalized like
+/// [`Comment::text`]. Block comments always form a paragraph of their own.
+pub fn paragraphs(comments: &[Comment<'_>]) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    let mut lines = Vec::new();
+    let mut previous: std::option::Option<&Comment<'_>> = None;
+    for comment in comments {
+        if previous.is_some_and(|previous| !comment.continues(previous)) {
+            paragraphs.push(normalize(std::mem::take(&mut lines)));
+        }
+        lines.extend(comment.lines());
+        previous = Some(comment);
+    }
+    if previous.is_some() {
+        paragraphs.push(normalize(lines));
+    }
+    paragraphs.retain(|paragraph| !paragraph.is_empty());
+    paragraphs
+}
+
+/// Trims trailing whitespace and surrounding blank lines, then removes the indentation shared by
+/// the remaining lines.
+fn normalize(lines: Vec<&str>) -> String {
+    let lines = lines.into_iter().map(str::trim_end).collect::<Vec<_>>();
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return String::new();
+    };
+    let lines = &lines[first..=last];
+    let indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A declaration documented by its comments.
+pub trait Documented<'a> {
+    /// The leading comments, or the trailing ones when there are none.
+    fn doc_comments(&self) -> &[Comment<'a>];
+
+    /// The documentation as one string, paragraphs separated by a blank line. When some of the
+    /// comments carry a doc marker, only those are used.
+    fn doc(&self) -> std::option::Option<String> {
+        let comments = self.doc_comments();
+        let paragraphs = if comments.iter().any(Comment::is_doc) {
+            let marked = comments
+                .iter()
+                .filter(|comment| comment.is_doc())
+                .cloned()
+                .collect::<Vec<_>>();
+            paragraphs(&marked)
+        } else {
+            paragraphs(comments)
+        };
+        let doc = paragraphs.join("\n\n");
+        (!doc.is_empty()).then_some(doc)
+    }
+}
+
+macro_rules! documented {
+    ($($ty:ident),*) => {
+        $(impl<'a> Documented<'a> for $ty<'a> {
+            fn doc_comments(&self) -> &[Comment<'a>] {
+                if self.comments.is_empty() {
+                    &self.trailing_comments
+                } else {
+                    &self.comments
+                }
+            }
+        })*
+    };
+}
+documented!(
+    Syntax,
+    Package,
+    Import,
+    Option,
+    Message,
+    Enum,
+    EnumItem,
+    Service,
+    ServiceRpc,
+    ReservedItems,
+    Extensions,
+    Extend,
+    NormalField,
+    MapField,
+    OneOfField,
+    OneOfFieldItem,
+    GroupField
+);
+
+impl<'a> Documented<'a> for Field<'a> {
+    fn doc_comments(&self) -> &[Comment<'a>] {
+        match self {
+            Field::Normal(field) => field.doc_comments(),
+            Field::OneOf(field) => field.doc_comments(),
+            Field::Map(field) => field.doc_comments(),
+            Field::Group(field) => field.doc_comments(),
+        }
+    }
+}
@@ -1,2 +1,20 @@
+//! The syntax tree of a `.proto` file.
+//!
+//! With the `serde` feature, every type here implements `Serialize` and `Deserialize`. Structs
+//! map to objects keyed by their field names, and the tuple structs [`Comment`] and
+//! [`MessageReference`] to arrays. Enums holding data ([`Field`], [`Type`], [`Constant`],
+//! [`ReservedData`], [`OptionNamePart`], [`AggregateFieldName`] and [`AggregateValue`]) are
+//! adjacently tagged as `{"kind": ..., "value": ...}`, with the lowercased variant name as `kind`
+//! (`"oneof"`, `"uint32"`, `"reference"`, ...) and no `value` for unit variants. The other enums
+//! are plain lowercased strings. Deserialized values always own their data.
+
+mod doc;
+pub use doc::*;
 mod model;
 pub use model::*;
+mod options;
+pub use options::*;
+mod owned;
+pub use owned::*;
+mod span;
+pub use span::*;
@@ -1,196 +1,575 @@
 use std::{
     borrow::Cow,
     fmt::{Display, Write},
+    hash::{Hash, Hasher},
+    mem,
 };
 
 use builder::Builder;
 use getter::Getter;
-#[derive(Debug, Clone, Builder, Getter)]
+
+use super::Span;
+/// A parsed file.
+///
+/// Names, references and comments compare without their span, but the declarations holding them
+/// still compare their own `span` fields, so the same file parsed from differently laid out text
+/// is not equal. Use [`crate::equivalent`] to compare files regardless of layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Proto<'a> {
-    syntax: Syntax<'a>,
-    package: Package<'a>,
+    pub(crate) syntax: Syntax<'a>,
+    pub(crate) package: Package<'a>,
     #[builder(setter_name = "import")]
-    imports: Cow<'a, [Import<'a>]>,
+    pub(crate) imports: Cow<'a, [Import<'a>]>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [Option<'a>]>,
+    pub(crate) options: Cow<'a, [Option<'a>]>,
     #[builder(setter_name = "message")]
-    messages: Cow<'a, [Message<'a>]>,
+    pub(crate) messages: Cow<'a, [Message<'a>]>,
     #[builder(setter_name = "enum")]
-    enums: Cow<'a, [Enum<'a>]>,
+    pub(crate) enums: Cow<'a, [Enum<'a>]>,
     #[builder(setter_name = "service")]
-    services: Cow<'a, [Service<'a>]>,
+    pub(crate) services: Cow<'a, [Service<'a>]>,
+    #[builder(setter_name = "extend")]
+    pub(crate) extends: Cow<'a, [Extend<'a>]>,
 }
-#[derive(Debug, Clone, Default, Builder, Getter)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Package<'a> {
     #[builder(into)]
-    value: Cow<'a, str>,
+    pub(crate) value: Cow<'a, str>,
+    pub(crate) value_span: Span,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
+}
+impl<'a> Package<'a> {
+    pub fn set_value(&mut self, value: impl Into<Cow<'a, str>>) {
+        self.value = value.into();
+    }
 }
-#[derive(Debug, Clone, Default, Builder, Getter)]
+/// The `syntax` or `edition` statement of a file. For editions, `value` holds the edition name,
+/// such as `2023`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Syntax<'a> {
     #[builder(into)]
-    value: Cow<'a, str>,
+    pub(crate) value: Cow<'a, str>,
+    pub(crate) value_span: Span,
+    pub(crate) edition: bool,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Default, Builder, Getter)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Import<'a> {
-    weak: bool,
-    public: bool,
+    pub(crate) weak: bool,
+    pub(crate) public: bool,
+    /// `import option` (edition 2024), only made available for resolving options.
+    pub(crate) option: bool,
     #[builder(into)]
-    value: Cow<'a, str>,
+    pub(crate) value: Cow<'a, str>,
+    pub(crate) value_span: Span,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct Option<'a> {
-    name: Ident<'a>,
-    value: Constant<'a>,
+    pub(crate) name: OptionName<'a>,
+    pub(crate) value: Constant<'a>,
+    #[builder(optional)]
+    pub(crate) value_span: Span,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
+}
+impl Option<'_> {
+    /// Name of the editions feature set by this option, such as `field_presence` for
+    /// `features.field_presence`.
+    pub fn feature(&self) -> std::option::Option<&str> {
+        match &self.name.parts[..] {
+            [OptionNamePart::Ident(first), OptionNamePart::Ident(feature)]
+                if first.value() == "features" =>
+            {
+                Some(feature.value())
+            }
+            _ => None,
+        }
+    }
+}
+/// The name of an option: plain identifiers and parenthesized extension names separated by dots,
+/// such as `java_package`, `features.field_presence` or `(google.api.http).post`.
+#[derive(Debug, Clone, Default, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptionName<'a> {
+    pub(crate) parts: Cow<'a, [OptionNamePart<'a>]>,
+    pub(crate) span: Span,
+}
+impl<'a> OptionName<'a> {
+    pub fn new(parts: impl Into<Cow<'a, [OptionNamePart<'a>]>>) -> Self {
+        Self {
+            parts: parts.into(),
+            span: Span::default(),
+        }
+    }
+
+    /// A name made of plain identifiers, such as `features.field_presence`.
+    pub fn plain(name: impl Into<Cow<'a, str>>) -> Self {
+        let parts: Vec<_> = match name.into() {
+            Cow::Borrowed(name) => name
+                .split('.')
+                .map(|part| OptionNamePart::Ident(Ident::new(false, part)))
+                .collect(),
+            Cow::Owned(name) => name
+                .split('.')
+                .map(|part| OptionNamePart::Ident(Ident::new(false, part.to_owned())))
+                .collect(),
+        };
+        Self::new(parts)
+    }
+
+    /// A name made of a single extension, such as `(.my.ext)` when `relative` is set.
+    pub fn extension(relative: bool, name: impl Into<Cow<'a, str>>) -> Self {
+        Self::new(vec![OptionNamePart::Extension(Ident::new(relative, name))])
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+
+    /// Whether this name is made of plain identifiers only, which spell `name` once joined with
+    /// dots.
+    pub fn is(&self, name: &str) -> bool {
+        let mut names = name.split('.');
+        self.parts.iter().all(|part| match part {
+            OptionNamePart::Ident(ident) => names.next() == Some(ident.value()),
+            OptionNamePart::Extension(_) => false,
+        }) && names.next().is_none()
+    }
 }
-#[derive(Debug, Clone, Builder, Getter)]
+impl Display for OptionName<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, part) in self.parts.iter().enumerate() {
+            if index > 0 {
+                f.write_char('.')?;
+            }
+            match part {
+                OptionNamePart::Ident(ident) => write!(f, "{ident}")?,
+                OptionNamePart::Extension(ident) => write!(f, "({ident})")?,
+            }
+        }
+        Ok(())
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
+pub enum OptionNamePart<'a> {
+    /// A plain identifier, such as `java_package` or the `post` of `(google.api.http).post`.
+    Ident(Ident<'a>),
+    /// A parenthesized extension name, such as `(google.api.http)`. The ident is relative when
+    /// the name starts with a dot, as in `(.my.ext)`.
+    Extension(Ident<'a>),
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Service<'a> {
-    name: Ident<'a>,
+    pub(crate) name: Ident<'a>,
     #[builder(setter_name = "rpc")]
-    rpcs: Cow<'a, [ServiceRpc<'a>]>,
+    pub(crate) rpcs: Cow<'a, [ServiceRpc<'a>]>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct ServiceRpc<'a> {
-    name: Ident<'a>,
-    input: ServiceRpcField<'a>,
-    output: ServiceRpcField<'a>,
+    pub(crate) name: Ident<'a>,
+    pub(crate) input: ServiceRpcField<'a>,
+    pub(crate) output: ServiceRpcField<'a>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct ServiceRpcField<'a> {
-    value: MessageReference<'a>,
+    pub(crate) value: MessageReference<'a>,
     #[builder(optional)]
-    stream: bool,
+    pub(crate) stream: bool,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct Message<'a> {
-    name: Ident<'a>,
+    pub(crate) name: Ident<'a>,
+    #[builder(optional)]
+    pub(crate) visibility: Visibility,
     #[builder(setter_name = "field")]
-    fields: Cow<'a, [Field<'a>]>,
+    pub(crate) fields: Cow<'a, [Field<'a>]>,
     #[builder(setter_name = "enum")]
-    enums: Cow<'a, [Enum<'a>]>,
+    pub(crate) enums: Cow<'a, [Enum<'a>]>,
     #[builder(setter_name = "message")]
-    messages: Cow<'a, [Message<'a>]>,
+    pub(crate) messages: Cow<'a, [Message<'a>]>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "reserved")]
-    reserved: Cow<'a, [ReservedItems<'a>]>,
+    pub(crate) reserved: Cow<'a, [ReservedItems<'a>]>,
+    #[builder(setter_name = "extensions")]
+    pub(crate) extensions: Cow<'a, [Extensions<'a>]>,
+    #[builder(setter_name = "extend")]
+    pub(crate) extends: Cow<'a, [Extend<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Enum<'a> {
-    name: Ident<'a>,
+    pub(crate) name: Ident<'a>,
+    pub(crate) visibility: Visibility,
     #[builder(setter_name = "field")]
-    fields: Cow<'a, [EnumItem<'a>]>,
+    pub(crate) fields: Cow<'a, [EnumItem<'a>]>,
     #[builder(setter_name = "reserved")]
-    reserved: Cow<'a, [ReservedItems<'a>]>,
+    pub(crate) reserved: Cow<'a, [ReservedItems<'a>]>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [Option<'a>]>,
+    pub(crate) options: Cow<'a, [Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
+}
+/// Symbol visibility of a message or enum, set with `export` or `local` since edition 2024.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Visibility {
+    #[default]
+    Default,
+    Export,
+    Local,
 }
 
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct ReservedItems<'a> {
     #[builder(setter_name = "item")]
-    items: Cow<'a, [ReservedData<'a>]>,
+    pub(crate) items: Cow<'a, [ReservedData<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
 pub enum ReservedData<'a> {
     Range(i64, i64),
     Field(Ident<'a>),
 }
 
-#[derive(Debug, Clone, Builder, Getter)]
+/// Field numbers declared with `extensions` (proto2), available to `extend` blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extensions<'a> {
+    #[builder(setter_name = "range")]
+    pub(crate) ranges: Cow<'a, [ExtensionRange]>,
+    #[builder(setter_name = "option")]
+    pub(crate) options: Cow<'a, [Option<'a>]>,
+    #[builder(setter_name = "comment")]
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExtensionRange {
+    pub(crate) start: i64,
+    pub(crate) end: i64,
+    pub(crate) span: Span,
+}
+impl ExtensionRange {
+    pub fn new(start: i64, end: i64) -> Self {
+        Self {
+            start,
+            end,
+            span: Span::default(),
+        }
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
+}
+
+/// Fields added to an existing message through an `extend` block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[builder(required)]
+pub struct Extend<'a> {
+    pub(crate) extendee: MessageReference<'a>,
+    #[builder(setter_name = "field")]
+    pub(crate) fields: Cow<'a, [Field<'a>]>,
+    #[builder(setter_name = "comment")]
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
+}
+impl Extend<'_> {
+    /// Whether this block declares custom options, i.e. extends one of the
+    /// `google.protobuf.*Options` messages.
+    pub fn is_option_declaration(&self) -> bool {
+        const OPTIONS: [&str; 10] = [
+            "FileOptions",
+            "MessageOptions",
+            "FieldOptions",
+            "OneofOptions",
+            "EnumOptions",
+            "EnumValueOptions",
+            "ServiceOptions",
+            "MethodOptions",
+            "ExtensionRangeOptions",
+            "FeatureSet",
+        ];
+        let extendee = self.extendee.value();
+        let extendee = extendee.strip_prefix('.').unwrap_or(extendee);
+        extendee
+            .strip_prefix("google.protobuf.")
+            .is_some_and(|name| OPTIONS.contains(&name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnumItem<'a> {
-    name: Ident<'a>,
-    number: i64,
+    pub(crate) name: Ident<'a>,
+    pub(crate) number: i64,
+    pub(crate) number_span: Span,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [Option<'a>]>,
+    pub(crate) options: Cow<'a, [Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
 pub enum Field<'a> {
     Normal(NormalField<'a>),
     OneOf(OneOfField<'a>),
     Map(MapField<'a>),
+    Group(GroupField<'a>),
 }
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct NormalField<'a> {
     #[builder(optional)]
-    repeated: bool,
+    pub(crate) repeated: bool,
+    #[builder(optional)]
+    pub(crate) optional: bool,
+    #[builder(optional)]
+    pub(crate) required: bool,
+    pub(crate) ty: Type<'a>,
+    #[builder(optional)]
+    pub(crate) ty_span: Span,
+    pub(crate) name: Ident<'a>,
+    pub(crate) number: u64,
     #[builder(optional)]
-    optional: bool,
-    ty: Type<'a>,
-    name: Ident<'a>,
-    number: u64,
+    pub(crate) number_span: Span,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
+}
+impl<'a> NormalField<'a> {
+    /// Value of the proto2 `[default = ...]` pseudo-option, if present.
+    pub fn default_value(&self) -> std::option::Option<&Constant<'a>> {
+        self.options
+            .iter()
+            .find(|option| option.name.is("default"))
+            .map(|option| &option.value)
+    }
+}
+
+/// A proto2 `group`, declaring both a field and the nested message type it holds. The field
+/// shares its name with the message.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[builder(required)]
+pub struct GroupField<'a> {
+    #[builder(optional)]
+    pub(crate) repeated: bool,
+    #[builder(optional)]
+    pub(crate) optional: bool,
+    #[builder(optional)]
+    pub(crate) required: bool,
+    pub(crate) number: u64,
+    #[builder(optional)]
+    pub(crate) number_span: Span,
+    pub(crate) message: Message<'a>,
+    #[builder(setter_name = "option")]
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
+    #[builder(setter_name = "comment")]
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
+}
+impl<'a> GroupField<'a> {
+    pub fn name(&self) -> &Ident<'a> {
+        self.message.name()
+    }
 }
 
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OneOfField<'a> {
-    name: Ident<'a>,
+    pub(crate) name: Ident<'a>,
     #[builder(setter_name = "field")]
-    fields: Cow<'a, [OneOfFieldItem<'a>]>,
+    pub(crate) fields: Cow<'a, [OneOfFieldItem<'a>]>,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) span: Span,
 }
 
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct OneOfFieldItem<'a> {
-    ty: Type<'a>,
-    name: Ident<'a>,
-    number: u64,
+    pub(crate) ty: Type<'a>,
+    #[builder(optional)]
+    pub(crate) ty_span: Span,
+    pub(crate) name: Ident<'a>,
+    pub(crate) number: u64,
+    #[builder(optional)]
+    pub(crate) number_span: Span,
     #[builder(setter_name = "option")]
-    options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
 
-#[derive(Debug, Clone, Builder, Getter)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[builder(required)]
 pub struct MapField<'a> {
-    pub(super) key_ty: MapFieldKeyType,
-    pub(super) value_ty: Type<'a>,
-    pub(super) name: Ident<'a>,
-    pub(super) number: u64,
+    pub(crate) key_ty: MapFieldKeyType,
+    #[builder(optional)]
+    pub(crate) key_ty_span: Span,
+    pub(crate) value_ty: Type<'a>,
+    #[builder(optional)]
+    pub(crate) value_ty_span: Span,
+    pub(crate) name: Ident<'a>,
+    pub(crate) number: u64,
+    #[builder(optional)]
+    pub(crate) number_span: Span,
     #[builder(setter_name = "option")]
-    pub(super) options: Cow<'a, [super::Option<'a>]>,
+    pub(crate) options: Cow<'a, [super::Option<'a>]>,
     #[builder(setter_name = "comment")]
-    pub(super) comments: Cow<'a, [Comment<'a>]>,
+    pub(crate) comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "trailing_comment")]
+    pub(crate) trailing_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(setter_name = "detached_comment")]
+    pub(crate) detached_comments: Cow<'a, [Comment<'a>]>,
+    #[builder(optional)]
+    pub(crate) span: Span,
 }
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum MapFieldKeyType {
     Int32,
     Int64,
@@ -206,14 +585,104 @@ pub enum MapFieldKeyType {
     String,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
 pub enum Constant<'a> {
     Ident(Ident<'a>),
     Int(i64),
     Float(f64),
     String(Cow<'a, str>),
     Bool(bool),
+    Aggregate(Aggregate<'a>),
 }
-#[derive(Debug, Clone)]
+/// Floats compare by bit pattern, so that `nan` equals itself and the comparison stays a total
+/// equivalence.
+impl PartialEq for Constant<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Constant::Ident(a), Constant::Ident(b)) => a == b,
+            (Constant::Int(a), Constant::Int(b)) => a == b,
+            (Constant::Float(a), Constant::Float(b)) => a.to_bits() == b.to_bits(),
+            (Constant::String(a), Constant::String(b)) => a == b,
+            (Constant::Bool(a), Constant::Bool(b)) => a == b,
+            (Constant::Aggregate(a), Constant::Aggregate(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+impl Eq for Constant<'_> {}
+impl Hash for Constant<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Constant::Ident(value) => value.hash(state),
+            Constant::Int(value) => value.hash(state),
+            Constant::Float(value) => value.to_bits().hash(state),
+            Constant::String(value) => value.hash(state),
+            Constant::Bool(value) => value.hash(state),
+            Constant::Aggregate(value) => value.hash(state),
+        }
+    }
+}
+
+/// A text-format message literal, such as `{ min: 1 nested { a: "x" } }`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Aggregate<'a> {
+    #[builder(setter_name = "field")]
+    pub(crate) fields: Cow<'a, [AggregateField<'a>]>,
+    pub(crate) span: Span,
+}
+impl<'a> Aggregate<'a> {
+    /// First value assigned to the field `name`.
+    pub fn get(&self, name: &str) -> std::option::Option<&AggregateValue<'a>> {
+        self.fields
+            .iter()
+            .find(|field| matches!(&field.name, AggregateFieldName::Field(ident) if ident.value() == name))
+            .map(|field| &field.value)
+    }
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Builder, Getter)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[builder(required)]
+pub struct AggregateField<'a> {
+    pub(crate) name: AggregateFieldName<'a>,
+    pub(crate) value: AggregateValue<'a>,
+    #[builder(optional)]
+    pub(crate) span: Span,
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
+pub enum AggregateFieldName<'a> {
+    Field(Ident<'a>),
+    /// A bracketed extension name such as `[foo.bar]`, or an `Any` type URL such as
+    /// `[type.googleapis.com/foo.Bar]`.
+    Extension(Ident<'a>),
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
+pub enum AggregateValue<'a> {
+    /// Scalar values, or [`Constant::Aggregate`] for nested messages.
+    Constant(Constant<'a>),
+    List(Cow<'a, [AggregateValue<'a>]>),
+}
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "kind", content = "value", rename_all = "lowercase")
+)]
 pub enum Type<'a> {
     Double,
     Float,
@@ -233,32 +702,98 @@ pub enum Type<'a> {
     Reference(Cow<'a, str>),
 }
 #[derive(Debug, Clone, Getter)]
-pub struct MessageReference<'a>(#[getter(name = "value")] Cow<'a, str>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageReference<'a>(
+    #[getter(name = "value")] pub(crate) Cow<'a, str>,
+    #[getter(name = "span")] pub(crate) Span,
+);
 
 impl<'a> MessageReference<'a> {
     pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
-        Self(value.into())
+        Self(value.into(), Span::default())
+    }
+
+    pub fn set_value(&mut self, value: impl Into<Cow<'a, str>>) {
+        self.0 = value.into();
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.1 = span;
     }
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ident<'a> {
-    relative: bool,
-    value: Cow<'a, str>,
+    pub(crate) relative: bool,
+    pub(crate) value: Cow<'a, str>,
+    pub(crate) span: Span,
 }
+/// A `//` or `/* */` comment, holding the text between the markers. Declarations keep comments
+/// the way `protoc` records them in `SourceCodeInfo`: leading comments directly precede the
+/// declaration, trailing comments follow it (or the opening brace of a block) on the same or the
+/// next lines, and detached comments precede it but are separated from it by a blank line.
 #[derive(Debug, Clone, Getter)]
-pub struct Comment<'a>(#[getter(name = "value")] Cow<'a, str>);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comment<'a>(
+    #[getter(name = "value")] pub(crate) Cow<'a, str>,
+    #[getter(name = "span")] pub(crate) Span,
+    #[getter(name = "kind")] pub(crate) CommentKind,
+);
+/// Equality and hashing on the listed fields only. Names and comments leave their span out, so
+/// that the same name written in two places compares equal and can be used as a map key.
+macro_rules! eq_without_span {
+    ($($ty:ident { $($field:tt),* })*) => {
+        $(impl PartialEq for $ty<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                $(self.$field == other.$field)&&*
+            }
+        }
+        impl Eq for $ty<'_> {}
+        impl Hash for $ty<'_> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$field.hash(state);)*
+            }
+        })*
+    };
+}
+eq_without_span! {
+    OptionName { parts }
+    Ident { relative, value }
+    MessageReference { 0 }
+    Comment { 0, 2 }
+}
+
 impl<'a> Comment<'a> {
     pub fn new(value: impl Into<Cow<'a, str>>) -> Self {
-        Comment(value.into())
+        Comment(value.into(), Span::default(), CommentKind::default())
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.1 = span;
+    }
+
+    pub fn set_kind(&mut self, kind: CommentKind) {
+        self.2 = kind;
     }
 }
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum CommentKind {
+    /// A `//` comment, running to the end of the line.
+    #[default]
+    Line,
+    /// A `/* */` comment.
+    Block,
+}
 
 impl<'a> Ident<'a> {
     pub fn new(relative: bool, value: impl Into<Cow<'a, str>>) -> Self {
         Self {
             relative,
             value: value.into(),
+            span: Span::default(),
         }
     }
 
@@ -277,6 +812,14 @@ impl<'a> Ident<'a> {
     pub fn set_value(&mut self, value: impl Into<Cow<'a, str>>) {
         self.value = value.into();
     }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn set_span(&mut self, span: Span) {
+        self.span = span;
+    }
 }
 impl<'a> Display for Ident<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -0,0 +1,221 @@
+use crate::{
+    Diagnostic, DiagnosticCode,
+    descriptor::{IDEMPOTENCY_LEVEL, OPTIMIZE_MODE, bool_constant, enum_constant, string_constant},
+};
+
+use super::{Enum, EnumItem, Message, NormalField, Option, Proto, Service, ServiceRpc};
+
+/// An enum-typed standard option, whose variants follow the values of its descriptor table.
+macro_rules! option_enum {
+    ($(#[$doc:meta])* $name:ident = $values:ident { $($variant:ident),* $(,)? }) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant),*
+        }
+        const _: () = assert!([$($name::$variant),*].len() == $values.len());
+        impl $name {
+            const VARIANTS: &[Self] = &[$(Self::$variant),*];
+
+            pub fn as_str(&self) -> &'static str {
+                $values[*self as usize].0
+            }
+
+            /// The number of the value in `descriptor.proto`.
+            pub fn number(&self) -> i32 {
+                $values[*self as usize].1
+            }
+
+            fn from_constant(option: &Option<'_>) -> Result<Self, Box<Diagnostic>> {
+                let (name, _) = enum_constant($values, &option.value)
+                    .map_err(|message| mismatch(option, message))?;
+                let index = $values.iter().position(|(value, _)| *value == name);
+                Ok(Self::VARIANTS[index.unwrap_or_default()])
+            }
+        }
+    };
+}
+
+option_enum!(
+    /// The `optimize_for` file option.
+    OptimizeMode = OPTIMIZE_MODE {
+        Speed,
+        CodeSize,
+        LiteRuntime,
+    }
+);
+option_enum!(
+    /// The `idempotency_level` method option.
+    IdempotencyLevel = IDEMPOTENCY_LEVEL {
+        IdempotencyUnknown,
+        NoSideEffects,
+        Idempotent,
+    }
+);
+
+/// The last option set as `name`, which is the one protoc keeps.
+fn find<'o, 'a>(options: &'o [Option<'a>], name: &str) -> std::option::Option<&'o Option<'a>> {
+    options.iter().rev().find(|option| option.name.is(name))
+}
+
+fn mismatch(option: &Option<'_>, message: String) -> Box<Diagnostic> {
+    Box::new(Diagnostic::warning(
+        DiagnosticCode::InvalidOptionValue,
+        format!("invalid value for option `{}`: {message}", option.name),
+        option.value_span,
+    ))
+}
+
+fn bool_option(
+    options: &[Option<'_>],
+    name: &str,
+) -> Result<std::option::Option<bool>, Box<Diagnostic>> {
+    find(options, name)
+        .map(|option| bool_constant(&option.value).map_err(|message| mismatch(option, message)))
+        .transpose()
+}
+
+/// A bool option that is `false` unless set.
+fn flag(options: &[Option<'_>], name: &str) -> Result<bool, Box<Diagnostic>> {
+    bool_option(options, name).map(std::option::Option::unwrap_or_default)
+}
+
+fn string_option<'o>(
+    options: &'o [Option<'_>],
+    name: &str,
+) -> Result<std::option::Option<&'o str>, Box<Diagnostic>> {
+    find(options, name)
+        .map(|option| string_constant(&option.value).map_err(|message| mismatch(option, message)))
+        .transpose()
+}
+
+fn enum_option<T>(
+    options: &[Option<'_>],
+    name: &str,
+    from_constant: fn(&Option<'_>) -> Result<T, Box<Diagnostic>>,
+) -> Result<std::option::Option<T>, Box<Diagnostic>> {
+    find(options, name).map(from_constant).transpose()
+}
+
+/// Typed accessors for the standard options. An option set to a value of the wrong type is
+/// reported as an [`DiagnosticCode::InvalidOptionValue`] diagnostic at that value.
+impl Proto<'_> {
+    pub fn java_package(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "java_package")
+    }
+
+    pub fn java_outer_classname(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "java_outer_classname")
+    }
+
+    pub fn java_multiple_files(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "java_multiple_files")
+    }
+
+    pub fn go_package(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "go_package")
+    }
+
+    pub fn csharp_namespace(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "csharp_namespace")
+    }
+
+    pub fn objc_class_prefix(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "objc_class_prefix")
+    }
+
+    pub fn php_namespace(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "php_namespace")
+    }
+
+    pub fn ruby_package(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "ruby_package")
+    }
+
+    pub fn swift_prefix(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "swift_prefix")
+    }
+
+    /// `SPEED` unless set otherwise.
+    pub fn optimize_for(&self) -> Result<OptimizeMode, Box<Diagnostic>> {
+        let mode = enum_option(&self.options, "optimize_for", OptimizeMode::from_constant)?;
+        Ok(mode.unwrap_or(OptimizeMode::Speed))
+    }
+
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+}
+
+impl Message<'_> {
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+
+    pub fn is_map_entry(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "map_entry")
+    }
+
+    pub fn message_set_wire_format(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "message_set_wire_format")
+    }
+}
+
+impl NormalField<'_> {
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+
+    /// The `packed` option when set. Unset, repeated scalars are packed from proto3 on.
+    pub fn packed(&self) -> Result<std::option::Option<bool>, Box<Diagnostic>> {
+        bool_option(&self.options, "packed")
+    }
+
+    pub fn is_lazy(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "lazy")
+    }
+
+    /// The `json_name` pseudo-option when set, see [`crate::descriptor::json_name`] for
+    /// the default.
+    pub fn json_name(&self) -> Result<std::option::Option<&str>, Box<Diagnostic>> {
+        string_option(&self.options, "json_name")
+    }
+}
+
+impl Enum<'_> {
+    pub fn allow_alias(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "allow_alias")
+    }
+
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+}
+
+impl EnumItem<'_> {
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+}
+
+impl Service<'_> {
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+}
+
+impl ServiceRpc<'_> {
+    pub fn is_deprecated(&self) -> Result<bool, Box<Diagnostic>> {
+        flag(&self.options, "deprecated")
+    }
+
+    /// `IDEMPOTENCY_UNKNOWN` unless set otherwise.
+    pub fn idempotency_level(&self) -> Result<IdempotencyLevel, Box<Diagnostic>> {
+        let level = enum_option(
+            &self.options,
+            "idempotency_level",
+            IdempotencyLevel::from_constant,
+        )?;
+        Ok(level.unwrap_or(IdempotencyLevel::IdempotencyUnknown))
+    }
+}
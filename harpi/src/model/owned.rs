@@ -0,0 +1,432 @@
+use std::borrow::Cow;
+
+use super::{
+    Aggregate, AggregateField, AggregateFieldName, AggregateValue, Comment, CommentKind, Constant,
+    Enum, EnumItem, Extend, ExtensionRange, Extensions, Field, GroupField, Ident, Import, MapField,
+    MapFieldKeyType, Message, MessageReference, NormalField, OneOfField, OneOfFieldItem, Option,
+    OptionName, OptionNamePart, Package, Proto, ReservedData, ReservedItems, Service, ServiceRpc,
+    ServiceRpcField, Span, Syntax, Type, Visibility,
+};
+
+/// Conversion into a value that no longer borrows from the parsed source, the way
+/// [`Cow::into_owned`] detaches a single string. Model types convert to their `'static` form, so
+/// they can be cached or sent to other threads after the source is dropped.
+pub trait IntoOwned {
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+impl IntoOwned for Cow<'_, str> {
+    type Owned = Cow<'static, str>;
+
+    fn into_owned(self) -> Cow<'static, str> {
+        Cow::Owned(Cow::into_owned(self))
+    }
+}
+
+impl<T: IntoOwned + Clone> IntoOwned for Cow<'_, [T]>
+where
+    T::Owned: Clone,
+{
+    type Owned = Cow<'static, [T::Owned]>;
+
+    fn into_owned(self) -> Cow<'static, [T::Owned]> {
+        Cow::into_owned(self)
+            .into_iter()
+            .map(IntoOwned::into_owned)
+            .collect()
+    }
+}
+
+/// Types without borrowed data, converted as is.
+macro_rules! owned {
+    ($($ty:ty),*) => {
+        $(impl IntoOwned for $ty {
+            type Owned = Self;
+
+            fn into_owned(self) -> Self {
+                self
+            }
+        })*
+    };
+}
+owned!(
+    bool,
+    u64,
+    i64,
+    Span,
+    Visibility,
+    MapFieldKeyType,
+    CommentKind,
+    ExtensionRange
+);
+
+/// Structs converted field by field.
+macro_rules! owned_fields {
+    ($($ty:ident { $($field:ident),* },)*) => {
+        $(impl IntoOwned for $ty<'_> {
+            type Owned = $ty<'static>;
+
+            fn into_owned(self) -> Self::Owned {
+                $ty {
+                    $($field: IntoOwned::into_owned(self.$field),)*
+                }
+            }
+        }
+
+        impl $ty<'_> {
+            /// Converts into a value that no longer borrows from the parsed source.
+            pub fn into_owned(self) -> $ty<'static> {
+                IntoOwned::into_owned(self)
+            }
+        })*
+    };
+}
+owned_fields!(
+    Proto {
+        syntax,
+        package,
+        imports,
+        options,
+        messages,
+        enums,
+        services,
+        extends
+    },
+    Package {
+        value,
+        value_span,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Syntax {
+        value,
+        value_span,
+        edition,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Import {
+        weak,
+        public,
+        option,
+        value,
+        value_span,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Option {
+        name,
+        value,
+        value_span,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Service {
+        name,
+        rpcs,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    ServiceRpc {
+        name,
+        input,
+        output,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    ServiceRpcField {
+        value,
+        stream,
+        span
+    },
+    Message {
+        name,
+        visibility,
+        fields,
+        enums,
+        messages,
+        options,
+        reserved,
+        extensions,
+        extends,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Enum {
+        name,
+        visibility,
+        fields,
+        reserved,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    ReservedItems {
+        items,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Extensions {
+        ranges,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    Extend {
+        extendee,
+        fields,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    EnumItem {
+        name,
+        number,
+        number_span,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    NormalField {
+        repeated,
+        optional,
+        required,
+        ty,
+        ty_span,
+        name,
+        number,
+        number_span,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    GroupField {
+        repeated,
+        optional,
+        required,
+        number,
+        number_span,
+        message,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    OneOfField {
+        name,
+        fields,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    OneOfFieldItem {
+        ty,
+        ty_span,
+        name,
+        number,
+        number_span,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    MapField {
+        key_ty,
+        key_ty_span,
+        value_ty,
+        value_ty_span,
+        name,
+        number,
+        number_span,
+        options,
+        comments,
+        trailing_comments,
+        detached_comments,
+        span
+    },
+    OptionName { parts, span },
+    Aggregate { fields, span },
+    AggregateField { name, value, span },
+    Ident {
+        relative,
+        value,
+        span
+    },
+);
+
+/// Inherent `into_owned` for the types converted by hand below.
+macro_rules! into_owned {
+    ($($ty:ident),*) => {
+        $(impl $ty<'_> {
+            /// Converts into a value that no longer borrows from the parsed source.
+            pub fn into_owned(self) -> $ty<'static> {
+                IntoOwned::into_owned(self)
+            }
+        })*
+    };
+}
+into_owned!(
+    MessageReference,
+    Comment,
+    Field,
+    ReservedData,
+    Constant,
+    OptionNamePart,
+    AggregateFieldName,
+    AggregateValue,
+    Type
+);
+
+impl IntoOwned for MessageReference<'_> {
+    type Owned = MessageReference<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        MessageReference(IntoOwned::into_owned(self.0), self.1)
+    }
+}
+
+impl IntoOwned for Comment<'_> {
+    type Owned = Comment<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        Comment(IntoOwned::into_owned(self.0), self.1, self.2)
+    }
+}
+
+impl IntoOwned for Field<'_> {
+    type Owned = Field<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Field::Normal(field) => Field::Normal(IntoOwned::into_owned(field)),
+            Field::OneOf(field) => Field::OneOf(IntoOwned::into_owned(field)),
+            Field::Map(field) => Field::Map(IntoOwned::into_owned(field)),
+            Field::Group(field) => Field::Group(IntoOwned::into_owned(field)),
+        }
+    }
+}
+
+impl IntoOwned for ReservedData<'_> {
+    type Owned = ReservedData<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            ReservedData::Range(start, end) => ReservedData::Range(start, end),
+            ReservedData::Field(name) => ReservedData::Field(IntoOwned::into_owned(name)),
+        }
+    }
+}
+
+impl IntoOwned for Constant<'_> {
+    type Owned = Constant<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Constant::Ident(ident) => Constant::Ident(IntoOwned::into_owned(ident)),
+            Constant::Int(value) => Constant::Int(value),
+            Constant::Float(value) => Constant::Float(value),
+            Constant::String(value) => Constant::String(IntoOwned::into_owned(value)),
+            Constant::Bool(value) => Constant::Bool(value),
+            Constant::Aggregate(aggregate) => Constant::Aggregate(IntoOwned::into_owned(aggregate)),
+        }
+    }
+}
+
+impl IntoOwned for OptionNamePart<'_> {
+    type Owned = OptionNamePart<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            OptionNamePart::Ident(name) => OptionNamePart::Ident(IntoOwned::into_owned(name)),
+            OptionNamePart::Extension(name) => {
+                OptionNamePart::Extension(IntoOwned::into_owned(name))
+            }
+        }
+    }
+}
+
+impl IntoOwned for AggregateFieldName<'_> {
+    type Owned = AggregateFieldName<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            AggregateFieldName::Field(name) => {
+                AggregateFieldName::Field(IntoOwned::into_owned(name))
+            }
+            AggregateFieldName::Extension(name) => {
+                AggregateFieldName::Extension(IntoOwned::into_owned(name))
+            }
+        }
+    }
+}
+
+impl IntoOwned for AggregateValue<'_> {
+    type Owned = AggregateValue<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            AggregateValue::Constant(constant) => {
+                AggregateValue::Constant(IntoOwned::into_owned(constant))
+            }
+            AggregateValue::List(values) => AggregateValue::List(IntoOwned::into_owned(values)),
+        }
+    }
+}
+
+impl IntoOwned for Type<'_> {
+    type Owned = Type<'static>;
+
+    fn into_owned(self) -> Self::Owned {
+        match self {
+            Type::Double => Type::Double,
+            Type::Float => Type::Float,
+            Type::Int32 => Type::Int32,
+            Type::Int64 => Type::Int64,
+            Type::UInt32 => Type::UInt32,
+            Type::UInt64 => Type::UInt64,
+            Type::SInt32 => Type::SInt32,
+            Type::SInt64 => Type::SInt64,
+            Type::Fixed32 => Type::Fixed32,
+            Type::Fixed64 => Type::Fixed64,
+            Type::SFixed32 => Type::SFixed32,
+            Type::SFixed64 => Type::SFixed64,
+            Type::Bool => Type::Bool,
+            Type::String => Type::String,
+            Type::Bytes => Type::Bytes,
+            Type::Reference(name) => Type::Reference(IntoOwned::into_owned(name)),
+        }
+    }
+}
@@ -1,16 +1,88 @@
-use crate::{Error, model::Syntax, visitor::ProtoVisitor};
+use crate::{
+    Diagnostic, Error, ProtoCollector,
+    model::{Proto, Syntax},
+    visitor::{ProtoVisitor, SyntaxCheck, TryProtoVisitor, TryVisitor},
+};
 
 pub trait ProtoParser {
     const SYNTAX: &'static str;
     fn parse<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Result<(), Error>
     where
-        Visitor: ProtoVisitor;
+        Visitor: ProtoVisitor<'a>;
 
+    /// Parses the whole file `data`, which must declare the given `syntax`. Its `syntax` or
+    /// `edition` statement is checked with a [`SyntaxCheck`] and a mismatch is reported as
+    /// [`Error::InvalidSyntax`]. Parsers derived with `ProtoParser`, such as [`DefaultParser`],
+    /// parse it with the parser supporting `syntax`.
     fn parse_with_syntax<'a, Visitor>(
         data: &'a str,
         syntax: Syntax<'a>,
         visitor: &mut Visitor,
     ) -> Result<(), Error>
     where
-        Visitor: ProtoVisitor;
+        Visitor: ProtoVisitor<'a>,
+    {
+        if !Self::supports(&syntax) {
+            return Err(Error::UndefinedParser(syntax.value().to_string()));
+        }
+        let mut check = SyntaxCheck::new(syntax, visitor);
+        Self::parse(data, &mut check)?;
+        check.finish()
+    }
+
+    /// Parses `data`, skipping declarations that fail to parse instead of stopping at the first
+    /// error. Every declaration that parses is still visited, and one diagnostic is returned per
+    /// skipped region. Parsers without recovery report their first error.
+    fn parse_recovering<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Vec<Diagnostic>
+    where
+        Visitor: ProtoVisitor<'a>,
+    {
+        match Self::parse(data, visitor) {
+            Ok(()) => Vec::new(),
+            Err(error) => vec![error.to_diagnostic()],
+        }
+    }
+
+    /// Parses `data` with a visitor that may stop early. Parsing ends at the first node where the
+    /// visitor breaks or fails, and its error is returned as [`Error::Visitor`]. The grammar is
+    /// checked before any node is visited, so syntax errors are still reported first.
+    fn try_parse<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Result<(), Error>
+    where
+        Visitor: TryProtoVisitor<'a>,
+    {
+        let mut visitor = TryVisitor::new(visitor);
+        Self::parse(data, &mut visitor)?;
+        visitor.finish()
+    }
+
+    /// Whether this parser handles files declaring the given syntax or edition.
+    fn supports(syntax: &Syntax<'_>) -> bool {
+        !*syntax.edition() && syntax.value() == Self::SYNTAX
+    }
+}
+
+/// Parser dispatching on the `syntax` or `edition` statement to every syntax supported by harpi.
+#[derive(Debug, Clone, Copy, Default, derive::ProtoParser)]
+#[parser(
+    crate::proto3::Proto3,
+    crate::proto2::Proto2,
+    crate::editions::Editions
+)]
+pub struct DefaultParser;
+
+/// Parses a complete file with [`DefaultParser`] and collects it into a [`Proto`].
+pub fn parse_file(data: &str) -> Result<Proto<'_>, Error> {
+    let mut collector = ProtoCollector::new();
+    DefaultParser::parse(data, &mut collector)?;
+    collector.finish()
+}
+
+/// Parses a complete file like [`parse_file`], but recovers from errors. Returns everything that
+/// could be parsed, along with a diagnostic for each error.
+pub fn parse_file_recovering(data: &str) -> (Proto<'_>, Vec<Diagnostic>) {
+    let mut collector = ProtoCollector::new();
+    let mut diagnostics = DefaultParser::parse_recovering(data, &mut collector);
+    let (proto, error) = collector.finish_partial();
+    diagnostics.extend(error.map(|error| error.to_diagnostic()));
+    (proto, diagnostics)
 }
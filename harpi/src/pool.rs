@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    Linked, Linker,
+    linker::join,
+    model::{Enum, EnumItem, Extend, Field, Message, Proto, Service, ServiceRpc},
+};
+
+/// A declaration found in a [`DescriptorPool`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Descriptor<'p> {
+    Message(&'p Message<'static>),
+    Enum(&'p Enum<'static>),
+    /// Enum values are scoped like protobuf does, as siblings of their enum: `shop.v1.ACTIVE`
+    /// rather than `shop.v1.Status.ACTIVE`.
+    EnumValue(&'p EnumItem<'static>),
+    Service(&'p Service<'static>),
+    Method(&'p ServiceRpc<'static>),
+    /// A field of an `extend` block. Group extensions are named by their lowercased name.
+    Extension(&'p Field<'static>),
+}
+
+/// Declarations of a set of linked files, looked up by fully qualified name. The pool owns its
+/// files, so it can be shared across threads behind an `Arc` once built.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorPool {
+    files: Vec<(String, Proto<'static>)>,
+    entries: HashMap<String, Entry>,
+    packages: BTreeMap<String, Vec<String>>,
+    linked: Linked,
+}
+impl DescriptorPool {
+    /// Links the files of `linker` and indexes what they declare. A name declared twice refers to
+    /// its first declaration.
+    pub fn new(linker: &Linker<'_, '_>) -> Self {
+        let mut pool = Self {
+            linked: linker.link(),
+            ..Default::default()
+        };
+        for (file, proto) in &linker.files {
+            pool.add_file(file, proto);
+        }
+        pool
+    }
+
+    fn add_file(&mut self, file: &str, proto: &Proto<'_>) {
+        let package = proto.package().value();
+        let file_index = self.files.len();
+        let mut indexer = Indexer {
+            pool: self,
+            file: file_index,
+            package,
+        };
+        indexer.add_members(package, &[], Scope::File(proto));
+        for (index, service) in proto.services().iter().enumerate() {
+            let full_name = join(package, service.name().value());
+            indexer.insert(&full_name, &[], Member::Service(index));
+            for (rpc, method) in service.rpcs().iter().enumerate() {
+                indexer.insert(
+                    &join(&full_name, method.name().value()),
+                    &[],
+                    Member::Method(index, rpc),
+                );
+            }
+        }
+        self.packages.entry(package.to_string()).or_default();
+        self.files
+            .push((file.to_string(), proto.clone().into_owned()));
+    }
+
+    /// The declaration named `full_name`, with or without a leading dot.
+    pub fn get(&self, full_name: &str) -> Option<Descriptor<'_>> {
+        let entry = self.entries.get(full_name.trim_start_matches('.'))?;
+        let scope = Scope::at(&self.files[entry.file].1, &entry.scope)?;
+        Some(match entry.member {
+            Member::Message(step) => Descriptor::Message(scope.nested(step)?),
+            Member::Enum(index) => Descriptor::Enum(scope.enums().get(index)?),
+            Member::EnumValue(index, value) => {
+                Descriptor::EnumValue(scope.enums().get(index)?.fields().get(value)?)
+            }
+            Member::Service(index) => Descriptor::Service(scope.services().get(index)?),
+            Member::Method(index, rpc) => {
+                Descriptor::Method(scope.services().get(index)?.rpcs().get(rpc)?)
+            }
+            Member::Extension(extend, field) => {
+                Descriptor::Extension(scope.extends().get(extend)?.fields().get(field)?)
+            }
+        })
+    }
+
+    pub fn message(&self, full_name: &str) -> Option<&Message<'static>> {
+        match self.get(full_name)? {
+            Descriptor::Message(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    pub fn enumeration(&self, full_name: &str) -> Option<&Enum<'static>> {
+        match self.get(full_name)? {
+            Descriptor::Enum(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn enum_value(&self, full_name: &str) -> Option<&EnumItem<'static>> {
+        match self.get(full_name)? {
+            Descriptor::EnumValue(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    pub fn service(&self, full_name: &str) -> Option<&Service<'static>> {
+        match self.get(full_name)? {
+            Descriptor::Service(service) => Some(service),
+            _ => None,
+        }
+    }
+
+    pub fn method(&self, full_name: &str) -> Option<&ServiceRpc<'static>> {
+        match self.get(full_name)? {
+            Descriptor::Method(rpc) => Some(rpc),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self, full_name: &str) -> Option<&Field<'static>> {
+        match self.get(full_name)? {
+            Descriptor::Extension(field) => Some(field),
+            _ => None,
+        }
+    }
+
+    /// Name of the file declaring `full_name`.
+    pub fn file_of(&self, full_name: &str) -> Option<&str> {
+        let entry = self.entries.get(full_name.trim_start_matches('.'))?;
+        Some(&self.files[entry.file].0)
+    }
+
+    pub fn file(&self, file: &str) -> Option<&Proto<'static>> {
+        self.files
+            .iter()
+            .find(|(name, _)| name == file)
+            .map(|(_, proto)| proto)
+    }
+
+    /// Every file of the pool with its name, in the order they were linked.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &Proto<'static>)> {
+        self.files
+            .iter()
+            .map(|(name, proto)| (name.as_str(), proto))
+    }
+
+    /// Packages declared by the files, in alphabetical order. Files without a package declaration
+    /// belong to the empty package.
+    pub fn packages(&self) -> impl Iterator<Item = &str> {
+        self.packages.keys().map(String::as_str)
+    }
+
+    /// Declarations of the files in `package`, nested ones included, with their fully qualified
+    /// names.
+    pub fn package(&self, package: &str) -> impl Iterator<Item = (&str, Descriptor<'_>)> {
+        self.packages
+            .get(package)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| Some((name.as_str(), self.get(name)?)))
+    }
+
+    /// Result of linking the files, with their resolved references and diagnostics.
+    pub fn linked(&self) -> &Linked {
+        &self.linked
+    }
+}
+
+/// Where a declaration sits: the messages leading to its scope from the top of its file, and the
+/// member of that scope it is.
+#[derive(Debug, Clone)]
+struct Entry {
+    file: usize,
+    scope: Vec<Step>,
+    member: Member,
+}
+
+/// A message nested in a file or message.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Message(usize),
+    /// The message of the group field at that index.
+    Group(usize),
+    /// The message of a group field of an `extend` block.
+    ExtendGroup(usize, usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Member {
+    Message(Step),
+    Enum(usize),
+    EnumValue(usize, usize),
+    Service(usize),
+    Method(usize, usize),
+    Extension(usize, usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Scope<'p, 'a> {
+    File(&'p Proto<'a>),
+    Message(&'p Message<'a>),
+}
+impl<'p, 'a> Scope<'p, 'a> {
+    fn at(proto: &'p Proto<'a>, path: &[Step]) -> Option<Self> {
+        path.iter().try_fold(Scope::File(proto), |scope, step| {
+            scope.nested(*step).map(Scope::Message)
+        })
+    }
+
+    fn nested(self, step: Step) -> Option<&'p Message<'a>> {
+        let field = match step {
+            Step::Message(index) => return self.messages().get(index),
+            Step::Group(index) => self.fields().get(index)?,
+            Step::ExtendGroup(extend, index) => self.extends().get(extend)?.fields().get(index)?,
+        };
+        match field {
+            Field::Group(group) => Some(group.message()),
+            _ => None,
+        }
+    }
+
+    fn messages(self) -> &'p [Message<'a>] {
+        match self {
+            Scope::File(proto) => proto.messages(),
+            Scope::Message(message) => message.messages(),
+        }
+    }
+
+    fn fields(self) -> &'p [Field<'a>] {
+        match self {
+            Scope::File(_) => &[],
+            Scope::Message(message) => message.fields(),
+        }
+    }
+
+    fn enums(self) -> &'p [Enum<'a>] {
+        match self {
+            Scope::File(proto) => proto.enums(),
+            Scope::Message(message) => message.enums(),
+        }
+    }
+
+    fn extends(self) -> &'p [Extend<'a>] {
+        match self {
+            Scope::File(proto) => proto.extends(),
+            Scope::Message(message) => message.extends(),
+        }
+    }
+
+    fn services(self) -> &'p [Service<'a>] {
+        match self {
+            Scope::File(proto) => proto.services(),
+            Scope::Message(_) => &[],
+        }
+    }
+}
+
+struct Indexer<'i> {
+    pool: &'i mut DescriptorPool,
+    file: usize,
+    package: &'i str,
+}
+impl Indexer<'_> {
+    fn insert(&mut self, full_name: &str, scope: &[Step], member: Member) {
+        if self.pool.entries.contains_key(full_name) {
+            return;
+        }
+        self.pool.entries.insert(
+            full_name.to_string(),
+            Entry {
+                file: self.file,
+                scope: scope.to_vec(),
+                member,
+            },
+        );
+        self.pool
+            .packages
+            .entry(self.package.to_string())
+            .or_default()
+            .push(full_name.to_string());
+    }
+
+    /// Indexes the messages, enums and extensions declared in `scope`, named `name`.
+    fn add_members(&mut self, name: &str, path: &[Step], scope: Scope<'_, '_>) {
+        let messages = scope.messages().iter().enumerate();
+        let groups = scope
+            .fields()
+            .iter()
+            .enumerate()
+            .filter_map(|(index, field)| match field {
+                Field::Group(group) => Some((Step::Group(index), group.message())),
+                _ => None,
+            });
+        let nested = messages
+            .map(|(index, message)| (Step::Message(index), message))
+            .chain(groups);
+        for (step, message) in nested {
+            self.add_message(name, path, step, message);
+        }
+        for (index, value) in scope.enums().iter().enumerate() {
+            self.insert(&join(name, value.name().value()), path, Member::Enum(index));
+            for (item, value) in value.fields().iter().enumerate() {
+                self.insert(
+                    &join(name, value.name().value()),
+                    path,
+                    Member::EnumValue(index, item),
+                );
+            }
+        }
+        for (extend, block) in scope.extends().iter().enumerate() {
+            for (index, field) in block.fields().iter().enumerate() {
+                let member = Member::Extension(extend, index);
+                match field {
+                    Field::Normal(field) => {
+                        self.insert(&join(name, field.name().value()), path, member)
+                    }
+                    Field::Group(group) => {
+                        let lowercase = group.name().value().to_lowercase();
+                        self.insert(&join(name, &lowercase), path, member);
+                        let step = Step::ExtendGroup(extend, index);
+                        self.add_message(name, path, step, group.message());
+                    }
+                    Field::OneOf(_) | Field::Map(_) => {}
+                }
+            }
+        }
+    }
+
+    fn add_message(&mut self, scope: &str, path: &[Step], step: Step, message: &Message<'_>) {
+        let full_name = join(scope, message.name().value());
+        self.insert(&full_name, path, Member::Message(step));
+        let path = [path, &[step]].concat();
+        self.add_members(&full_name, &path, Scope::Message(message));
+    }
+}
@@ -0,0 +1,803 @@
+use std::fmt::Display;
+
+use crate::model::{
+    Aggregate, AggregateFieldName, AggregateValue, Comment, Constant, Enum, EnumItem, Extend,
+    Extensions, Field, Ident, MapField, MapFieldKeyType, Message, NormalField, OneOfField, Option,
+    Proto, ReservedData, ReservedItems, Service, ServiceRpc, ServiceRpcField, Type, Visibility,
+};
+
+/// How comments attached to declarations are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// One `//` comment per line of comment text.
+    #[default]
+    Line,
+    /// One `/* */` comment per comment, keeping its text as is. Several leading or trailing
+    /// comments are written as line comments, since separate blocks would not stay attached.
+    Block,
+    /// Comments are left out.
+    Omit,
+}
+
+/// Quote character used for string literals.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QuoteStyle {
+    #[default]
+    Double,
+    Single,
+}
+
+/// Formats the model back into `.proto` source. The output parses back into an equivalent model,
+/// so printing a parsed file works as a formatter. Comments are laid out so they attach to the
+/// same declarations when the output is parsed again: detached comments are set apart by blank
+/// lines, and a trailing comment follows the statement or the opening brace of a block.
+///
+/// Declarations are grouped by kind: options first, then fields, reserved ranges, and nested
+/// declarations, so the original order across kinds is not kept.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    indent: usize,
+    align: bool,
+    max_width: usize,
+    comments: CommentStyle,
+    quote: QuoteStyle,
+}
+impl Default for Printer {
+    fn default() -> Self {
+        Self {
+            indent: 2,
+            align: false,
+            max_width: 100,
+            comments: CommentStyle::default(),
+            quote: QuoteStyle::default(),
+        }
+    }
+}
+impl Printer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of spaces per nesting level.
+    pub fn with_indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Pads field and enum value names so the `=` of consecutive declarations line up.
+    pub fn with_alignment(mut self, align: bool) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Line width past which field options are wrapped one per line, and message literals are
+    /// spread over several lines.
+    pub fn with_max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+        self
+    }
+
+    pub fn with_comments(mut self, comments: CommentStyle) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    pub fn with_quotes(mut self, quote: QuoteStyle) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    pub fn print(&self, proto: &Proto<'_>) -> String {
+        let syntax = proto.syntax();
+        let dialect = if *syntax.edition() {
+            Dialect::Editions
+        } else if syntax.value() == "proto3" {
+            Dialect::Proto3
+        } else {
+            Dialect::Proto2
+        };
+        let mut output = Output::new(self, dialect);
+        output.proto(proto);
+        output.buffer
+    }
+
+    /// Prints a single message. Without a file to take the syntax from, fields only get the labels
+    /// set on them and reserved names are quoted, as in proto3.
+    pub fn print_message(&self, message: &Message<'_>) -> String {
+        let mut output = Output::new(self, Dialect::Proto3);
+        output.message(message);
+        output.buffer
+    }
+
+    pub fn print_enum(&self, value: &Enum<'_>) -> String {
+        let mut output = Output::new(self, Dialect::Proto3);
+        output.enumeration(value);
+        output.buffer
+    }
+
+    pub fn print_service(&self, service: &Service<'_>) -> String {
+        let mut output = Output::new(self, Dialect::Proto3);
+        output.service(service);
+        output.buffer
+    }
+
+    fn quoted(&self, value: &str) -> String {
+        let quote = match self.quote {
+            QuoteStyle::Double => '"',
+            QuoteStyle::Single => '\'',
+        };
+        let mut output = String::with_capacity(value.len() + 2);
+        output.push(quote);
+        // Both quotes are escaped, since the grammar accepts neither unescaped inside a string.
+        for char in value.chars() {
+            match char {
+                '\\' => output.push_str("\\\\"),
+                '"' => output.push_str("\\\""),
+                '\'' => output.push_str("\\'"),
+                '\n' => output.push_str("\\n"),
+                '\r' => output.push_str("\\r"),
+                '\t' => output.push_str("\\t"),
+                '\u{7}' => output.push_str("\\a"),
+                '\u{8}' => output.push_str("\\b"),
+                '\u{b}' => output.push_str("\\v"),
+                '\u{c}' => output.push_str("\\f"),
+                char => output.push(char),
+            }
+        }
+        output.push(quote);
+        output
+    }
+}
+
+impl Display for Proto<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Printer::default().print(self))
+    }
+}
+impl Display for Message<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Printer::default().print_message(self))
+    }
+}
+impl Display for Enum<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Printer::default().print_enum(self))
+    }
+}
+impl Display for Service<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&Printer::default().print_service(self))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Proto2,
+    Proto3,
+    Editions,
+}
+
+struct Output<'p> {
+    printer: &'p Printer,
+    dialect: Dialect,
+    depth: usize,
+    /// Set right after an opening brace, where no blank line is needed before the next section.
+    fresh: bool,
+    /// Set after trailing comments written on their own lines, which need a blank line after them.
+    pending: bool,
+    buffer: String,
+}
+impl<'p> Output<'p> {
+    fn new(printer: &'p Printer, dialect: Dialect) -> Self {
+        Self {
+            printer,
+            dialect,
+            depth: 0,
+            fresh: true,
+            pending: false,
+            buffer: String::new(),
+        }
+    }
+
+    fn indentation(&self) -> usize {
+        self.depth * self.printer.indent
+    }
+
+    fn line(&mut self, text: &str) {
+        if self.pending {
+            self.pending = false;
+            if text != "}" {
+                self.buffer.push('\n');
+            }
+        }
+        let indentation = self.indentation();
+        self.buffer.extend(std::iter::repeat_n(' ', indentation));
+        self.buffer.push_str(text);
+        self.buffer.push('\n');
+        self.fresh = false;
+    }
+
+    /// Starts a new group of declarations, separated from the previous one by a blank line.
+    fn section(&mut self) {
+        if !self.fresh && !self.pending {
+            self.buffer.push('\n');
+            self.fresh = true;
+        }
+    }
+
+    fn open(&mut self, text: &str) {
+        self.line(text);
+        self.depth += 1;
+        self.fresh = true;
+    }
+
+    fn close(&mut self) {
+        self.depth -= 1;
+        self.line("}");
+    }
+
+    /// Writes detached comments, one paragraph at a time, followed by the leading comments.
+    fn comments(&mut self, detached: &[Comment<'_>], leading: &[Comment<'_>]) {
+        if self.printer.comments == CommentStyle::Omit {
+            return;
+        }
+        let mut previous: std::option::Option<&Comment<'_>> = None;
+        for comment in detached {
+            let paragraph = previous.is_none_or(|previous| !comment.continues(previous));
+            if paragraph
+                && !self.pending
+                && !self.buffer.is_empty()
+                && !self.buffer.ends_with("\n\n")
+            {
+                self.buffer.push('\n');
+            }
+            self.comment(comment, true);
+            previous = Some(comment);
+        }
+        if !detached.is_empty() {
+            self.buffer.push('\n');
+        }
+        for comment in leading {
+            self.comment(comment, leading.len() == 1);
+        }
+    }
+
+    /// Writes trailing comments at the end of the last line when they fit on it, or on the
+    /// following lines otherwise.
+    fn trailing(&mut self, trailing: &[Comment<'_>]) {
+        let style = self.printer.comments;
+        match trailing {
+            _ if style == CommentStyle::Omit => {}
+            [] => {}
+            [comment] if !comment.value().contains('\n') => {
+                self.buffer.pop();
+                let value = comment.value();
+                if style == CommentStyle::Block && !value.contains("*/") {
+                    self.buffer.push_str(&format!("  /*{value}*/"));
+                } else {
+                    self.buffer.push_str(format!("  //{value}").trim_end());
+                }
+                self.buffer.push('\n');
+            }
+            comments => {
+                let fresh = self.fresh;
+                for comment in comments {
+                    self.comment(comment, comments.len() == 1);
+                }
+                self.fresh = fresh;
+                self.pending = true;
+            }
+        }
+    }
+
+    fn comment(&mut self, comment: &Comment<'_>, alone: bool) {
+        let value = comment.value();
+        if self.printer.comments == CommentStyle::Block && alone && !value.contains("*/") {
+            self.line(&format!("/*{value}*/"));
+        } else {
+            for line in value.split('\n') {
+                self.line(format!("//{line}").trim_end());
+            }
+        }
+    }
+
+    fn proto(&mut self, proto: &Proto<'_>) {
+        let syntax = proto.syntax();
+        if !syntax.value().is_empty() {
+            self.comments(syntax.detached_comments(), syntax.comments());
+            let keyword = if *syntax.edition() {
+                "edition"
+            } else {
+                "syntax"
+            };
+            let value = self.printer.quoted(syntax.value());
+            self.line(&format!("{keyword} = {value};"));
+            self.trailing(syntax.trailing_comments());
+        }
+        let package = proto.package();
+        if !package.value().is_empty() {
+            self.section();
+            self.comments(package.detached_comments(), package.comments());
+            self.line(&format!("package {};", package.value()));
+            self.trailing(package.trailing_comments());
+        }
+        if !proto.imports().is_empty() {
+            self.section();
+        }
+        for import in proto.imports().iter() {
+            self.comments(import.detached_comments(), import.comments());
+            let modifier = if *import.public() {
+                "public "
+            } else if *import.weak() {
+                "weak "
+            } else if *import.option() {
+                "option "
+            } else {
+                ""
+            };
+            let value = self.printer.quoted(import.value());
+            self.line(&format!("import {modifier}{value};"));
+            self.trailing(import.trailing_comments());
+        }
+        self.options(proto.options());
+        for message in proto.messages().iter() {
+            self.section();
+            self.message(message);
+        }
+        for value in proto.enums().iter() {
+            self.section();
+            self.enumeration(value);
+        }
+        for extend in proto.extends().iter() {
+            self.section();
+            self.extend(extend);
+        }
+        for service in proto.services().iter() {
+            self.section();
+            self.service(service);
+        }
+    }
+
+    /// Writes `option` statements as their own section.
+    fn options(&mut self, options: &[Option<'_>]) {
+        if !options.is_empty() {
+            self.section();
+        }
+        for option in options {
+            self.comments(option.detached_comments(), option.comments());
+            let name = option.name().to_string();
+            let value = self.constant(option.value(), self.depth);
+            self.line(&format!("option {name} = {value};"));
+            self.trailing(option.trailing_comments());
+        }
+    }
+
+    /// Writes a declaration followed by its `[...]` options and `terminator`, wrapping the options
+    /// one per line when they do not fit.
+    fn declaration(&mut self, head: &str, options: &[Option<'_>], terminator: &str) {
+        if options.is_empty() {
+            self.line(&format!("{head}{terminator}"));
+            return;
+        }
+        let inline = options
+            .iter()
+            .map(|option| self.field_option(option, self.depth))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let line = format!("{head} [{inline}]{terminator}");
+        if !line.contains('\n') && self.indentation() + line.len() <= self.printer.max_width {
+            self.line(&line);
+            return;
+        }
+        self.line(&format!("{head} ["));
+        self.depth += 1;
+        for (index, option) in options.iter().enumerate() {
+            let separator = if index + 1 == options.len() { "" } else { "," };
+            let option = self.field_option(option, self.depth);
+            self.line(&format!("{option}{separator}"));
+        }
+        self.depth -= 1;
+        self.line(&format!("]{terminator}"));
+    }
+
+    fn field_option(&self, option: &Option<'_>, depth: usize) -> String {
+        let name = option.name().to_string();
+        let value = self.constant(option.value(), depth);
+        format!("{name} = {value}")
+    }
+
+    fn constant(&self, value: &Constant<'_>, depth: usize) -> String {
+        match value {
+            Constant::Ident(ident) => ident.to_string(),
+            Constant::Int(value) => value.to_string(),
+            Constant::Float(value) => float(*value),
+            Constant::String(value) => self.printer.quoted(value),
+            Constant::Bool(value) => value.to_string(),
+            Constant::Aggregate(aggregate) => self.aggregate(aggregate, depth),
+        }
+    }
+
+    /// Writes a message literal on one line if it fits, or one field per line otherwise.
+    fn aggregate(&self, aggregate: &Aggregate<'_>, depth: usize) -> String {
+        if aggregate.fields().is_empty() {
+            return "{}".to_owned();
+        }
+        let fields = aggregate
+            .fields()
+            .iter()
+            .map(|field| {
+                let name = match field.name() {
+                    AggregateFieldName::Field(ident) => ident.value().to_owned(),
+                    AggregateFieldName::Extension(ident) => format!("[{}]", ident.value()),
+                };
+                let value = self.aggregate_value(field.value(), depth + 1);
+                format!("{name}: {value}")
+            })
+            .collect::<Vec<_>>();
+        let inline = format!("{{ {} }}", fields.join(" "));
+        if !inline.contains('\n')
+            && depth * self.printer.indent + inline.len() <= self.printer.max_width
+        {
+            return inline;
+        }
+        let indentation = " ".repeat((depth + 1) * self.printer.indent);
+        let mut output = "{\n".to_owned();
+        for field in fields {
+            output.push_str(&indentation);
+            output.push_str(&field);
+            output.push('\n');
+        }
+        output.push_str(&" ".repeat(depth * self.printer.indent));
+        output.push('}');
+        output
+    }
+
+    fn aggregate_value(&self, value: &AggregateValue<'_>, depth: usize) -> String {
+        match value {
+            AggregateValue::Constant(value) => self.constant(value, depth),
+            AggregateValue::List(values) => {
+                let values = values
+                    .iter()
+                    .map(|value| self.aggregate_value(value, depth))
+                    .collect::<Vec<_>>();
+                format!("[{}]", values.join(", "))
+            }
+        }
+    }
+
+    fn visibility(visibility: Visibility) -> &'static str {
+        match visibility {
+            Visibility::Default => "",
+            Visibility::Export => "export ",
+            Visibility::Local => "local ",
+        }
+    }
+
+    fn message(&mut self, message: &Message<'_>) {
+        self.comments(message.detached_comments(), message.comments());
+        let visibility = Self::visibility(*message.visibility());
+        let head = format!("{visibility}message {}", message.name().value());
+        if is_empty(message) && message.trailing_comments().is_empty() {
+            self.line(&format!("{head} {{}}"));
+            return;
+        }
+        self.open(&format!("{head} {{"));
+        self.trailing(message.trailing_comments());
+        self.message_body(message);
+        self.close();
+    }
+
+    fn message_body(&mut self, message: &Message<'_>) {
+        self.options(message.options());
+        self.fields(message.fields());
+        if !message.reserved().is_empty() || !message.extensions().is_empty() {
+            self.section();
+        }
+        for reserved in message.reserved().iter() {
+            self.reserved(reserved);
+        }
+        for extensions in message.extensions().iter() {
+            self.extensions(extensions);
+        }
+        for nested in message.messages().iter() {
+            self.section();
+            self.message(nested);
+        }
+        for value in message.enums().iter() {
+            self.section();
+            self.enumeration(value);
+        }
+        for extend in message.extends().iter() {
+            self.section();
+            self.extend(extend);
+        }
+    }
+
+    fn fields(&mut self, fields: &[Field<'_>]) {
+        if fields.is_empty() {
+            return;
+        }
+        self.section();
+        let width = self.width(fields.iter().filter_map(|field| match field {
+            Field::Normal(field) => Some(self.normal_head(field).len()),
+            Field::Map(field) => Some(map_head(field).len()),
+            Field::OneOf(_) | Field::Group(_) => None,
+        }));
+        for field in fields {
+            match field {
+                Field::Normal(field) => {
+                    self.comments(field.detached_comments(), field.comments());
+                    let head = pad(self.normal_head(field), width);
+                    let head = format!("{head} = {}", field.number());
+                    self.declaration(&head, field.options(), ";");
+                    self.trailing(field.trailing_comments());
+                }
+                Field::Map(field) => {
+                    self.comments(field.detached_comments(), field.comments());
+                    let head = format!("{} = {}", pad(map_head(field), width), field.number());
+                    self.declaration(&head, field.options(), ";");
+                    self.trailing(field.trailing_comments());
+                }
+                Field::OneOf(field) => self.oneof(field),
+                Field::Group(field) => {
+                    self.comments(field.detached_comments(), field.comments());
+                    let label = self.label(*field.repeated(), *field.optional(), *field.required());
+                    let head =
+                        format!("{label}group {} = {}", field.name().value(), field.number());
+                    self.declaration(&head, field.options(), " {");
+                    self.depth += 1;
+                    self.fresh = true;
+                    self.trailing(field.trailing_comments());
+                    self.message_body(field.message());
+                    self.close();
+                }
+            }
+        }
+    }
+
+    fn oneof(&mut self, oneof: &OneOfField<'_>) {
+        self.comments(oneof.detached_comments(), oneof.comments());
+        self.open(&format!("oneof {} {{", oneof.name().value()));
+        self.trailing(oneof.trailing_comments());
+        self.options(oneof.options());
+        self.section();
+        let width = self.width(
+            oneof
+                .fields()
+                .iter()
+                .map(|field| oneof_head(field.ty(), field.name()).len()),
+        );
+        for field in oneof.fields().iter() {
+            self.comments(field.detached_comments(), field.comments());
+            let head = pad(oneof_head(field.ty(), field.name()), width);
+            let head = format!("{head} = {}", field.number());
+            self.declaration(&head, field.options(), ";");
+            self.trailing(field.trailing_comments());
+        }
+        self.close();
+    }
+
+    fn normal_head(&self, field: &NormalField<'_>) -> String {
+        let label = self.label(*field.repeated(), *field.optional(), *field.required());
+        format!("{label}{} {}", type_name(field.ty()), field.name().value())
+    }
+
+    /// Proto2 requires a label on every field outside of a oneof, so unlabelled fields are
+    /// written as `optional`.
+    fn label(&self, repeated: bool, optional: bool, required: bool) -> &'static str {
+        if repeated {
+            "repeated "
+        } else if required {
+            "required "
+        } else if optional || self.dialect == Dialect::Proto2 {
+            "optional "
+        } else {
+            ""
+        }
+    }
+
+    fn width(&self, widths: impl Iterator<Item = usize>) -> usize {
+        if self.printer.align {
+            widths.max().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
+    /// Ranges and names cannot share a `reserved` statement, so each gets its own.
+    fn reserved(&mut self, reserved: &ReservedItems<'_>) {
+        self.comments(reserved.detached_comments(), reserved.comments());
+        let mut ranges = Vec::new();
+        let mut names = Vec::new();
+        for item in reserved.items().iter() {
+            match item {
+                ReservedData::Range(start, end) => ranges.push(range(*start, *end)),
+                ReservedData::Field(name) if self.dialect == Dialect::Editions => {
+                    names.push(name.value().to_owned());
+                }
+                ReservedData::Field(name) => names.push(self.printer.quoted(name.value())),
+            }
+        }
+        for items in [ranges, names] {
+            if !items.is_empty() {
+                self.line(&format!("reserved {};", items.join(", ")));
+            }
+        }
+        self.trailing(reserved.trailing_comments());
+    }
+
+    fn extensions(&mut self, extensions: &Extensions<'_>) {
+        self.comments(extensions.detached_comments(), extensions.comments());
+        let ranges = extensions
+            .ranges()
+            .iter()
+            .map(|extension| range(*extension.start(), *extension.end()))
+            .collect::<Vec<_>>();
+        let head = format!("extensions {}", ranges.join(", "));
+        self.declaration(&head, extensions.options(), ";");
+        self.trailing(extensions.trailing_comments());
+    }
+
+    fn extend(&mut self, extend: &Extend<'_>) {
+        self.comments(extend.detached_comments(), extend.comments());
+        self.open(&format!("extend {} {{", extend.extendee().value()));
+        self.trailing(extend.trailing_comments());
+        self.fields(extend.fields());
+        self.close();
+    }
+
+    fn enumeration(&mut self, value: &Enum<'_>) {
+        self.comments(value.detached_comments(), value.comments());
+        let visibility = Self::visibility(*value.visibility());
+        self.open(&format!("{visibility}enum {} {{", value.name().value()));
+        self.trailing(value.trailing_comments());
+        self.options(value.options());
+        if !value.fields().is_empty() {
+            self.section();
+        }
+        let width = self.width(value.fields().iter().map(|item| item.name().value().len()));
+        for item in value.fields().iter() {
+            self.enum_item(item, width);
+        }
+        if !value.reserved().is_empty() {
+            self.section();
+        }
+        for reserved in value.reserved().iter() {
+            self.reserved(reserved);
+        }
+        self.close();
+    }
+
+    fn enum_item(&mut self, item: &EnumItem<'_>, width: usize) {
+        self.comments(item.detached_comments(), item.comments());
+        let head = pad(item.name().value().to_owned(), width);
+        let head = format!("{head} = {}", item.number());
+        self.declaration(&head, item.options(), ";");
+        self.trailing(item.trailing_comments());
+    }
+
+    fn service(&mut self, service: &Service<'_>) {
+        self.comments(service.detached_comments(), service.comments());
+        self.open(&format!("service {} {{", service.name().value()));
+        self.trailing(service.trailing_comments());
+        self.options(service.options());
+        if !service.rpcs().is_empty() {
+            self.section();
+        }
+        for rpc in service.rpcs().iter() {
+            self.rpc(rpc);
+        }
+        self.close();
+    }
+
+    fn rpc(&mut self, rpc: &ServiceRpc<'_>) {
+        self.comments(rpc.detached_comments(), rpc.comments());
+        let head = format!(
+            "rpc {}({}) returns ({})",
+            rpc.name().value(),
+            rpc_field(rpc.input()),
+            rpc_field(rpc.output())
+        );
+        if rpc.options().is_empty() {
+            self.line(&format!("{head};"));
+            self.trailing(rpc.trailing_comments());
+            return;
+        }
+        self.open(&format!("{head} {{"));
+        self.trailing(rpc.trailing_comments());
+        self.options(rpc.options());
+        self.close();
+    }
+}
+
+fn is_empty(message: &Message<'_>) -> bool {
+    message.options().is_empty()
+        && message.fields().is_empty()
+        && message.reserved().is_empty()
+        && message.extensions().is_empty()
+        && message.messages().is_empty()
+        && message.enums().is_empty()
+        && message.extends().is_empty()
+}
+
+fn pad(head: String, width: usize) -> String {
+    format!("{head:<width$}")
+}
+
+fn map_head(field: &MapField<'_>) -> String {
+    format!(
+        "map<{}, {}> {}",
+        key_type_name(*field.key_ty()),
+        type_name(field.value_ty()),
+        field.name().value()
+    )
+}
+
+fn oneof_head(ty: &Type<'_>, name: &Ident<'_>) -> String {
+    format!("{} {}", type_name(ty), name.value())
+}
+
+fn rpc_field(field: &ServiceRpcField<'_>) -> String {
+    let stream = if *field.stream() { "stream " } else { "" };
+    format!("{stream}{}", field.value().value())
+}
+
+/// `max` is stored as [`i64::MAX`].
+fn range(start: i64, end: i64) -> String {
+    if start == end {
+        start.to_string()
+    } else if end == i64::MAX {
+        format!("{start} to max")
+    } else {
+        format!("{start} to {end}")
+    }
+}
+
+/// Floats always get a `.` or an exponent, so they do not read back as integers.
+fn float(value: f64) -> String {
+    if value.is_nan() {
+        "nan".to_owned()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.to_owned()
+    } else {
+        format!("{value:?}")
+    }
+}
+
+fn type_name<'t>(ty: &'t Type<'_>) -> &'t str {
+    match ty {
+        Type::Double => "double",
+        Type::Float => "float",
+        Type::Int32 => "int32",
+        Type::Int64 => "int64",
+        Type::UInt32 => "uint32",
+        Type::UInt64 => "uint64",
+        Type::SInt32 => "sint32",
+        Type::SInt64 => "sint64",
+        Type::Fixed32 => "fixed32",
+        Type::Fixed64 => "fixed64",
+        Type::SFixed32 => "sfixed32",
+        Type::SFixed64 => "sfixed64",
+        Type::Bool => "bool",
+        Type::String => "string",
+        Type::Bytes => "bytes",
+        Type::Reference(name) => name,
+    }
+}
+
+fn key_type_name(ty: MapFieldKeyType) -> &'static str {
+    match ty {
+        MapFieldKeyType::Int32 => "int32",
+        MapFieldKeyType::Int64 => "int64",
+        MapFieldKeyType::UInt32 => "uint32",
+        MapFieldKeyType::UInt64 => "uint64",
+        MapFieldKeyType::SInt32 => "sint32",
+        MapFieldKeyType::SInt64 => "sint64",
+        MapFieldKeyType::Fixed32 => "fixed32",
+        MapFieldKeyType::Fixed64 => "fixed64",
+        MapFieldKeyType::SFixed32 => "sfixed32",
+        MapFieldKeyType::SFixed64 => "sfixed64",
+        MapFieldKeyType::Bool => "bool",
+        MapFieldKeyType::String => "string",
+    }
+}
@@ -0,0 +1,396 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+    mem,
+};
+
+use pest::{RuleType, iterators::Pairs};
+
+use crate::{
+    Node,
+    model::{
+        Comment, CommentKind, Enum, EnumItem, Extend, Extensions, Field, GroupField, Import,
+        MapField, Message, NormalField, OneOfField, OneOfFieldItem, Option, Package, Position,
+        ReservedItems, Service, ServiceRpc, Span, Syntax,
+    },
+};
+
+/// Leading, trailing and detached comments of every token in a file, attributed the way
+/// `protoc` fills `SourceCodeInfo.Location`.
+pub(crate) struct CommentIndex<'a> {
+    tokens: Vec<Token<'a>>,
+    starts: HashMap<usize, usize>,
+    ends: HashMap<usize, usize>,
+}
+
+#[derive(Default)]
+struct Token<'a> {
+    start: usize,
+    end: usize,
+    line: usize,
+    /// For a closing brace, the index of the matching opening brace.
+    open: std::option::Option<usize>,
+    closer: bool,
+    leading: Vec<Comment<'a>>,
+    trailing: Vec<Comment<'a>>,
+    detached: Vec<Comment<'a>>,
+}
+
+/// Comments found between two tokens.
+type Gap<'a> = Vec<Comment<'a>>;
+
+/// Comments read since the previous token, waiting to be attached.
+struct Collector<'a> {
+    buffer: Vec<Comment<'a>>,
+    can_attach: bool,
+    trailing: Vec<Comment<'a>>,
+    detached: Vec<Comment<'a>>,
+}
+impl<'a> Collector<'a> {
+    /// Line comments gather into one paragraph, while a block comment always starts a new one.
+    fn push(&mut self, comment: Comment<'a>) {
+        let continues = self
+            .buffer
+            .last()
+            .is_some_and(|last| is_line(last) && is_line(&comment));
+        if !self.buffer.is_empty() && !continues {
+            self.flush();
+        }
+        self.buffer.push(comment);
+    }
+
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        if self.can_attach {
+            self.trailing.append(&mut self.buffer);
+            self.can_attach = false;
+        } else {
+            self.detached.append(&mut self.buffer);
+        }
+    }
+
+    fn detach(&mut self) {
+        self.flush();
+        self.can_attach = false;
+    }
+}
+
+impl<'a> CommentIndex<'a> {
+    /// Indexes the comments of a file from the `comment` pairs found anywhere in `pairs`.
+    pub(crate) fn new<R: RuleType>(pairs: &Pairs<'a, R>, comment: R) -> Self {
+        let comments = pairs
+            .clone()
+            .flatten()
+            .filter(|pair| pair.as_rule() == comment)
+            .map(|pair| pair.as_span().start())
+            .collect();
+        let (tokens, gaps) = tokenize(pairs.get_input(), &comments);
+        let mut index = Self {
+            starts: tokens
+                .iter()
+                .enumerate()
+                .map(|(i, t)| (t.start, i))
+                .collect(),
+            ends: tokens.iter().enumerate().map(|(i, t)| (t.end, i)).collect(),
+            tokens,
+        };
+        let mut previous = None;
+        for (next, gap) in gaps.into_iter().enumerate() {
+            index.attribute(previous, next, gap);
+            previous = Some(next);
+        }
+        index
+    }
+
+    fn attribute(&mut self, previous: std::option::Option<usize>, next: usize, gap: Gap<'a>) {
+        let next_line = self.tokens.get(next).map(|token| token.line);
+        let end_of_scope = self.tokens.get(next).is_none_or(|token| token.closer);
+        let mut collector = Collector {
+            buffer: Vec::new(),
+            can_attach: previous.is_some(),
+            trailing: Vec::new(),
+            detached: Vec::new(),
+        };
+        let mut comments = gap.into_iter().peekable();
+        let mut line = 1;
+        if let Some(previous) = previous {
+            // A comment starting on the line of the previous token trails it.
+            let previous_line = self.tokens[previous].line;
+            line = previous_line + 1;
+            if let Some(comment) =
+                comments.next_if(|comment| comment.span().start().line() == previous_line)
+            {
+                let end_line = comment.span().end().line();
+                let following = comments
+                    .peek()
+                    .map(|comment| comment.span().start().line())
+                    .or(next_line);
+                if !is_line(&comment) && following == Some(end_line) {
+                    return;
+                }
+                collector.push(comment);
+                collector.flush();
+                line = end_line + 1;
+            } else if comments.peek().is_none() && next_line == Some(previous_line) {
+                return;
+            }
+        }
+        while let Some(comment) = comments.next() {
+            if comment.span().start().line() > line {
+                collector.detach();
+            }
+            let end_line = comment.span().end().line();
+            let following = comments
+                .peek()
+                .map(|comment| comment.span().start().line())
+                .or(next_line);
+            // A block comment followed by more text on its last line does not end that line.
+            line = if !is_line(&comment) && following == Some(end_line) {
+                end_line
+            } else {
+                end_line + 1
+            };
+            collector.push(comment);
+        }
+        if next_line.is_some_and(|next_line| next_line > line) {
+            collector.detach();
+        }
+        if end_of_scope {
+            collector.flush();
+        }
+        if let Some(previous) = previous {
+            self.tokens[previous].trailing = collector.trailing;
+        }
+        if let Some(token) = self.tokens.get_mut(next) {
+            token.leading = collector.buffer;
+            token.detached = collector.detached;
+        }
+    }
+
+    /// Moves the comments of `node` and of every declaration nested in it onto the model.
+    pub(crate) fn attach(&mut self, mut node: Node<'a>) -> Node<'a> {
+        match &mut node {
+            Node::Syntax(syntax) => self.assign(syntax),
+            Node::Package(package) => self.assign(package),
+            Node::Import(import) => self.assign(import),
+            Node::Option(option) => self.assign(option),
+            Node::Message(message) => self.message(message),
+            Node::Service(service) => self.service(service),
+            Node::Enum(value) => self.enumeration(value),
+            Node::Extend(extend) => self.extend(extend),
+            Node::Start | Node::End => {}
+        }
+        node
+    }
+
+    fn message(&mut self, message: &mut Message<'a>) {
+        self.assign(message);
+        self.message_body(message);
+    }
+
+    fn message_body(&mut self, message: &mut Message<'a>) {
+        self.assign_all(message.options.to_mut());
+        self.fields(message.fields.to_mut());
+        self.assign_all(message.reserved.to_mut());
+        self.assign_all(message.extensions.to_mut());
+        for nested in message.messages.to_mut() {
+            self.message(nested);
+        }
+        for value in message.enums.to_mut() {
+            self.enumeration(value);
+        }
+        for extend in message.extends.to_mut() {
+            self.extend(extend);
+        }
+    }
+
+    fn fields(&mut self, fields: &mut [Field<'a>]) {
+        for field in fields {
+            match field {
+                Field::Normal(field) => self.assign(field),
+                Field::Map(field) => self.assign(field),
+                Field::OneOf(oneof) => {
+                    self.assign(oneof);
+                    self.assign_all(oneof.options.to_mut());
+                    self.assign_all(oneof.fields.to_mut());
+                }
+                Field::Group(group) => {
+                    self.assign(group);
+                    self.message_body(&mut group.message);
+                }
+            }
+        }
+    }
+
+    fn enumeration(&mut self, value: &mut Enum<'a>) {
+        self.assign(value);
+        self.assign_all(value.options.to_mut());
+        self.assign_all(value.fields.to_mut());
+        self.assign_all(value.reserved.to_mut());
+    }
+
+    fn service(&mut self, service: &mut Service<'a>) {
+        self.assign(service);
+        self.assign_all(service.options.to_mut());
+        for rpc in service.rpcs.to_mut() {
+            self.assign(rpc);
+            self.assign_all(rpc.options.to_mut());
+        }
+    }
+
+    fn extend(&mut self, extend: &mut Extend<'a>) {
+        self.assign(extend);
+        self.fields(extend.fields.to_mut());
+    }
+
+    fn assign_all(&mut self, declarations: &mut [impl Commented<'a>]) {
+        for declaration in declarations {
+            self.assign(declaration);
+        }
+    }
+
+    fn assign(&mut self, declaration: &mut impl Commented<'a>) {
+        let (span, [leading, trailing, detached]) = declaration.comments_mut();
+        if let Some(&first) = self.starts.get(&span.start().offset()) {
+            *leading = Cow::Owned(mem::take(&mut self.tokens[first].leading));
+            *detached = Cow::Owned(mem::take(&mut self.tokens[first].detached));
+        }
+        if let Some(&last) = self.ends.get(&span.end().offset()) {
+            let last = self.tokens[last].open.unwrap_or(last);
+            *trailing = Cow::Owned(mem::take(&mut self.tokens[last].trailing));
+        }
+    }
+}
+
+/// Splits `source` into tokens and the comments found in the gap before each of them, given the
+/// offsets where the parser found comments. The gap after the last token is returned too, so
+/// there is one more gap than tokens.
+fn tokenize<'a>(source: &'a str, comments: &BTreeSet<usize>) -> (Vec<Token<'a>>, Vec<Gap<'a>>) {
+    let mut tokens: Vec<Token<'_>> = Vec::new();
+    let mut gaps = Vec::new();
+    let mut gap = Gap::new();
+    let mut braces = Vec::new();
+    let mut position = Position::new(0, 1, 1);
+    let mut offset = 0;
+    while offset < source.len() {
+        let start = offset;
+        let rest = &source[offset..];
+        let comment_here = comments.contains(&offset);
+        if let Some(line) = rest.strip_prefix("//").filter(|_| comment_here) {
+            let line = &line[..line.find('\n').unwrap_or(line.len())];
+            let value = line.strip_suffix('\r').unwrap_or(line);
+            offset += value.len() + 2;
+            let text = &source[start..offset];
+            gap.push(comment(value, CommentKind::Line, position, text));
+        } else if let Some(block) = rest.strip_prefix("/*").filter(|_| comment_here) {
+            let value = &block[..block.find("*/").unwrap_or(block.len())];
+            offset = (offset + value.len() + 4).min(source.len());
+            let text = &source[start..offset];
+            gap.push(comment(value, CommentKind::Block, position, text));
+        } else {
+            let char = rest.chars().next().unwrap_or_default();
+            if char.is_whitespace() {
+                offset += char.len_utf8();
+            } else {
+                // A token never runs into a comment found by the parser.
+                let end = offset + token_length(rest, char);
+                offset = comments
+                    .range(offset + 1..)
+                    .next()
+                    .map_or(end, |&next| end.min(next));
+                let mut token = Token {
+                    start,
+                    end: offset,
+                    line: position.line(),
+                    closer: matches!(char, '}' | ']' | ')'),
+                    ..Token::default()
+                };
+                match char {
+                    '{' => braces.push(tokens.len()),
+                    '}' => token.open = braces.pop(),
+                    _ => {}
+                }
+                tokens.push(token);
+                gaps.push(mem::take(&mut gap));
+            }
+        }
+        position = position.advance(&source[start..offset]);
+    }
+    gaps.push(gap);
+    (tokens, gaps)
+}
+
+fn token_length(rest: &str, first: char) -> usize {
+    match first {
+        '"' | '\'' => {
+            let mut escaped = false;
+            for (index, char) in rest.char_indices().skip(1) {
+                match char {
+                    '\\' if !escaped => escaped = true,
+                    '\n' => return index,
+                    char if char == first && !escaped => return index + 1,
+                    _ => escaped = false,
+                }
+            }
+            rest.len()
+        }
+        char if char.is_alphanumeric() || char == '_' || char == '.' => rest
+            .find(|char: char| !(char.is_alphanumeric() || char == '_' || char == '.'))
+            .unwrap_or(rest.len()),
+        char => char.len_utf8(),
+    }
+}
+
+fn is_line(comment: &Comment<'_>) -> bool {
+    *comment.kind() == CommentKind::Line
+}
+
+fn comment<'a>(value: &'a str, kind: CommentKind, start: Position, text: &str) -> Comment<'a> {
+    let mut comment = Comment::new(value);
+    comment.set_kind(kind);
+    comment.set_span(Span::new(start, start.advance(text)));
+    comment
+}
+
+/// A declaration that carries leading, trailing and detached comments.
+trait Commented<'a> {
+    fn comments_mut(&mut self) -> (Span, [&mut Cow<'a, [Comment<'a>]>; 3]);
+}
+
+macro_rules! commented {
+    ($($ty:ident),*) => {
+        $(impl<'a> Commented<'a> for $ty<'a> {
+            fn comments_mut(&mut self) -> (Span, [&mut Cow<'a, [Comment<'a>]>; 3]) {
+                (
+                    self.span,
+                    [
+                        &mut self.comments,
+                        &mut self.trailing_comments,
+                        &mut self.detached_comments,
+                    ],
+                )
+            }
+        })*
+    };
+}
+commented!(
+    Syntax,
+    Package,
+    Import,
+    Option,
+    Message,
+    Enum,
+    EnumItem,
+    Service,
+    ServiceRpc,
+    ReservedItems,
+    Extensions,
+    Extend,
+    NormalField,
+    MapField,
+    OneOfField,
+    OneOfFieldItem,
+    GroupField
+);
@@ -0,0 +1,1077 @@
+//! Declaration parsing shared by every `.proto` grammar.
+//!
+//! The grammars of proto2, proto3 and editions share their rules in `proto.pest` and only differ
+//! in which of them a declaration accepts, so their parsers are written once here and instantiated
+//! per grammar with [`declarations!`].
+
+/// Defines the parsing functions for the grammar of `$parser`, whose rule enum is `$rule`.
+///
+/// Grammars with groups pass the function parsing a `group` rule, which is then accepted in
+/// message bodies and `extend` blocks.
+macro_rules! declarations {
+    ($parser:ty, $rule:ty $(, group = $group:path)?) => {
+        use pest::{
+            Parser,
+            iterators::{Pair, Pairs},
+        };
+
+        use super::{
+            parse_aggregate, parse_bool, parse_literal_int, parse_literal_signed_int,
+            parse_literal_string, parse_literal_unsigned_int, parse_option_name,
+            parse_signed_float,
+        };
+        use $crate::{
+            Diagnostic, DiagnosticCode, Error, Node, ProtoVisitor,
+            model::{
+                self, Constant, Enum, EnumBuilder, EnumItem, Extend, ExtensionRange, Extensions,
+                Import, MapField, MapFieldKeyType, Message, MessageBuilder, NormalField,
+                OneOfField, OneOfFieldBuilder, OneOfFieldItem, Package, ReservedData,
+                ReservedItems, ReservedItemsBuilder, Service, ServiceBuilder, ServiceRpc,
+                ServiceRpcField, Span, Syntax, Type, Visibility,
+            },
+            syntax::{CommentIndex, declaration_span, ident_of, reference_of, relocate, span_of},
+        };
+
+        type DeclarationRule = $rule;
+        type DeclarationPairs<'a> = Pairs<'a, $rule>;
+        type DeclarationPair<'a> = Pair<'a, $rule>;
+        type DeclarationResult<T> = Result<T, Error>;
+
+        pub(super) fn parse_constant<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<Constant<'a>> {
+            let pairs = pair.into_inner();
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::FULL_IDENT => {
+                        return Ok(Constant::Ident(ident_of(&pair)));
+                    }
+                    DeclarationRule::SIGNED_INT_LIT => {
+                        return Ok(Constant::Int(parse_literal_signed_int(pair)?));
+                    }
+                    DeclarationRule::SIGNED_FLOAT_LIT => {
+                        return Ok(Constant::Float(parse_signed_float(pair)?));
+                    }
+                    DeclarationRule::BOOL_LIT => {
+                        return Ok(Constant::Bool(parse_bool(pair)?));
+                    }
+                    DeclarationRule::STRING_LIT => {
+                        return Ok(Constant::String(parse_literal_string(pair)?));
+                    }
+                    DeclarationRule::AGGREGATE => {
+                        return Ok(Constant::Aggregate(parse_aggregate(pair)?));
+                    }
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Err(Error::UndefinedParsingRoute)
+        }
+
+        pub(super) fn parse_inner<'a, F, R>(
+            pair: DeclarationPair<'a>,
+            rule: DeclarationRule,
+            handle: F,
+        ) -> DeclarationResult<R>
+        where
+            F: FnOnce(DeclarationPair<'a>) -> DeclarationResult<R>,
+        {
+            let pair = pair
+                .into_inner()
+                .next()
+                .ok_or(Error::UndefinedParsingRoute)?;
+            if pair.as_rule() == rule {
+                handle(pair)
+            } else {
+                Err(Error::UndefinedParsingRoute)
+            }
+        }
+
+        pub(super) fn parse_map_type<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<MapFieldKeyType> {
+            let pairs = pair.into_inner();
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_int32 => {
+                        return Ok(MapFieldKeyType::Int32);
+                    }
+                    DeclarationRule::keyword_int64 => {
+                        return Ok(MapFieldKeyType::Int64);
+                    }
+                    DeclarationRule::keyword_uint32 => {
+                        return Ok(MapFieldKeyType::UInt32);
+                    }
+                    DeclarationRule::keyword_uint64 => {
+                        return Ok(MapFieldKeyType::UInt64);
+                    }
+                    DeclarationRule::keyword_sint32 => {
+                        return Ok(MapFieldKeyType::SInt32);
+                    }
+                    DeclarationRule::keyword_sint64 => {
+                        return Ok(MapFieldKeyType::SInt64);
+                    }
+                    DeclarationRule::keyword_fixed32 => {
+                        return Ok(MapFieldKeyType::Fixed32);
+                    }
+                    DeclarationRule::keyword_fixed64 => {
+                        return Ok(MapFieldKeyType::Fixed64);
+                    }
+                    DeclarationRule::keyword_sfixed32 => {
+                        return Ok(MapFieldKeyType::SFixed32);
+                    }
+                    DeclarationRule::keyword_sfixed64 => {
+                        return Ok(MapFieldKeyType::SFixed64);
+                    }
+                    DeclarationRule::keyword_bool => {
+                        return Ok(MapFieldKeyType::Bool);
+                    }
+                    DeclarationRule::keyword_string => {
+                        return Ok(MapFieldKeyType::String);
+                    }
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Err(Error::UndefinedParsingRoute)
+        }
+
+        pub(super) fn parse_option<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<model::Option<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = model::Option::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::option_name => {
+                        builder.set_name(parse_option_name(pair)?);
+                    }
+                    DeclarationRule::CONSTANT => {
+                        builder.set_value_span(span_of(&pair));
+                        builder.set_value(parse_constant(pair)?);
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build()?)
+        }
+
+        pub(super) fn parse_package<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<Package<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Package::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::COMMENT => {}
+                    DeclarationRule::FULL_IDENT => {
+                        builder.set_value_span(span_of(&pair));
+                        builder.set_value(pair.as_str());
+                    }
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        pub(super) fn parse_service<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<Service<'a>> {
+            fn parse_service_body<'b>(
+                builder: &mut ServiceBuilder<'b>,
+                pair: DeclarationPair<'b>,
+            ) -> DeclarationResult<()> {
+                fn parse_service_rpc<'c>(
+                    pair: DeclarationPair<'c>,
+                ) -> DeclarationResult<ServiceRpc<'c>> {
+                    fn parse_service_rpc_field<'d>(
+                        pair: DeclarationPair<'d>,
+                    ) -> DeclarationResult<ServiceRpcField<'d>> {
+                        let span = declaration_span(&pair, DeclarationRule::COMMENT);
+                        let pairs = pair.into_inner();
+                        let mut builder = ServiceRpcField::builder();
+                        builder.set_span(span);
+                        for pair in pairs {
+                            let rule = pair.as_rule();
+                            match rule {
+                                DeclarationRule::keyword_stream => {
+                                    builder.set_stream(true);
+                                }
+                                DeclarationRule::message_type => {
+                                    builder.set_value(reference_of(&pair));
+                                }
+
+                                _ => {
+                                    return Err(Error::UndefinedParsingRoute);
+                                }
+                            }
+                        }
+                        builder.build().map_err(|_| Error::UndefinedParsingRoute)
+                    }
+                    let span = declaration_span(&pair, DeclarationRule::COMMENT);
+                    let pairs = pair.into_inner();
+                    let mut builder = ServiceRpc::builder();
+                    builder.set_span(span);
+                    for pair in pairs {
+                        let rule = pair.as_rule();
+
+                        match rule {
+                            DeclarationRule::rpc_name => {
+                                builder.set_name(ident_of(&pair));
+                            }
+                            DeclarationRule::rpc_input => {
+                                builder.set_input(parse_inner(
+                                    pair,
+                                    DeclarationRule::rpc_field,
+                                    parse_service_rpc_field,
+                                )?);
+                            }
+                            DeclarationRule::rpc_output => {
+                                builder.set_output(parse_inner(
+                                    pair,
+                                    DeclarationRule::rpc_field,
+                                    parse_service_rpc_field,
+                                )?);
+                            }
+                            DeclarationRule::option => {
+                                builder.with_option(parse_option(pair)?);
+                            }
+                            DeclarationRule::COMMENT => {}
+                            _ => {
+                                return Err(Error::UndefinedParsingRoute);
+                            }
+                        }
+                    }
+                    builder.build().map_err(|_| Error::UndefinedParsingRoute)
+                }
+                let pairs = pair.into_inner();
+                for pair in pairs {
+                    let rule = pair.as_rule();
+                    match rule {
+                        DeclarationRule::option => {
+                            builder.with_option(parse_option(pair)?);
+                        }
+                        DeclarationRule::rpc => {
+                            builder.with_rpc(parse_service_rpc(pair)?);
+                        }
+                        DeclarationRule::COMMENT => {}
+                        _ => {
+                            return Err(Error::UndefinedParsingRoute);
+                        }
+                    }
+                }
+                Ok(())
+            }
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Service::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+
+                match rule {
+                    DeclarationRule::COMMENT => {}
+                    DeclarationRule::service_name => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::service_body => {
+                        parse_service_body(&mut builder, pair)?;
+                    }
+
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        pub(super) fn parse_type<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Type<'a>> {
+            let pairs = pair.into_inner();
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_double => {
+                        return Ok(Type::Double);
+                    }
+                    DeclarationRule::keyword_float => {
+                        return Ok(Type::Float);
+                    }
+                    DeclarationRule::keyword_int32 => {
+                        return Ok(Type::Int32);
+                    }
+                    DeclarationRule::keyword_int64 => {
+                        return Ok(Type::Int64);
+                    }
+                    DeclarationRule::keyword_uint32 => {
+                        return Ok(Type::UInt32);
+                    }
+                    DeclarationRule::keyword_uint64 => {
+                        return Ok(Type::UInt64);
+                    }
+                    DeclarationRule::keyword_sint32 => {
+                        return Ok(Type::SInt32);
+                    }
+                    DeclarationRule::keyword_sint64 => {
+                        return Ok(Type::SInt64);
+                    }
+                    DeclarationRule::keyword_fixed32 => {
+                        return Ok(Type::Fixed32);
+                    }
+                    DeclarationRule::keyword_fixed64 => {
+                        return Ok(Type::Fixed64);
+                    }
+                    DeclarationRule::keyword_sfixed32 => {
+                        return Ok(Type::SFixed32);
+                    }
+                    DeclarationRule::keyword_sfixed64 => {
+                        return Ok(Type::SFixed64);
+                    }
+                    DeclarationRule::keyword_bool => {
+                        return Ok(Type::Bool);
+                    }
+                    DeclarationRule::keyword_string => {
+                        return Ok(Type::String);
+                    }
+                    DeclarationRule::keyword_bytes => {
+                        return Ok(Type::Bytes);
+                    }
+                    DeclarationRule::user_type => {
+                        return Ok(Type::Reference(pair.into_inner().as_str().into()));
+                    }
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Err(Error::UndefinedParsingRoute)
+        }
+
+        /// Parses the whole file `data` and visits its declarations, between [`Node::Start`] and
+        /// [`Node::End`].
+        pub(super) fn parse_proto<'a, Visitor>(
+            data: &'a str,
+            visitor: &mut Visitor,
+        ) -> DeclarationResult<()>
+        where
+            Visitor: ProtoVisitor<'a>,
+        {
+            let pairs = <$parser as Parser<$rule>>::parse(DeclarationRule::proto, data)?
+                .next()
+                .ok_or(Error::UndefinedParsingRoute)?
+                .into_inner();
+            let mut comments = CommentIndex::new(&pairs, DeclarationRule::COMMENT);
+            let edition_2023 = is_edition_2023(&pairs);
+            visitor.on(Node::Start);
+            for pair in pairs {
+                if visitor.is_done() {
+                    return Ok(());
+                }
+                match pair.as_rule() {
+                    DeclarationRule::EOI => break,
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        if edition_2023 {
+                            check_edition_2023(&pair)?;
+                        }
+                        visitor.on(comments.attach(parse_declaration(pair)?));
+                    }
+                }
+            }
+            visitor.on(Node::End);
+            Ok(())
+        }
+
+        /// Parses `data` like [`parse_proto`], but skips the declarations that fail to parse and
+        /// returns a diagnostic for each of them.
+        pub(super) fn parse_proto_recovering<'a, Visitor>(
+            data: &'a str,
+            visitor: &mut Visitor,
+        ) -> Vec<Diagnostic>
+        where
+            Visitor: ProtoVisitor<'a>,
+        {
+            let pairs = match <$parser as Parser<$rule>>::parse(DeclarationRule::proto_recover, data)
+            {
+                Ok(mut pairs) => match pairs.next() {
+                    Some(pair) => pair.into_inner(),
+                    None => return vec![Error::UndefinedParsingRoute.to_diagnostic()],
+                },
+                Err(error) => return vec![Error::from(error).to_diagnostic()],
+            };
+            let mut comments = CommentIndex::new(&pairs, DeclarationRule::COMMENT);
+            let edition_2023 = is_edition_2023(&pairs);
+            let mut diagnostics = Vec::new();
+            visitor.on(Node::Start);
+            for pair in pairs {
+                if visitor.is_done() {
+                    return diagnostics;
+                }
+                match pair.as_rule() {
+                    DeclarationRule::EOI => break,
+                    DeclarationRule::COMMENT => {}
+                    DeclarationRule::recovery => diagnostics.push(recovery_diagnostic(pair)),
+                    _ => {
+                        let span = declaration_span(&pair, DeclarationRule::COMMENT);
+                        let checked = if edition_2023 {
+                            check_edition_2023(&pair)
+                        } else {
+                            Ok(())
+                        };
+                        match checked.and_then(|()| parse_declaration(pair)) {
+                            Ok(node) => visitor.on(comments.attach(node)),
+                            Err(error) => {
+                                let diagnostic = error.to_diagnostic();
+                                diagnostics.push(if *diagnostic.span() == Span::default() {
+                                    diagnostic.with_span(span)
+                                } else {
+                                    diagnostic
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+            visitor.on(Node::End);
+            diagnostics
+        }
+
+        /// Whether the file declares `edition = "2023"`.
+        fn is_edition_2023(pairs: &DeclarationPairs<'_>) -> bool {
+            pairs
+                .clone()
+                .filter(|pair| pair.as_rule() == DeclarationRule::syntax)
+                .flat_map(|pair| pair.into_inner())
+                .any(|pair| {
+                    pair.as_rule() == DeclarationRule::syntax_value && pair.as_str() == "2023"
+                })
+        }
+
+        /// Rejects `import option` and the `export` and `local` visibilities, which edition 2023
+        /// predates, at the span of their keyword.
+        fn check_edition_2023(pair: &DeclarationPair<'_>) -> DeclarationResult<()> {
+            let keyword = pair.clone().into_inner().flatten().find(|pair| {
+                matches!(
+                    pair.as_rule(),
+                    DeclarationRule::keyword_option
+                        | DeclarationRule::keyword_export
+                        | DeclarationRule::keyword_local
+                )
+            });
+            match keyword {
+                Some(keyword) => {
+                    let name = match keyword.as_rule() {
+                        DeclarationRule::keyword_option => "import option",
+                        _ => keyword.as_str(),
+                    };
+                    let message = format!("`{name}` is not available in edition 2023");
+                    Err(Error::from(pest::error::Error::<$rule>::new_from_span(
+                        pest::error::ErrorVariant::CustomError { message },
+                        keyword.as_span(),
+                    )))
+                }
+                None => Ok(()),
+            }
+        }
+
+        /// Explains why a skipped region failed to parse, by parsing it again on its own with the
+        /// rule selected by its leading keyword.
+        fn recovery_diagnostic(pair: DeclarationPair<'_>) -> Diagnostic {
+            let span = span_of(&pair);
+            let text = pair.as_str();
+            let keyword = text
+                .split(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+                .next()
+                .unwrap_or_default();
+            let rule = match keyword {
+                "syntax" | "edition" => DeclarationRule::syntax,
+                "import" => DeclarationRule::import,
+                "package" => DeclarationRule::package,
+                "option" => DeclarationRule::option,
+                "message" => DeclarationRule::message,
+                "enum" => DeclarationRule::r#enum,
+                "service" => DeclarationRule::service,
+                "extend" => DeclarationRule::extend,
+                _ => {
+                    return Diagnostic::error(
+                        DiagnosticCode::Syntax,
+                        "expected `import`, `package`, `option`, `message`, `enum`, `service` or \
+                         `extend`",
+                        span,
+                    );
+                }
+            };
+            match <$parser as Parser<$rule>>::parse(rule, text) {
+                Err(error) => {
+                    let diagnostic = Diagnostic::from_pest(&error);
+                    let relocated = relocate(*diagnostic.span(), span.start(), text);
+                    diagnostic.with_span(relocated)
+                }
+                Ok(_) => Diagnostic::error(
+                    DiagnosticCode::Syntax,
+                    format!("unexpected `{keyword}` declaration"),
+                    span,
+                ),
+            }
+        }
+
+        fn parse_declaration<'a>(
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<Node<'a>> {
+            let rule = pair.as_rule();
+            match rule {
+                DeclarationRule::syntax => Ok(Node::Syntax(parse_syntax(pair)?)),
+                DeclarationRule::import => Ok(Node::Import(parse_import(pair)?)),
+                DeclarationRule::package => Ok(Node::Package(parse_package(pair)?)),
+                DeclarationRule::option => Ok(Node::Option(parse_option(pair)?)),
+                DeclarationRule::r#enum => Ok(Node::Enum(parse_enum(pair)?)),
+                DeclarationRule::message => Ok(Node::Message(parse_message(pair)?)),
+                DeclarationRule::service => Ok(Node::Service(parse_service(pair)?)),
+                DeclarationRule::extend => Ok(Node::Extend(parse_extend(pair)?)),
+                _ => Err(Error::UndefinedParsingRoute),
+            }
+        }
+
+        fn parse_syntax<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Syntax<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Syntax::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::COMMENT => {}
+                    DeclarationRule::keyword_edition => {
+                        builder.set_edition(true);
+                    }
+                    DeclarationRule::syntax_value => {
+                        builder.set_value_span(span_of(&pair));
+                        builder.set_value(pair.as_str());
+                    }
+
+                    _ => return Err(Error::UndefinedParsingRoute),
+                }
+            }
+
+            Ok(builder.build())
+        }
+
+        fn parse_import<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Import<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Import::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_weak => {
+                        builder.set_weak(true);
+                    }
+                    DeclarationRule::keyword_public => {
+                        builder.set_public(true);
+                    }
+                    DeclarationRule::keyword_option => {
+                        builder.set_option(true);
+                    }
+                    DeclarationRule::STRING_LIT => {
+                        builder.set_value_span(span_of(&pair));
+                        let output = parse_literal_string(pair)?;
+                        builder.set_value(output);
+                    }
+                    DeclarationRule::COMMENT => {}
+
+                    _ => return Err(Error::UndefinedParsingRoute),
+                }
+            }
+
+            Ok(builder.build())
+        }
+
+        fn parse_range(pair: DeclarationPair<'_>) -> DeclarationResult<(i64, i64)> {
+            let mut value = None;
+            let pairs = pair.into_inner();
+            for pair in pairs {
+                let rule = pair.as_rule();
+                let range_value = match rule {
+                    DeclarationRule::INT_LIT => parse_literal_int(pair),
+                    DeclarationRule::keyword_max => Ok(i64::MAX),
+                    _ => Err(Error::UndefinedParsingRoute),
+                }?;
+                value = if let Some((old, _)) = value {
+                    Some((old, range_value))
+                } else {
+                    Some((range_value, range_value))
+                };
+            }
+            value.ok_or(Error::UndefinedParsingRoute)
+        }
+
+        fn parse_reserved<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<ReservedItems<'a>> {
+            fn parse_reserved_ranges<'a>(
+                pair: DeclarationPair<'a>,
+                builder: &mut ReservedItemsBuilder<'a>,
+            ) -> DeclarationResult<()> {
+                let pairs = pair.into_inner();
+                for pair in pairs {
+                    let rule = pair.as_rule();
+                    match rule {
+                        DeclarationRule::range => {
+                            let (start, end) = parse_range(pair)?;
+                            builder.with_item(ReservedData::Range(start, end));
+                        }
+                        _ => {
+                            return Err(Error::UndefinedParsingRoute);
+                        }
+                    }
+                }
+                Ok(())
+            }
+            fn parse_reserved_fields<'a>(
+                pair: DeclarationPair<'a>,
+                builder: &mut ReservedItemsBuilder<'a>,
+            ) -> DeclarationResult<()> {
+                let pairs = pair.into_inner();
+                for pair in pairs {
+                    let rule = pair.as_rule();
+                    match rule {
+                        DeclarationRule::str_field_name => {
+                            let name = pair
+                                .into_inner()
+                                .find(|pair| pair.as_rule() == DeclarationRule::IDENT)
+                                .ok_or(Error::UndefinedParsingRoute)?;
+                            builder.with_item(ReservedData::Field(ident_of(&name)));
+                        }
+                        DeclarationRule::field_name => {
+                            builder.with_item(ReservedData::Field(ident_of(&pair)));
+                        }
+                        _ => {
+                            return Err(Error::UndefinedParsingRoute);
+                        }
+                    }
+                }
+                Ok(())
+            }
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = ReservedItems::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::ranges => {
+                        parse_reserved_ranges(pair, &mut builder)?;
+                    }
+                    DeclarationRule::str_field_names | DeclarationRule::field_names => {
+                        parse_reserved_fields(pair, &mut builder)?;
+                    }
+                    DeclarationRule::COMMENT => {}
+
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        fn parse_enum<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Enum<'a>> {
+            fn parse_enum_body<'b>(
+                builder: &mut EnumBuilder<'b>,
+                pair: DeclarationPair<'b>,
+            ) -> DeclarationResult<()> {
+                fn parse_enum_field<'c>(
+                    pair: DeclarationPair<'c>,
+                ) -> DeclarationResult<EnumItem<'c>> {
+                    let span = declaration_span(&pair, DeclarationRule::COMMENT);
+                    let pairs = pair.into_inner();
+                    let mut builder = EnumItem::builder();
+                    builder.set_span(span);
+                    for pair in pairs {
+                        let rule = pair.as_rule();
+
+                        match rule {
+                            DeclarationRule::IDENT => {
+                                builder.set_name(ident_of(&pair));
+                            }
+                            DeclarationRule::SIGNED_INT_LIT => {
+                                builder.set_number_span(span_of(&pair));
+                                builder.set_number(parse_literal_signed_int(pair)?);
+                            }
+                            DeclarationRule::enum_value_option => {
+                                builder.with_option(parse_option(pair)?);
+                            }
+                            DeclarationRule::COMMENT => {}
+                            _ => {
+                                return Err(Error::UndefinedParsingRoute);
+                            }
+                        }
+                    }
+                    Ok(builder.build())
+                }
+                let pairs = pair.into_inner();
+                for pair in pairs {
+                    let rule = pair.as_rule();
+
+                    match rule {
+                        DeclarationRule::option => {
+                            builder.with_option(parse_option(pair)?);
+                        }
+                        DeclarationRule::enum_field => {
+                            builder.with_field(parse_enum_field(pair)?);
+                        }
+                        DeclarationRule::reserved => {
+                            builder.with_reserved(parse_reserved(pair)?);
+                        }
+                        DeclarationRule::COMMENT => {}
+                        _ => {
+                            return Err(Error::UndefinedParsingRoute);
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Enum::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_export => {
+                        builder.set_visibility(Visibility::Export);
+                    }
+                    DeclarationRule::keyword_local => {
+                        builder.set_visibility(Visibility::Local);
+                    }
+                    DeclarationRule::enum_name => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::enum_body => {
+                        parse_enum_body(&mut builder, pair)?;
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        fn parse_normal_field<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<NormalField<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = NormalField::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_repeated => {
+                        builder.set_repeated(true);
+                    }
+                    DeclarationRule::keyword_optional => {
+                        builder.set_optional(true);
+                    }
+                    DeclarationRule::keyword_required => {
+                        builder.set_required(true);
+                    }
+                    DeclarationRule::r#type => {
+                        builder.set_ty_span(span_of(&pair));
+                        builder.set_ty(parse_type(pair)?);
+                    }
+                    DeclarationRule::IDENT => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::INT_LIT => {
+                        builder.set_number_span(span_of(&pair));
+                        builder.set_number(parse_literal_unsigned_int(pair)?);
+                    }
+                    DeclarationRule::field_option => {
+                        builder.with_option(parse_option(pair)?);
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build()?)
+        }
+
+        fn parse_extensions<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Extensions<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Extensions::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::ranges => {
+                        for pair in pair.into_inner() {
+                            let span = span_of(&pair);
+                            let (start, end) = parse_range(pair)?;
+                            let mut range = ExtensionRange::new(start, end);
+                            range.set_span(span);
+                            builder.with_range(range);
+                        }
+                    }
+                    DeclarationRule::field_option => {
+                        builder.with_option(parse_option(pair)?);
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        fn parse_extend<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Extend<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Extend::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::extend_type => {
+                        builder.set_extendee(reference_of(&pair));
+                    }
+                    DeclarationRule::extend_body => {
+                        for pair in pair.into_inner() {
+                            let rule = pair.as_rule();
+                            match rule {
+                                DeclarationRule::field => {
+                                    builder.with_field(model::Field::Normal(
+                                        parse_normal_field(pair)?,
+                                    ));
+                                }
+                                $(DeclarationRule::group => {
+                                    builder.with_field(model::Field::Group($group(pair)?));
+                                })?
+                                DeclarationRule::COMMENT => {}
+                                _ => {
+                                    return Err(Error::UndefinedParsingRoute);
+                                }
+                            }
+                        }
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build()?)
+        }
+
+        fn parse_message<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<Message<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = Message::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::keyword_export => {
+                        builder.set_visibility(Visibility::Export);
+                    }
+                    DeclarationRule::keyword_local => {
+                        builder.set_visibility(Visibility::Local);
+                    }
+                    DeclarationRule::message_name => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::message_body => {
+                        parse_message_body(&mut builder, pair)?;
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build()?)
+        }
+
+        pub(super) fn parse_message_body<'a>(
+            builder: &mut MessageBuilder<'a>,
+            pair: DeclarationPair<'a>,
+        ) -> DeclarationResult<()> {
+            let pairs = pair.into_inner();
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::option => {
+                        builder.with_option(parse_option(pair)?);
+                    }
+                    DeclarationRule::message => {
+                        builder.with_message(parse_message(pair)?);
+                    }
+                    DeclarationRule::r#enum => {
+                        builder.with_enum(parse_enum(pair)?);
+                    }
+                    DeclarationRule::reserved => {
+                        builder.with_reserved(parse_reserved(pair)?);
+                    }
+                    DeclarationRule::field => {
+                        builder.with_field(model::Field::Normal(parse_normal_field(pair)?));
+                    }
+                    $(DeclarationRule::group => {
+                        builder.with_field(model::Field::Group($group(pair)?));
+                    })?
+                    DeclarationRule::extensions => {
+                        builder.with_extensions(parse_extensions(pair)?);
+                    }
+                    DeclarationRule::extend => {
+                        builder.with_extend(parse_extend(pair)?);
+                    }
+                    DeclarationRule::one_of => {
+                        builder.with_field(model::Field::OneOf(parse_one_of_field(pair)?));
+                    }
+                    DeclarationRule::map_field => {
+                        builder.with_field(model::Field::Map(parse_map_field(pair)?));
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        fn parse_one_of_field<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<OneOfField<'a>> {
+            fn parse_one_of_body<'b>(
+                builder: &mut OneOfFieldBuilder<'b>,
+                pair: DeclarationPair<'b>,
+            ) -> DeclarationResult<()> {
+                fn parse_one_of_field_item<'c>(
+                    pair: DeclarationPair<'c>,
+                ) -> DeclarationResult<OneOfFieldItem<'c>> {
+                    let span = declaration_span(&pair, DeclarationRule::COMMENT);
+                    let pairs = pair.into_inner();
+                    let mut builder = OneOfFieldItem::builder();
+                    builder.set_span(span);
+                    for pair in pairs {
+                        let rule = pair.as_rule();
+                        match rule {
+                            DeclarationRule::r#type => {
+                                builder.set_ty_span(span_of(&pair));
+                                builder.set_ty(parse_type(pair)?);
+                            }
+                            DeclarationRule::IDENT => {
+                                builder.set_name(ident_of(&pair));
+                            }
+                            DeclarationRule::INT_LIT => {
+                                builder.set_number_span(span_of(&pair));
+                                builder.set_number(parse_literal_unsigned_int(pair)?);
+                            }
+                            DeclarationRule::field_option => {
+                                builder.with_option(parse_option(pair)?);
+                            }
+                            DeclarationRule::COMMENT => {}
+                            _ => {
+                                return Err(Error::UndefinedParsingRoute);
+                            }
+                        }
+                    }
+                    Ok(builder.build()?)
+                }
+                let pairs = pair.into_inner();
+                for pair in pairs {
+                    let rule = pair.as_rule();
+                    match rule {
+                        DeclarationRule::one_of_field => {
+                            builder.with_field(parse_one_of_field_item(pair)?);
+                        }
+                        DeclarationRule::option => {
+                            builder.with_option(parse_option(pair)?);
+                        }
+                        DeclarationRule::COMMENT => {}
+                        _ => {
+                            return Err(Error::UndefinedParsingRoute);
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = OneOfField::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::IDENT => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::one_of_body => {
+                        parse_one_of_body(&mut builder, pair)?;
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build())
+        }
+
+        fn parse_map_field<'a>(pair: DeclarationPair<'a>) -> DeclarationResult<MapField<'a>> {
+            let span = declaration_span(&pair, DeclarationRule::COMMENT);
+            let pairs = pair.into_inner();
+            let mut builder = MapField::builder();
+            builder.set_span(span);
+            for pair in pairs {
+                let rule = pair.as_rule();
+                match rule {
+                    DeclarationRule::key_type => {
+                        builder.set_key_ty_span(span_of(&pair));
+                        builder.set_key_ty(parse_map_type(pair)?);
+                    }
+
+                    DeclarationRule::r#type => {
+                        builder.set_value_ty_span(span_of(&pair));
+                        builder.set_value_ty(parse_type(pair)?);
+                    }
+                    DeclarationRule::IDENT => {
+                        builder.set_name(ident_of(&pair));
+                    }
+                    DeclarationRule::INT_LIT => {
+                        builder.set_number_span(span_of(&pair));
+                        builder.set_number(parse_literal_unsigned_int(pair)?);
+                    }
+                    DeclarationRule::field_option => {
+                        builder.with_option(parse_option(pair)?);
+                    }
+                    DeclarationRule::COMMENT => {}
+                    _ => {
+                        return Err(Error::UndefinedParsingRoute);
+                    }
+                }
+            }
+            Ok(builder.build()?)
+        }
+    };
+}
+
+pub(crate) use declarations;
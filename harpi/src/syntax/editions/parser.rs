@@ -0,0 +1,37 @@
+use crate::{
+    Diagnostic, Error, ProtoParser, ProtoVisitor,
+    editions::{parse_proto, parse_proto_recovering},
+    model::Syntax,
+};
+
+#[derive(Debug, Default, Clone, Copy, pest_derive::Parser)]
+#[grammar = "./grammar/literal.pest"]
+#[grammar = "./grammar/proto.pest"]
+#[grammar = "./grammar/editions.pest"]
+pub(crate) struct InternalParser;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Editions;
+
+type EditionsResult<T> = Result<T, Error>;
+
+impl ProtoParser for Editions {
+    const SYNTAX: &'static str = "editions";
+
+    fn parse<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> EditionsResult<()>
+    where
+        Visitor: ProtoVisitor<'a>,
+    {
+        parse_proto(data, visitor)
+    }
+
+    fn parse_recovering<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Vec<Diagnostic>
+    where
+        Visitor: ProtoVisitor<'a>,
+    {
+        parse_proto_recovering(data, visitor)
+    }
+
+    fn supports(syntax: &Syntax<'_>) -> bool {
+        *syntax.edition() && matches!(syntax.value().as_ref(), "2023" | "2024")
+    }
+}
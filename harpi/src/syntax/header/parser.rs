@@ -5,8 +5,10 @@ use pest::{
 use pest_derive::Parser;
 
 use crate::{
-    Error,
-    model::{Comment, Syntax},
+    Error, ProtoParser,
+    model::Syntax,
+    proto2::Proto2,
+    syntax::{declaration_span, span_of},
 };
 
 use super::literals::parse_literal_string;
@@ -21,6 +23,7 @@ type HeaderPairs<'a> = Pairs<'a, HeaderRule>;
 type HeaderPair<'a> = Pair<'a, HeaderRule>;
 type HeaderResult<T> = Result<T, Error>;
 
+/// Reads the `syntax` or `edition` statement of a file, returning it with the rest of the file.
 pub fn parse_header<'a>(input: &'a str) -> Result<(Syntax<'a>, &'a str), Error> {
     let pairs = InternalParser::parse(Rule::proto, input)?;
     let mut result = Err(Error::UndefinedParsingRoute);
@@ -57,21 +60,31 @@ fn parse<'a>(pairs: HeaderPairs<'a>) -> HeaderResult<(Syntax<'a>, usize)> {
             _ => return Err(Error::UndefinedParsingRoute),
         }
     }
-    syntax
-        .ok_or(Error::UndefinedParsingRoute)
-        .map(|syntax| (syntax, body_offset))
+    Ok((syntax.unwrap_or_else(default_syntax), body_offset))
+}
+
+/// Files without a `syntax` or `edition` statement are proto2, as in `protoc`. The syntax keeps
+/// an empty span since it is not written in the file.
+fn default_syntax<'a>() -> Syntax<'a> {
+    let mut builder = Syntax::builder();
+    builder.set_value(Proto2::SYNTAX);
+    builder.build()
 }
 
 fn parse_syntax<'a>(pair: HeaderPair<'a>) -> HeaderResult<Syntax<'a>> {
+    let span = declaration_span(&pair, Rule::COMMENT);
     let pairs = pair.into_inner();
     let mut builder = Syntax::builder();
+    builder.set_span(span);
     for pair in pairs {
         let rule = pair.as_rule();
         match rule {
-            Rule::COMMENT => {
-                builder.with_comment(parse_comment(pair)?);
+            Rule::COMMENT => {}
+            Rule::keyword_edition => {
+                builder.set_edition(true);
             }
             Rule::STRING_LIT => {
+                builder.set_value_span(span_of(&pair));
                 builder.set_value(parse_literal_string(pair)?);
             }
 
@@ -81,40 +94,3 @@ fn parse_syntax<'a>(pair: HeaderPair<'a>) -> HeaderResult<Syntax<'a>> {
 
     Ok(builder.build())
 }
-fn parse_comment<'a>(pair: HeaderPair<'a>) -> HeaderResult<Comment<'a>> {
-    let pairs = pair.into_inner();
-    for pair in pairs {
-        let rule = pair.as_rule();
-        match rule {
-            Rule::COMMENT_LINE => {
-                return parse_inner(pair, Rule::COMMENT_LINE_INNER, |pair| {
-                    Ok(Comment::new(pair.as_str()))
-                });
-            }
-            Rule::COMMENT_BLOCK => {
-                return parse_inner(pair, Rule::COMMENT_BLOCK_INNER, |pair| {
-                    Ok(Comment::new(pair.as_str()))
-                });
-            }
-
-            _ => {
-                return Err(Error::UndefinedParsingRoute);
-            }
-        }
-    }
-    Err(Error::UndefinedParsingRoute)
-}
-fn parse_inner<'a, F, R>(pair: HeaderPair<'a>, rule: HeaderRule, handle: F) -> HeaderResult<R>
-where
-    F: FnOnce(HeaderPair<'a>) -> HeaderResult<R>,
-{
-    let pair = pair
-        .into_inner()
-        .next()
-        .ok_or(Error::UndefinedParsingRoute)?;
-    if pair.as_rule() == rule {
-        handle(pair)
-    } else {
-        Err(Error::UndefinedParsingRoute)
-    }
-}
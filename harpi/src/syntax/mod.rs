@@ -1,2 +1,59 @@
+use pest::{RuleType, iterators::Pair};
+
+pub(crate) use comments::CommentIndex;
+use declarations::declarations;
+use literals::literals;
+
+use crate::model::{Ident, MessageReference, Position, Span};
+
+mod comments;
+mod declarations;
+pub mod editions;
 pub mod header;
+mod literals;
+pub mod proto2;
 pub mod proto3;
+
+/// Span of a pair, excluding surrounding whitespace.
+pub(crate) fn span_of<R: RuleType>(pair: &Pair<'_, R>) -> Span {
+    trimmed_span(pair, 0)
+}
+
+/// Span of a declaration, excluding the leading comments attached to it.
+pub(crate) fn declaration_span<R: RuleType>(pair: &Pair<'_, R>, comment: R) -> Span {
+    let skip = pair
+        .clone()
+        .into_inner()
+        .take_while(|pair| pair.as_rule() == comment)
+        .last()
+        .map_or(0, |last| last.as_span().end() - pair.as_span().start());
+    trimmed_span(pair, skip)
+}
+
+fn trimmed_span<R: RuleType>(pair: &Pair<'_, R>, skip: usize) -> Span {
+    let (line, column) = pair.line_col();
+    let origin = Position::new(pair.as_span().start(), line, column);
+    let text = pair.as_str();
+    let trimmed = text[skip..].trim_start();
+    let start = origin.advance(&text[..text.len() - trimmed.len()]);
+    Span::new(start, start.advance(trimmed.trim_end()))
+}
+
+/// Moves a span computed within `text` to the file, where `text` starts at `origin`.
+pub(crate) fn relocate(span: Span, origin: Position, text: &str) -> Span {
+    let (start, end) = (span.start().offset(), span.end().offset());
+    let start_position = origin.advance(&text[..start]);
+    Span::new(start_position, start_position.advance(&text[start..end]))
+}
+
+pub(crate) fn ident_of<'a, R: RuleType>(pair: &Pair<'a, R>) -> Ident<'a> {
+    let mut ident = Ident::new(false, pair.as_str());
+    ident.set_span(span_of(pair));
+    ident
+}
+
+pub(crate) fn reference_of<'a, R: RuleType>(pair: &Pair<'a, R>) -> MessageReference<'a> {
+    let mut reference = MessageReference::new(pair.as_str());
+    reference.set_span(span_of(pair));
+    reference
+}
@@ -0,0 +1,14 @@
+mod parser;
+pub use parser::*;
+mod literals {
+    crate::syntax::literals!(super::Rule);
+}
+use literals::*;
+mod declarations {
+    crate::syntax::declarations!(
+        super::InternalParser,
+        super::Rule,
+        group = super::parse_group_field
+    );
+}
+use declarations::*;
@@ -0,0 +1,83 @@
+use pest::iterators::Pair;
+
+use crate::{
+    Diagnostic, Error, ProtoParser, ProtoVisitor,
+    model::{GroupField, Message},
+    proto2::{
+        parse_literal_unsigned_int, parse_message_body, parse_option, parse_proto,
+        parse_proto_recovering,
+    },
+    syntax::{declaration_span, ident_of, span_of},
+};
+
+#[derive(Debug, Default, Clone, Copy, pest_derive::Parser)]
+#[grammar = "./grammar/literal.pest"]
+#[grammar = "./grammar/proto.pest"]
+#[grammar = "./grammar/proto2.pest"]
+pub(crate) struct InternalParser;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Proto2;
+
+type Proto2Rule = Rule;
+type Proto2Pair<'a> = Pair<'a, Proto2Rule>;
+type Proto2Result<T> = Result<T, Error>;
+
+impl ProtoParser for Proto2 {
+    const SYNTAX: &'static str = "proto2";
+
+    fn parse<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Proto2Result<()>
+    where
+        Visitor: ProtoVisitor<'a>,
+    {
+        parse_proto(data, visitor)
+    }
+
+    fn parse_recovering<'a, Visitor>(data: &'a str, visitor: &mut Visitor) -> Vec<Diagnostic>
+    where
+        Visitor: ProtoVisitor<'a>,
+    {
+        parse_proto_recovering(data, visitor)
+    }
+}
+
+pub(super) fn parse_group_field<'a>(pair: Proto2Pair<'a>) -> Proto2Result<GroupField<'a>> {
+    let span = declaration_span(&pair, Rule::COMMENT);
+    let pairs = pair.into_inner();
+    let mut builder = GroupField::builder();
+    builder.set_span(span);
+    let mut message = Message::builder();
+    message.set_span(span);
+    for pair in pairs {
+        let rule = pair.as_rule();
+        match rule {
+            Rule::keyword_repeated => {
+                builder.set_repeated(true);
+            }
+            Rule::keyword_optional => {
+                builder.set_optional(true);
+            }
+            Rule::keyword_required => {
+                builder.set_required(true);
+            }
+            Rule::group_name => {
+                message.set_name(ident_of(&pair));
+            }
+            Rule::INT_LIT => {
+                builder.set_number_span(span_of(&pair));
+                builder.set_number(parse_literal_unsigned_int(pair)?);
+            }
+            Rule::field_option => {
+                builder.with_option(parse_option(pair)?);
+            }
+            Rule::message_body => {
+                parse_message_body(&mut message, pair)?;
+            }
+            Rule::COMMENT => {}
+            _ => {
+                return Err(Error::UndefinedParsingRoute);
+            }
+        }
+    }
+    builder.set_message(message.build()?);
+    Ok(builder.build()?)
+}
@@ -1,4 +1,10 @@
 mod parser;
 pub use parser::*;
-mod literals;
+mod literals {
+    crate::syntax::literals!(super::Rule);
+}
 use literals::*;
+mod declarations {
+    crate::syntax::declarations!(super::InternalParser, super::Rule);
+}
+use declarations::*;
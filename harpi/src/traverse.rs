@@ -0,0 +1,164 @@
+//! The shape of the model as traversed by [`crate::visit`], [`crate::visit_mut`] and
+//! [`crate::fold`].
+//!
+//! All three traversals are generated from the one description below, so a node or field added
+//! here is visited, mutated and folded alike.
+
+/// Passes the description of the model to the generator macro `$generate`.
+///
+/// Every node names its `Visit`, `VisitMut` and `Fold` methods. Structs list the fields that are
+/// traversed, in order; enums list their variants, naming each payload; leaves have no children.
+/// Values are payload types that are not nodes and are left untouched.
+macro_rules! traversal {
+    ($generate:ident) => {
+        $generate! {
+            struct Proto<'a> (visit_proto, visit_proto_mut, fold_proto) {
+                syntax, package, imports, options, messages, enums, services, extends
+            }
+            struct Syntax<'a> (visit_syntax, visit_syntax_mut, fold_syntax) {
+                comments, trailing_comments, detached_comments
+            }
+            struct Package<'a> (visit_package, visit_package_mut, fold_package) {
+                comments, trailing_comments, detached_comments
+            }
+            struct Import<'a> (visit_import, visit_import_mut, fold_import) {
+                comments, trailing_comments, detached_comments
+            }
+            struct Option<'a> (visit_option, visit_option_mut, fold_option) {
+                name, value, comments, trailing_comments, detached_comments
+            }
+            struct OptionName<'a> (visit_option_name, visit_option_name_mut, fold_option_name) {
+                parts
+            }
+            struct Service<'a> (visit_service, visit_service_mut, fold_service) {
+                name, options, rpcs, comments, trailing_comments, detached_comments
+            }
+            struct ServiceRpc<'a> (visit_service_rpc, visit_service_rpc_mut, fold_service_rpc) {
+                name, input, output, options, comments, trailing_comments, detached_comments
+            }
+            struct ServiceRpcField<'a> (
+                visit_service_rpc_field,
+                visit_service_rpc_field_mut,
+                fold_service_rpc_field
+            ) {
+                value
+            }
+            struct Message<'a> (visit_message, visit_message_mut, fold_message) {
+                name, visibility, options, fields, reserved, extensions, messages, enums, extends,
+                comments, trailing_comments, detached_comments
+            }
+            struct Enum<'a> (visit_enum, visit_enum_mut, fold_enum) {
+                name, visibility, options, fields, reserved,
+                comments, trailing_comments, detached_comments
+            }
+            struct EnumItem<'a> (visit_enum_item, visit_enum_item_mut, fold_enum_item) {
+                name, options, comments, trailing_comments, detached_comments
+            }
+            struct ReservedItems<'a> (
+                visit_reserved_items,
+                visit_reserved_items_mut,
+                fold_reserved_items
+            ) {
+                items, comments, trailing_comments, detached_comments
+            }
+            struct Extensions<'a> (visit_extensions, visit_extensions_mut, fold_extensions) {
+                ranges, options, comments, trailing_comments, detached_comments
+            }
+            struct Extend<'a> (visit_extend, visit_extend_mut, fold_extend) {
+                extendee, fields, comments, trailing_comments, detached_comments
+            }
+            struct NormalField<'a> (visit_normal_field, visit_normal_field_mut, fold_normal_field) {
+                ty, name, options, comments, trailing_comments, detached_comments
+            }
+            struct GroupField<'a> (visit_group_field, visit_group_field_mut, fold_group_field) {
+                message, options, comments, trailing_comments, detached_comments
+            }
+            struct OneOfField<'a> (visit_one_of_field, visit_one_of_field_mut, fold_one_of_field) {
+                name, options, fields, comments, trailing_comments, detached_comments
+            }
+            struct OneOfFieldItem<'a> (
+                visit_one_of_field_item,
+                visit_one_of_field_item_mut,
+                fold_one_of_field_item
+            ) {
+                ty, name, options, comments, trailing_comments, detached_comments
+            }
+            struct MapField<'a> (visit_map_field, visit_map_field_mut, fold_map_field) {
+                key_ty, value_ty, name, options, comments, trailing_comments, detached_comments
+            }
+            struct Aggregate<'a> (visit_aggregate, visit_aggregate_mut, fold_aggregate) {
+                fields
+            }
+            struct AggregateField<'a> (
+                visit_aggregate_field,
+                visit_aggregate_field_mut,
+                fold_aggregate_field
+            ) {
+                name, value
+            }
+
+            enum OptionNamePart<'a> (
+                visit_option_name_part,
+                visit_option_name_part_mut,
+                fold_option_name_part
+            ) {
+                Ident(name), Extension(name)
+            }
+            enum ReservedData<'a> (
+                visit_reserved_data,
+                visit_reserved_data_mut,
+                fold_reserved_data
+            ) {
+                Range(start, end), Field(name)
+            }
+            enum Field<'a> (visit_field, visit_field_mut, fold_field) {
+                Normal(field), OneOf(field), Map(field), Group(field)
+            }
+            enum Constant<'a> (visit_constant, visit_constant_mut, fold_constant) {
+                Ident(ident), Int(value), Float(value), String(value), Bool(value),
+                Aggregate(aggregate)
+            }
+            enum AggregateFieldName<'a> (
+                visit_aggregate_field_name,
+                visit_aggregate_field_name_mut,
+                fold_aggregate_field_name
+            ) {
+                Field(name), Extension(name)
+            }
+            enum AggregateValue<'a> (
+                visit_aggregate_value,
+                visit_aggregate_value_mut,
+                fold_aggregate_value
+            ) {
+                Constant(constant), List(values)
+            }
+
+            leaf Visibility (visit_visibility, visit_visibility_mut, fold_visibility);
+            leaf ExtensionRange (
+                visit_extension_range,
+                visit_extension_range_mut,
+                fold_extension_range
+            );
+            leaf MapFieldKeyType (
+                visit_map_field_key_type,
+                visit_map_field_key_type_mut,
+                fold_map_field_key_type
+            );
+            leaf Type<'a> (visit_type, visit_type_mut, fold_type);
+            leaf MessageReference<'a> (
+                visit_message_reference,
+                visit_message_reference_mut,
+                fold_message_reference
+            );
+            leaf Ident<'a> (visit_ident, visit_ident_mut, fold_ident);
+            leaf Comment<'a> (visit_comment, visit_comment_mut, fold_comment);
+
+            value i64;
+            value f64;
+            value bool;
+            value Cow<'a, str>;
+        }
+    };
+}
+
+pub(crate) use traversal;
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use crate::{
+    Diagnostic, DiagnosticCode,
+    model::{
+        Constant, Enum, Extend, Field, Ident, Message, Option, Proto, ReservedData, ReservedItems,
+        Span,
+    },
+};
+
+/// Largest field number allowed, `2^29 - 1`.
+pub const MAX_FIELD_NUMBER: u64 = (1 << 29) - 1;
+/// Field numbers reserved for the protobuf implementation.
+pub const RESERVED_FIELD_NUMBERS: std::ops::RangeInclusive<u64> = 19000..=19999;
+
+/// Checks `proto` against the rules protoc enforces beyond the grammar, returning a diagnostic for
+/// each violation found.
+pub fn validate(proto: &Proto<'_>) -> Vec<Diagnostic> {
+    let syntax = if *proto.syntax().edition() {
+        Syntax::Editions
+    } else if proto.syntax().value() == "proto2" {
+        Syntax::Proto2
+    } else {
+        Syntax::Proto3
+    };
+    let mut validator = Validator {
+        syntax,
+        diagnostics: Vec::new(),
+    };
+    let closed = closed_enums(proto.options(), false);
+    for message in proto.messages().iter() {
+        validator.message(message, closed);
+    }
+    for value in proto.enums().iter() {
+        validator.enumeration(value, closed);
+    }
+    for extend in proto.extends().iter() {
+        validator.extend(extend, closed);
+    }
+    validator.diagnostics
+}
+
+/// Whether `options` make enums closed through `features.enum_type`, or `inherited` when they
+/// don't set it. Editions enums are open unless a file, message or enum closes them.
+fn closed_enums(options: &[Option<'_>], inherited: bool) -> bool {
+    let enum_type = options
+        .iter()
+        .rev()
+        .find(|option| option.feature() == Some("enum_type"));
+    match enum_type {
+        Some(option) => {
+            matches!(option.value(), Constant::Ident(value) if value.value() == "CLOSED")
+        }
+        None => inherited,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Proto2,
+    Proto3,
+    Editions,
+}
+
+/// A field number declared by a message, with what is needed to report it.
+struct Number<'m> {
+    name: &'m Ident<'m>,
+    number: u64,
+    number_span: Span,
+    required: bool,
+}
+
+struct Validator {
+    syntax: Syntax,
+    diagnostics: Vec<Diagnostic>,
+}
+impl Validator {
+    fn message(&mut self, message: &Message<'_>, closed: bool) {
+        let closed = closed_enums(message.options(), closed);
+        let mut numbers = Vec::new();
+        for field in message.fields().iter() {
+            match field {
+                Field::Normal(field) => numbers.push(Number {
+                    name: field.name(),
+                    number: *field.number(),
+                    number_span: *field.number_span(),
+                    required: *field.required(),
+                }),
+                Field::OneOf(field) => numbers.extend(field.fields().iter().map(|item| Number {
+                    name: item.name(),
+                    number: *item.number(),
+                    number_span: *item.number_span(),
+                    required: false,
+                })),
+                Field::Map(field) => numbers.push(Number {
+                    name: field.name(),
+                    number: *field.number(),
+                    number_span: *field.number_span(),
+                    required: false,
+                }),
+                Field::Group(field) => {
+                    numbers.push(Number {
+                        name: field.name(),
+                        number: *field.number(),
+                        number_span: *field.number_span(),
+                        required: *field.required(),
+                    });
+                    self.message(field.message(), closed);
+                }
+            }
+        }
+
+        let mut seen = HashMap::new();
+        for number in &numbers {
+            self.field_number(number);
+            self.required(number, false);
+            if let Some(first) = seen.insert(number.number, number.name.value()) {
+                self.report(
+                    DiagnosticCode::DuplicateFieldNumber,
+                    format!(
+                        "field number {} of `{}` is already used by `{first}`",
+                        number.number,
+                        number.name.value()
+                    ),
+                    number.number_span,
+                );
+                seen.insert(number.number, first);
+            }
+            self.reserved(
+                message.reserved(),
+                number.name,
+                i64::try_from(number.number).ok(),
+                number.number_span,
+                "field",
+            );
+        }
+
+        for nested in message.messages().iter() {
+            self.message(nested, closed);
+        }
+        for value in message.enums().iter() {
+            self.enumeration(value, closed);
+        }
+        for extend in message.extends().iter() {
+            self.extend(extend, closed);
+        }
+    }
+
+    fn extend(&mut self, extend: &Extend<'_>, closed: bool) {
+        for field in extend.fields().iter() {
+            let number = match field {
+                Field::Normal(field) => Number {
+                    name: field.name(),
+                    number: *field.number(),
+                    number_span: *field.number_span(),
+                    required: *field.required(),
+                },
+                Field::Group(field) => {
+                    self.message(field.message(), closed);
+                    Number {
+                        name: field.name(),
+                        number: *field.number(),
+                        number_span: *field.number_span(),
+                        required: *field.required(),
+                    }
+                }
+                Field::OneOf(_) | Field::Map(_) => continue,
+            };
+            self.field_number(&number);
+            self.required(&number, true);
+        }
+    }
+
+    /// Enums of proto3 files, and open enums of editions files, must start at zero.
+    fn enumeration(&mut self, value: &Enum<'_>, closed: bool) {
+        let open = match self.syntax {
+            Syntax::Proto2 => false,
+            Syntax::Proto3 => true,
+            Syntax::Editions => !closed_enums(value.options(), closed),
+        };
+        if let Some(first) = value.fields().first()
+            && open
+            && *first.number() != 0
+        {
+            self.report(
+                DiagnosticCode::EnumFirstValueNotZero,
+                format!(
+                    "the first value of enum `{}` must be zero, found {}",
+                    value.name().value(),
+                    first.number()
+                ),
+                *first.number_span(),
+            );
+        }
+
+        let allow_alias = value.allow_alias().unwrap_or_else(|diagnostic| {
+            self.diagnostics.push(*diagnostic);
+            false
+        });
+        let mut seen = HashMap::new();
+        for item in value.fields().iter() {
+            match seen.insert(*item.number(), item.name().value()) {
+                Some(first) if !allow_alias => {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            DiagnosticCode::DuplicateEnumValue,
+                            format!(
+                                "value {} of `{}` is already used by `{first}`",
+                                item.number(),
+                                item.name().value()
+                            ),
+                            *item.number_span(),
+                        )
+                        .with_note("set `option allow_alias = true;` to declare aliases"),
+                    );
+                    seen.insert(*item.number(), first);
+                }
+                _ => {}
+            }
+            self.reserved(
+                value.reserved(),
+                item.name(),
+                Some(*item.number()),
+                *item.number_span(),
+                "enum value",
+            );
+        }
+    }
+
+    fn field_number(&mut self, number: &Number<'_>) {
+        if number.number == 0 || number.number > MAX_FIELD_NUMBER {
+            self.report(
+                DiagnosticCode::FieldNumberOutOfRange,
+                format!(
+                    "field number {} of `{}` is outside of 1 to {MAX_FIELD_NUMBER}",
+                    number.number,
+                    number.name.value()
+                ),
+                number.number_span,
+            );
+        } else if RESERVED_FIELD_NUMBERS.contains(&number.number) {
+            self.report(
+                DiagnosticCode::ReservedFieldNumber,
+                format!(
+                    "field number {} of `{}` is reserved for the protobuf implementation",
+                    number.number,
+                    number.name.value()
+                ),
+                number.number_span,
+            );
+        }
+    }
+
+    fn required(&mut self, number: &Number<'_>, extension: bool) {
+        if !number.required {
+            return;
+        }
+        let message = match self.syntax {
+            Syntax::Proto3 => "`required` fields are not allowed in proto3",
+            Syntax::Editions => {
+                "`required` fields are not allowed in editions, use `features.field_presence = LEGACY_REQUIRED`"
+            }
+            Syntax::Proto2 if extension => "extension fields cannot be `required`",
+            Syntax::Proto2 => return,
+        };
+        self.report(
+            DiagnosticCode::RequiredMisuse,
+            format!("{message}, found `{}`", number.name.value()),
+            number.name.span(),
+        );
+    }
+
+    fn reserved(
+        &mut self,
+        reserved: &[ReservedItems<'_>],
+        name: &Ident<'_>,
+        number: std::option::Option<i64>,
+        number_span: Span,
+        kind: &str,
+    ) {
+        for data in reserved.iter().flat_map(|items| items.items().iter()) {
+            match data {
+                ReservedData::Range(start, end) => {
+                    if let Some(number) = number
+                        && (*start..=*end).contains(&number)
+                    {
+                        self.report(
+                            DiagnosticCode::ReservedFieldCollision,
+                            format!("{kind} `{}` uses reserved number {number}", name.value()),
+                            number_span,
+                        );
+                    }
+                }
+                ReservedData::Field(reserved) if reserved.value() == name.value() => {
+                    self.report(
+                        DiagnosticCode::ReservedFieldCollision,
+                        format!("{kind} name `{}` is reserved", name.value()),
+                        name.span(),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn report(&mut self, code: DiagnosticCode, message: String, span: Span) {
+        self.diagnostics
+            .push(Diagnostic::error(code, message, span));
+    }
+}
@@ -0,0 +1,114 @@
+//! Traversal of a borrowed model, in the style of `syn::visit`.
+//!
+//! Each method of [`Visit`] defaults to the free function of the same name, which visits the
+//! children of the node. Overriding a method and calling the free function from it keeps the
+//! traversal going below that node.
+
+use std::borrow::Cow;
+
+use crate::model::{
+    Aggregate, AggregateField, AggregateFieldName, AggregateValue, Comment, Constant, Enum,
+    EnumItem, Extend, ExtensionRange, Extensions, Field, GroupField, Ident, Import, MapField,
+    MapFieldKeyType, Message, MessageReference, NormalField, OneOfField, OneOfFieldItem, Option,
+    OptionName, OptionNamePart, Package, Proto, ReservedData, ReservedItems, Service, ServiceRpc,
+    ServiceRpcField, Syntax, Type, Visibility,
+};
+
+/// Generates [`Visit`] and its free functions from [`crate::traverse::traversal`].
+macro_rules! visit {
+    (@field $v:ident, $node:ident, options) => {
+        $v.visit_options(&$node.options);
+    };
+    (@field $v:ident, $node:ident, $field:ident) => {
+        Visitable::visit(&$node.$field, $v);
+    };
+    (
+        $(struct $struct:ident $(<$struct_lt:lifetime>)? (
+            $visit_struct:ident, $visit_struct_mut:ident, $fold_struct:ident $(,)?
+        ) {
+            $($field:ident),* $(,)?
+        })*
+        $(enum $enum:ident $(<$enum_lt:lifetime>)? (
+            $visit_enum:ident, $visit_enum_mut:ident, $fold_enum:ident $(,)?
+        ) {
+            $($variant:ident($($payload:ident),*)),* $(,)?
+        })*
+        $(leaf $leaf:ident $(<$leaf_lt:lifetime>)? (
+            $visit_leaf:ident, $visit_leaf_mut:ident, $fold_leaf:ident $(,)?
+        );)*
+        $(value $value:ty;)*
+    ) => {
+        /// Visits every node of a model by shared reference.
+        #[allow(unused_variables)]
+        pub trait Visit<'a> {
+            /// Visits the options of a declaration, one at a time through [`Visit::visit_option`].
+            fn visit_options(&mut self, node: &'a [Option<'a>]) {
+                visit_options(self, node)
+            }
+            $(fn $visit_struct(&mut self, node: &'a $struct$(<$struct_lt>)?) {
+                $visit_struct(self, node)
+            })*
+            $(fn $visit_enum(&mut self, node: &'a $enum$(<$enum_lt>)?) {
+                $visit_enum(self, node)
+            })*
+            $(fn $visit_leaf(&mut self, node: &'a $leaf$(<$leaf_lt>)?) {})*
+        }
+
+        $(pub fn $visit_struct<'a, V: Visit<'a> + ?Sized>(
+            v: &mut V,
+            node: &'a $struct$(<$struct_lt>)?,
+        ) {
+            $(visit!(@field v, node, $field);)*
+        }
+
+        impl<'a> Visitable<'a> for $struct$(<$struct_lt>)? {
+            fn visit<V: Visit<'a> + ?Sized>(&'a self, v: &mut V) {
+                v.$visit_struct(self);
+            }
+        })*
+
+        $(pub fn $visit_enum<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &'a $enum$(<$enum_lt>)?) {
+            match node {
+                $($enum::$variant($($payload),*) => {
+                    $(Visitable::visit($payload, v);)*
+                })*
+            }
+        }
+
+        impl<'a> Visitable<'a> for $enum$(<$enum_lt>)? {
+            fn visit<V: Visit<'a> + ?Sized>(&'a self, v: &mut V) {
+                v.$visit_enum(self);
+            }
+        })*
+
+        $(impl<'a> Visitable<'a> for $leaf$(<$leaf_lt>)? {
+            fn visit<V: Visit<'a> + ?Sized>(&'a self, v: &mut V) {
+                v.$visit_leaf(self);
+            }
+        })*
+
+        $(impl<'a> Visitable<'a> for $value {
+            fn visit<V: Visit<'a> + ?Sized>(&'a self, _: &mut V) {}
+        })*
+    };
+}
+crate::traverse::traversal!(visit);
+
+/// A field visited through the [`Visit`] method for its type.
+trait Visitable<'a> {
+    fn visit<V: Visit<'a> + ?Sized>(&'a self, v: &mut V);
+}
+
+impl<'a, T: Visitable<'a> + Clone> Visitable<'a> for Cow<'a, [T]> {
+    fn visit<V: Visit<'a> + ?Sized>(&'a self, v: &mut V) {
+        for item in self.iter() {
+            item.visit(v);
+        }
+    }
+}
+
+pub fn visit_options<'a, V: Visit<'a> + ?Sized>(v: &mut V, node: &'a [Option<'a>]) {
+    for option in node {
+        v.visit_option(option);
+    }
+}
@@ -0,0 +1,118 @@
+//! In-place traversal of a model, in the style of `syn::visit_mut`.
+//!
+//! Each method of [`VisitMut`] defaults to the free function of the same name, which visits the
+//! children of the node. Overriding a method and calling the free function from it keeps the
+//! traversal going below that node.
+
+use std::borrow::Cow;
+
+use crate::model::{
+    Aggregate, AggregateField, AggregateFieldName, AggregateValue, Comment, Constant, Enum,
+    EnumItem, Extend, ExtensionRange, Extensions, Field, GroupField, Ident, Import, MapField,
+    MapFieldKeyType, Message, MessageReference, NormalField, OneOfField, OneOfFieldItem, Option,
+    OptionName, OptionNamePart, Package, Proto, ReservedData, ReservedItems, Service, ServiceRpc,
+    ServiceRpcField, Syntax, Type, Visibility,
+};
+
+/// Generates [`VisitMut`] and its free functions from [`crate::traverse::traversal`].
+macro_rules! visit_mut {
+    (@field $v:ident, $node:ident, options) => {
+        $v.visit_options_mut($node.options.to_mut());
+    };
+    (@field $v:ident, $node:ident, $field:ident) => {
+        VisitableMut::visit_mut(&mut $node.$field, $v);
+    };
+    (
+        $(struct $struct:ident $(<$struct_lt:lifetime>)? (
+            $visit_struct:ident, $visit_struct_mut:ident, $fold_struct:ident $(,)?
+        ) {
+            $($field:ident),* $(,)?
+        })*
+        $(enum $enum:ident $(<$enum_lt:lifetime>)? (
+            $visit_enum:ident, $visit_enum_mut:ident, $fold_enum:ident $(,)?
+        ) {
+            $($variant:ident($($payload:ident),*)),* $(,)?
+        })*
+        $(leaf $leaf:ident $(<$leaf_lt:lifetime>)? (
+            $visit_leaf:ident, $visit_leaf_mut:ident, $fold_leaf:ident $(,)?
+        );)*
+        $(value $value:ty;)*
+    ) => {
+        /// Visits every node of a model by mutable reference.
+        #[allow(unused_variables)]
+        pub trait VisitMut<'a> {
+            /// Visits the options of a declaration, one at a time through
+            /// [`VisitMut::visit_option_mut`]. Overriding it allows adding or removing options.
+            fn visit_options_mut(&mut self, node: &mut Vec<Option<'a>>) {
+                visit_options_mut(self, node)
+            }
+            $(fn $visit_struct_mut(&mut self, node: &mut $struct$(<$struct_lt>)?) {
+                $visit_struct_mut(self, node)
+            })*
+            $(fn $visit_enum_mut(&mut self, node: &mut $enum$(<$enum_lt>)?) {
+                $visit_enum_mut(self, node)
+            })*
+            $(fn $visit_leaf_mut(&mut self, node: &mut $leaf$(<$leaf_lt>)?) {})*
+        }
+
+        $(pub fn $visit_struct_mut<'a, V: VisitMut<'a> + ?Sized>(
+            v: &mut V,
+            node: &mut $struct$(<$struct_lt>)?,
+        ) {
+            $(visit_mut!(@field v, node, $field);)*
+        }
+
+        impl<'a> VisitableMut<'a> for $struct$(<$struct_lt>)? {
+            fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, v: &mut V) {
+                v.$visit_struct_mut(self);
+            }
+        })*
+
+        $(pub fn $visit_enum_mut<'a, V: VisitMut<'a> + ?Sized>(
+            v: &mut V,
+            node: &mut $enum$(<$enum_lt>)?,
+        ) {
+            match node {
+                $($enum::$variant($($payload),*) => {
+                    $(VisitableMut::visit_mut($payload, v);)*
+                })*
+            }
+        }
+
+        impl<'a> VisitableMut<'a> for $enum$(<$enum_lt>)? {
+            fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, v: &mut V) {
+                v.$visit_enum_mut(self);
+            }
+        })*
+
+        $(impl<'a> VisitableMut<'a> for $leaf$(<$leaf_lt>)? {
+            fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, v: &mut V) {
+                v.$visit_leaf_mut(self);
+            }
+        })*
+
+        $(impl<'a> VisitableMut<'a> for $value {
+            fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, _: &mut V) {}
+        })*
+    };
+}
+crate::traverse::traversal!(visit_mut);
+
+/// A field visited through the [`VisitMut`] method for its type.
+trait VisitableMut<'a> {
+    fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, v: &mut V);
+}
+
+impl<'a, T: VisitableMut<'a> + Clone> VisitableMut<'a> for Cow<'a, [T]> {
+    fn visit_mut<V: VisitMut<'a> + ?Sized>(&mut self, v: &mut V) {
+        for item in self.to_mut() {
+            item.visit_mut(v);
+        }
+    }
+}
+
+pub fn visit_options_mut<'a, V: VisitMut<'a> + ?Sized>(v: &mut V, node: &mut Vec<Option<'a>>) {
+    for option in node {
+        v.visit_option_mut(option);
+    }
+}
@@ -1,4 +1,13 @@
-use crate::model::{Enum, Import, Message, Package, Service, Syntax};
+use std::ops::ControlFlow;
+
+use crate::{
+    Error,
+    model::{
+        Enum, EnumItem, Extend, Extensions, Field, GroupField, Import, MapField, Message,
+        NormalField, OneOfField, OneOfFieldItem, Package, Proto, ProtoBuilder, ReservedItems,
+        Service, ServiceRpc, Syntax,
+    },
+};
 
 #[derive(Debug, Clone)]
 pub enum Node<'a> {
@@ -10,8 +19,463 @@ pub enum Node<'a> {
     Message(Message<'a>),
     Service(Service<'a>),
     Enum(Enum<'a>),
+    Extend(Extend<'a>),
     End,
 }
-pub trait ProtoVisitor {
-    fn on<'a>(&mut self, node: Node<'a>);
+pub trait ProtoVisitor<'a> {
+    fn on(&mut self, node: Node<'a>);
+
+    /// Checked by parsers before each declaration. Once it returns `true`, parsing stops and no
+    /// further nodes are reported.
+    fn is_done(&self) -> bool {
+        false
+    }
+}
+
+/// A visitor that can stop the parse early, either because it has seen enough or because it
+/// failed. Run it with [`ProtoParser::try_parse`](crate::ProtoParser::try_parse), which returns
+/// the error wrapped in [`Error::Visitor`].
+pub trait TryProtoVisitor<'a> {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Returns [`ControlFlow::Break`] to stop parsing without an error.
+    fn try_on(&mut self, node: Node<'a>) -> Result<ControlFlow<()>, Self::Error>;
+}
+
+/// Runs a [`TryProtoVisitor`] where a [`ProtoVisitor`] is expected. Once the visitor breaks or
+/// fails, it is done, and the error is kept until [`TryVisitor::finish`] is called.
+pub struct TryVisitor<'v, V> {
+    visitor: &'v mut V,
+    done: bool,
+    error: std::option::Option<Error>,
+}
+impl<'v, V> TryVisitor<'v, V> {
+    pub fn new(visitor: &'v mut V) -> Self {
+        Self {
+            visitor,
+            done: false,
+            error: None,
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+impl<'a, V: TryProtoVisitor<'a>> ProtoVisitor<'a> for TryVisitor<'_, V> {
+    fn on(&mut self, node: Node<'a>) {
+        if self.done {
+            return;
+        }
+        match self.visitor.try_on(node) {
+            Ok(ControlFlow::Continue(())) => {}
+            Ok(ControlFlow::Break(())) => self.done = true,
+            Err(error) => {
+                self.done = true;
+                self.error = Some(Error::Visitor(Box::new(error)));
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+/// Forwards nodes to a visitor, checking that the file declares the expected syntax. On a
+/// mismatch the syntax node is not forwarded, the visitor is done, and [`SyntaxCheck::finish`]
+/// returns [`Error::InvalidSyntax`].
+pub struct SyntaxCheck<'v, 'a, V> {
+    visitor: &'v mut V,
+    expected: Syntax<'a>,
+    error: std::option::Option<Error>,
+}
+impl<'v, 'a, V> SyntaxCheck<'v, 'a, V> {
+    pub fn new(expected: Syntax<'a>, visitor: &'v mut V) -> Self {
+        Self {
+            visitor,
+            expected,
+            error: None,
+        }
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+impl<'a, V: ProtoVisitor<'a>> ProtoVisitor<'a> for SyntaxCheck<'_, 'a, V> {
+    fn on(&mut self, node: Node<'a>) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Node::Syntax(syntax) = &node
+            && (syntax.value() != self.expected.value()
+                || syntax.edition() != self.expected.edition())
+        {
+            self.error = Some(Error::InvalidSyntax(
+                self.expected.value().to_string(),
+                syntax.value().to_string(),
+            ));
+            return;
+        }
+        self.visitor.on(node);
+    }
+
+    fn is_done(&self) -> bool {
+        self.error.is_some() || self.visitor.is_done()
+    }
+}
+
+/// Visitor that accumulates every top-level node into a [`Proto`]. Declarations are kept in the
+/// order they were visited. Since [`ProtoVisitor::on`] cannot fail, the first error encountered
+/// is held until [`ProtoCollector::finish`] is called.
+pub struct ProtoCollector<'a> {
+    builder: ProtoBuilder<'a>,
+    package: std::option::Option<Package<'a>>,
+    error: std::option::Option<Error>,
+}
+impl<'a> ProtoCollector<'a> {
+    pub fn new() -> Self {
+        Self {
+            builder: Proto::builder(),
+            package: None,
+            error: None,
+        }
+    }
+
+    pub fn finish(self) -> Result<Proto<'a>, Error> {
+        match self.finish_partial() {
+            (_, Some(error)) => Err(error),
+            (proto, None) => Ok(proto),
+        }
+    }
+
+    /// Like [`ProtoCollector::finish`], but keeps what was collected even if an error occurred.
+    pub fn finish_partial(self) -> (Proto<'a>, std::option::Option<Error>) {
+        let mut builder = self.builder;
+        if let Some(package) = self.package {
+            builder.set_package(package);
+        }
+        (builder.build(), self.error)
+    }
+}
+impl Default for ProtoCollector<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<'a> ProtoVisitor<'a> for ProtoCollector<'a> {
+    fn on(&mut self, node: Node<'a>) {
+        match node {
+            Node::Syntax(syntax) => {
+                self.builder.set_syntax(syntax);
+            }
+            Node::Package(package) => match &self.package {
+                Some(existing) if self.error.is_none() => {
+                    self.error = Some(Error::DuplicatePackage(
+                        existing.value().to_string(),
+                        package.value().to_string(),
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    self.package = Some(package);
+                }
+            },
+            Node::Import(import) => {
+                self.builder.with_import(import);
+            }
+            Node::Option(option) => {
+                self.builder.with_option(option);
+            }
+            Node::Message(message) => {
+                self.builder.with_message(message);
+            }
+            Node::Service(service) => {
+                self.builder.with_service(service);
+            }
+            Node::Enum(r#enum) => {
+                self.builder.with_enum(r#enum);
+            }
+            Node::Extend(extend) => {
+                self.builder.with_extend(extend);
+            }
+            Node::Start | Node::End => {}
+        }
+    }
+}
+
+/// What a [`Scope`] segment names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Message,
+    Field,
+    Oneof,
+    Enum,
+    EnumValue,
+    Service,
+    Rpc,
+}
+
+/// The declarations enclosing the element being visited, outermost first, along with the
+/// package of the file.
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    package: String,
+    path: Vec<(ScopeKind, String)>,
+}
+impl Scope {
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    pub fn path(&self) -> &[(ScopeKind, String)] {
+        &self.path
+    }
+
+    /// Fully qualified name of the current element, without a leading dot. Names follow protobuf
+    /// scoping: enum values are siblings of their enum, and oneof members are fields of the
+    /// enclosing message.
+    pub fn full_name(&self) -> String {
+        let mut full_name = self.package.clone();
+        for (index, (kind, name)) in self.path.iter().enumerate() {
+            let next = self.path.get(index + 1).map(|(kind, _)| *kind);
+            let skipped = match kind {
+                ScopeKind::Enum => next == Some(ScopeKind::EnumValue),
+                ScopeKind::Oneof => next.is_some(),
+                _ => false,
+            };
+            if skipped {
+                continue;
+            }
+            if !full_name.is_empty() {
+                full_name.push('.');
+            }
+            full_name.push_str(name);
+        }
+        full_name
+    }
+
+    fn push(&mut self, kind: ScopeKind, name: &str) {
+        self.path.push((kind, name.to_owned()));
+    }
+
+    fn pop(&mut self) {
+        self.path.pop();
+    }
+}
+
+/// Visitor called for every declaration of a file, nested ones included. Declarations with a body
+/// get an `enter_*` call before their content and an `exit_*` call after it. The [`Scope`] passed
+/// along already includes the declaration itself. Every method does nothing by default.
+#[allow(unused_variables)]
+pub trait ScopeVisitor<'a> {
+    fn visit_syntax(&mut self, scope: &Scope, syntax: &Syntax<'a>) {}
+    fn visit_package(&mut self, scope: &Scope, package: &Package<'a>) {}
+    fn visit_import(&mut self, scope: &Scope, import: &Import<'a>) {}
+    /// An `option` statement or a `[...]` option, scoped to the declaration it applies to.
+    fn visit_option(&mut self, scope: &Scope, option: &crate::model::Option<'a>) {}
+    fn visit_reserved(&mut self, scope: &Scope, reserved: &ReservedItems<'a>) {}
+    fn visit_extensions(&mut self, scope: &Scope, extensions: &Extensions<'a>) {}
+    fn enter_message(&mut self, scope: &Scope, message: &Message<'a>) {}
+    fn exit_message(&mut self, scope: &Scope, message: &Message<'a>) {}
+    fn enter_field(&mut self, scope: &Scope, field: &NormalField<'a>) {}
+    fn exit_field(&mut self, scope: &Scope, field: &NormalField<'a>) {}
+    fn enter_map_field(&mut self, scope: &Scope, field: &MapField<'a>) {}
+    fn exit_map_field(&mut self, scope: &Scope, field: &MapField<'a>) {}
+    /// A group, scoped as the message it declares.
+    fn enter_group(&mut self, scope: &Scope, group: &GroupField<'a>) {}
+    fn exit_group(&mut self, scope: &Scope, group: &GroupField<'a>) {}
+    fn enter_oneof(&mut self, scope: &Scope, oneof: &OneOfField<'a>) {}
+    fn exit_oneof(&mut self, scope: &Scope, oneof: &OneOfField<'a>) {}
+    fn enter_oneof_field(&mut self, scope: &Scope, field: &OneOfFieldItem<'a>) {}
+    fn exit_oneof_field(&mut self, scope: &Scope, field: &OneOfFieldItem<'a>) {}
+    fn enter_enum(&mut self, scope: &Scope, value: &Enum<'a>) {}
+    fn exit_enum(&mut self, scope: &Scope, value: &Enum<'a>) {}
+    fn enter_enum_value(&mut self, scope: &Scope, item: &EnumItem<'a>) {}
+    fn exit_enum_value(&mut self, scope: &Scope, item: &EnumItem<'a>) {}
+    fn enter_service(&mut self, scope: &Scope, service: &Service<'a>) {}
+    fn exit_service(&mut self, scope: &Scope, service: &Service<'a>) {}
+    fn enter_rpc(&mut self, scope: &Scope, rpc: &ServiceRpc<'a>) {}
+    fn exit_rpc(&mut self, scope: &Scope, rpc: &ServiceRpc<'a>) {}
+    /// An `extend` block, scoped to the package or message it appears in.
+    fn enter_extend(&mut self, scope: &Scope, extend: &Extend<'a>) {}
+    fn exit_extend(&mut self, scope: &Scope, extend: &Extend<'a>) {}
+}
+
+/// Walks every declaration of `proto`, in the order the printer writes them.
+pub fn walk<'a, V: ScopeVisitor<'a>>(proto: &Proto<'a>, visitor: &mut V) {
+    let mut walker = ScopeWalker::new(visitor);
+    walker.scope.package = proto.package().value().to_string();
+    walker.visitor.visit_syntax(&walker.scope, proto.syntax());
+    walker.visitor.visit_package(&walker.scope, proto.package());
+    for import in proto.imports().iter() {
+        walker.visitor.visit_import(&walker.scope, import);
+    }
+    walker.options(proto.options());
+    for message in proto.messages().iter() {
+        walker.message(message);
+    }
+    for value in proto.enums().iter() {
+        walker.enumeration(value);
+    }
+    for service in proto.services().iter() {
+        walker.service(service);
+    }
+    for extend in proto.extends().iter() {
+        walker.extend(extend);
+    }
+}
+
+/// Adapts a [`ScopeVisitor`] to the top-level nodes reported while parsing, walking into each
+/// declaration as it arrives.
+pub struct ScopeWalker<'v, V> {
+    visitor: &'v mut V,
+    scope: Scope,
+}
+impl<'v, V> ScopeWalker<'v, V> {
+    pub fn new(visitor: &'v mut V) -> Self {
+        Self {
+            visitor,
+            scope: Scope::default(),
+        }
+    }
+}
+impl<'a, V: ScopeVisitor<'a>> ProtoVisitor<'a> for ScopeWalker<'_, V> {
+    fn on(&mut self, node: Node<'a>) {
+        match &node {
+            Node::Syntax(syntax) => self.visitor.visit_syntax(&self.scope, syntax),
+            Node::Package(package) => {
+                self.scope.package = package.value().to_string();
+                self.visitor.visit_package(&self.scope, package);
+            }
+            Node::Import(import) => self.visitor.visit_import(&self.scope, import),
+            Node::Option(option) => self.visitor.visit_option(&self.scope, option),
+            Node::Message(message) => self.message(message),
+            Node::Service(service) => self.service(service),
+            Node::Enum(value) => self.enumeration(value),
+            Node::Extend(extend) => self.extend(extend),
+            Node::Start | Node::End => {}
+        }
+    }
+}
+impl<'a, V: ScopeVisitor<'a>> ScopeWalker<'_, V> {
+    fn options(&mut self, options: &[crate::model::Option<'a>]) {
+        for option in options {
+            self.visitor.visit_option(&self.scope, option);
+        }
+    }
+
+    fn message(&mut self, message: &Message<'a>) {
+        self.scope.push(ScopeKind::Message, message.name().value());
+        self.visitor.enter_message(&self.scope, message);
+        self.message_body(message);
+        self.visitor.exit_message(&self.scope, message);
+        self.scope.pop();
+    }
+
+    fn message_body(&mut self, message: &Message<'a>) {
+        self.options(message.options());
+        self.fields(message.fields());
+        for reserved in message.reserved().iter() {
+            self.visitor.visit_reserved(&self.scope, reserved);
+        }
+        for extensions in message.extensions().iter() {
+            self.visitor.visit_extensions(&self.scope, extensions);
+            self.options(extensions.options());
+        }
+        for nested in message.messages().iter() {
+            self.message(nested);
+        }
+        for value in message.enums().iter() {
+            self.enumeration(value);
+        }
+        for extend in message.extends().iter() {
+            self.extend(extend);
+        }
+    }
+
+    fn fields(&mut self, fields: &[Field<'a>]) {
+        for field in fields {
+            match field {
+                Field::Normal(field) => {
+                    self.scope.push(ScopeKind::Field, field.name().value());
+                    self.visitor.enter_field(&self.scope, field);
+                    self.options(field.options());
+                    self.visitor.exit_field(&self.scope, field);
+                    self.scope.pop();
+                }
+                Field::Map(field) => {
+                    self.scope.push(ScopeKind::Field, field.name().value());
+                    self.visitor.enter_map_field(&self.scope, field);
+                    self.options(field.options());
+                    self.visitor.exit_map_field(&self.scope, field);
+                    self.scope.pop();
+                }
+                Field::Group(group) => {
+                    self.scope.push(ScopeKind::Message, group.name().value());
+                    self.visitor.enter_group(&self.scope, group);
+                    self.options(group.options());
+                    self.message_body(group.message());
+                    self.visitor.exit_group(&self.scope, group);
+                    self.scope.pop();
+                }
+                Field::OneOf(oneof) => self.oneof(oneof),
+            }
+        }
+    }
+
+    fn oneof(&mut self, oneof: &OneOfField<'a>) {
+        self.scope.push(ScopeKind::Oneof, oneof.name().value());
+        self.visitor.enter_oneof(&self.scope, oneof);
+        self.options(oneof.options());
+        for field in oneof.fields().iter() {
+            self.scope.push(ScopeKind::Field, field.name().value());
+            self.visitor.enter_oneof_field(&self.scope, field);
+            self.options(field.options());
+            self.visitor.exit_oneof_field(&self.scope, field);
+            self.scope.pop();
+        }
+        self.visitor.exit_oneof(&self.scope, oneof);
+        self.scope.pop();
+    }
+
+    fn enumeration(&mut self, value: &Enum<'a>) {
+        self.scope.push(ScopeKind::Enum, value.name().value());
+        self.visitor.enter_enum(&self.scope, value);
+        self.options(value.options());
+        for item in value.fields().iter() {
+            self.scope.push(ScopeKind::EnumValue, item.name().value());
+            self.visitor.enter_enum_value(&self.scope, item);
+            self.options(item.options());
+            self.visitor.exit_enum_value(&self.scope, item);
+            self.scope.pop();
+        }
+        for reserved in value.reserved().iter() {
+            self.visitor.visit_reserved(&self.scope, reserved);
+        }
+        self.visitor.exit_enum(&self.scope, value);
+        self.scope.pop();
+    }
+
+    fn service(&mut self, service: &Service<'a>) {
+        self.scope.push(ScopeKind::Service, service.name().value());
+        self.visitor.enter_service(&self.scope, service);
+        self.options(service.options());
+        for rpc in service.rpcs().iter() {
+            self.scope.push(ScopeKind::Rpc, rpc.name().value());
+            self.visitor.enter_rpc(&self.scope, rpc);
+            self.options(rpc.options());
+            self.visitor.exit_rpc(&self.scope, rpc);
+            self.scope.pop();
+        }
+        self.visitor.exit_service(&self.scope, service);
+        self.scope.pop();
+    }
+
+    fn extend(&mut self, extend: &Extend<'a>) {
+        self.visitor.enter_extend(&self.scope, extend);
+        self.fields(extend.fields());
+        self.visitor.exit_extend(&self.scope, extend);
+    }
 }
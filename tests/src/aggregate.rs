@@ -0,0 +1,74 @@
+use harpi::{
+    model::{AggregateFieldName, AggregateValue, Constant},
+    parse_file,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+service Library {
+  rpc CreateBook(CreateBookRequest) returns (Book) {
+    option (google.api.http) = {
+      // Comments are allowed between fields.
+      post: "/v1/{parent=shelves/*}"
+            "/books"
+      body: "book"
+      additional_bindings { get: "/v1/books/{id}" }
+    };
+  }
+}
+
+message Book {
+  string id = 1 [(my.rule) = { min: 1 nested < a: "x" > repeated: [1, -2.5, true, ENUM_VALUE] [ext.field]: {} }];
+}
+"#;
+
+#[test]
+fn aggregate_options() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let rpc = &proto.services()[0].rpcs()[0];
+    let Constant::Aggregate(http) = rpc.options()[0].value() else {
+        return Err("expected an aggregate".into());
+    };
+    assert!(matches!(
+        http.get("post"),
+        Some(AggregateValue::Constant(Constant::String(value))) if value == "/v1/{parent=shelves/*}/books"
+    ));
+    let Some(AggregateValue::Constant(Constant::Aggregate(binding))) =
+        http.get("additional_bindings")
+    else {
+        return Err("expected a nested message".into());
+    };
+    assert!(binding.get("get").is_some());
+
+    let harpi::model::Field::Normal(field) = &proto.messages()[0].fields()[0] else {
+        return Err("expected a normal field".into());
+    };
+    let Constant::Aggregate(rule) = field.options()[0].value() else {
+        return Err("expected an aggregate".into());
+    };
+    assert!(matches!(
+        rule.get("min"),
+        Some(AggregateValue::Constant(Constant::Int(1)))
+    ));
+    assert!(matches!(
+        rule.get("nested"),
+        Some(AggregateValue::Constant(Constant::Aggregate(_)))
+    ));
+    let Some(AggregateValue::List(values)) = rule.get("repeated") else {
+        return Err("expected a list".into());
+    };
+    assert!(matches!(
+        values.as_ref(),
+        [
+            AggregateValue::Constant(Constant::Int(1)),
+            AggregateValue::Constant(Constant::Float(-2.5)),
+            AggregateValue::Constant(Constant::Bool(true)),
+            AggregateValue::Constant(Constant::Ident(_)),
+        ]
+    ));
+    assert!(matches!(
+        rule.fields()[3].name(),
+        AggregateFieldName::Extension(name) if name.value() == "ext.field"
+    ));
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use harpi::{Error, parse_file};
+
+#[test]
+fn collect_proto() -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string("./proto/unittest_proto3.proto")?;
+    let proto = parse_file(data.as_str())?;
+    assert_eq!(proto.syntax().value(), "proto3");
+    assert_eq!(proto.package().value(), "proto3_unittest");
+    assert_eq!(proto.imports().len(), 1);
+    let messages = proto
+        .messages()
+        .iter()
+        .map(|message| message.name().value())
+        .collect::<Vec<_>>();
+    assert_eq!(messages.first(), Some(&"TestAllTypes"));
+    assert!(messages.contains(&"TestEmptyMessage"));
+    Ok(())
+}
+
+#[test]
+fn collect_duplicate_package() {
+    let data = "syntax = \"proto3\";\npackage a;\npackage b;\n";
+    assert!(matches!(
+        parse_file(data),
+        Err(Error::DuplicatePackage(first, second)) if first == "a" && second == "b"
+    ));
+}
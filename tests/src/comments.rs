@@ -0,0 +1,303 @@
+use harpi::{
+    model::{Comment, CommentKind, Documented, Field, NormalField, paragraphs},
+    parse_file,
+};
+
+const DATA: &str = r#"// Copyright header.
+
+syntax = "proto2";
+
+message Foo {  // Trailing for Foo.
+  optional int32 foo = 1;  // Comment attached to foo.
+  // Comment attached to bar.
+  optional int32 bar = 2;
+
+  optional string baz = 3;
+  // Comment attached to baz.
+  // Another line attached to baz.
+
+  // Comment attached to moo.
+  //
+  // Another line attached to moo.
+  optional double moo = 4;
+
+  // Detached comment for corge. This is not leading or trailing comments
+  // to moo or corge because there are blank lines separating it from
+  // both.
+
+  // Detached comment for corge paragraph 2.
+
+  optional string corge = 5;
+  /* Block comment attached
+   * to corge.  Leading asterisks
+   * will be removed. */
+  /* Block comment attached to
+   * grault. */
+  optional int32 grault = 6;
+
+  // ignored detached comments.
+}
+
+enum Sign {
+  ZERO = 0;
+  NEG = -1; // Intentionally negative.
+  /* inline */ POS = 1;
+}
+"#;
+
+fn values<'a>(comments: &'a [Comment<'a>]) -> Vec<&'a str> {
+    comments
+        .iter()
+        .map(|comment| comment.value().as_ref())
+        .collect()
+}
+
+#[test]
+fn attributes_comments() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let syntax = proto.syntax();
+    assert!(syntax.comments().is_empty());
+    assert_eq!(values(syntax.detached_comments()), [" Copyright header."]);
+
+    let message = &proto.messages()[0];
+    assert_eq!(values(message.trailing_comments()), [" Trailing for Foo."]);
+    let fields = message
+        .fields()
+        .iter()
+        .map(|field| match field {
+            Field::Normal(field) => field,
+            _ => panic!("expected normal fields"),
+        })
+        .collect::<Vec<&NormalField>>();
+
+    assert_eq!(
+        values(fields[0].trailing_comments()),
+        [" Comment attached to foo."]
+    );
+    assert_eq!(values(fields[1].comments()), [" Comment attached to bar."]);
+    assert!(fields[1].trailing_comments().is_empty());
+    assert_eq!(
+        values(fields[2].trailing_comments()),
+        [
+            " Comment attached to baz.",
+            " Another line attached to baz."
+        ]
+    );
+    assert_eq!(
+        values(fields[3].comments()),
+        [
+            " Comment attached to moo.",
+            "",
+            " Another line attached to moo."
+        ]
+    );
+    assert!(fields[3].comments()[0].span().start().line() < fields[3].span().start().line());
+
+    let corge = fields[4];
+    assert!(corge.comments().is_empty());
+    assert_eq!(corge.detached_comments().len(), 4);
+    assert_eq!(
+        corge.detached_comments()[3].value(),
+        " Detached comment for corge paragraph 2."
+    );
+    assert_eq!(
+        values(corge.trailing_comments()),
+        [" Block comment attached\n   * to corge.  Leading asterisks\n   * will be removed. "]
+    );
+    assert_eq!(
+        corge.trailing_comments()[0].span().as_str(DATA),
+        "/* Block comment attached\n   * to corge.  Leading asterisks\n   * will be removed. */"
+    );
+    let grault = fields[5];
+    assert_eq!(
+        values(grault.comments()),
+        [" Block comment attached to\n   * grault. "]
+    );
+    assert!(grault.trailing_comments().is_empty());
+
+    let items = proto.enums()[0].fields();
+    assert!(items[0].trailing_comments().is_empty());
+    assert_eq!(
+        values(items[1].trailing_comments()),
+        [" Intentionally negative."]
+    );
+    assert_eq!(values(items[2].comments()), [" inline "]);
+    Ok(())
+}
+
+/// Block comments come back as line comments, so only the text of each line is compared.
+#[test]
+fn prints_comments_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    let comments = |data: &str| -> Result<Vec<Vec<String>>, Box<dyn std::error::Error>> {
+        let proto = parse_file(data)?;
+        let mut comments = vec![values(proto.syntax().detached_comments())];
+        comments.push(values(proto.messages()[0].trailing_comments()));
+        for field in proto.messages()[0].fields().iter() {
+            if let Field::Normal(field) = field {
+                comments.push(values(field.detached_comments()));
+                comments.push(values(field.comments()));
+                comments.push(values(field.trailing_comments()));
+            }
+        }
+        for item in proto.enums()[0].fields().iter() {
+            comments.push(values(item.comments()));
+            comments.push(values(item.trailing_comments()));
+        }
+        Ok(comments
+            .into_iter()
+            .map(|values| {
+                values
+                    .into_iter()
+                    .flat_map(|value| value.split('\n').map(str::trim_end))
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .collect())
+    };
+    let printed = parse_file(DATA)?.to_string();
+    assert_eq!(comments(&printed)?, comments(DATA)?);
+    Ok(())
+}
+
+const DOCS: &str = r#"syntax = "proto3";
+
+/**
+ * A user account.
+ *
+ *   Indented example.
+ */
+message Account {
+  //  Unique id,
+  //  never reused.
+  string id = 1;
+  // Not documentation.
+  /// The display name.
+  string name = 2;
+  int32 age = 3;  // Age in years.
+  /* Plain block */
+  bool active = 4;
+}
+"#;
+
+#[test]
+fn normalizes_doc_comments() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DOCS)?;
+    let account = &proto.messages()[0];
+    let comment = &account.comments()[0];
+    assert_eq!(*comment.kind(), CommentKind::Block);
+    assert!(comment.is_doc());
+    assert_eq!(comment.text(), "A user account.\n\n  Indented example.");
+    assert_eq!(
+        account.doc().as_deref(),
+        Some("A user account.\n\n  Indented example.")
+    );
+
+    let fields = account.fields();
+    assert_eq!(fields[0].doc_comments().len(), 2);
+    assert_eq!(
+        fields[0].doc().as_deref(),
+        Some("Unique id,\nnever reused.")
+    );
+    assert_eq!(fields[1].doc().as_deref(), Some("The display name."));
+    assert_eq!(fields[2].doc().as_deref(), Some("Age in years."));
+    assert_eq!(fields[3].doc().as_deref(), Some("Plain block"));
+
+    let mut first = Comment::new(" First paragraph.");
+    first.set_kind(CommentKind::Line);
+    let mut second = Comment::new("\n   * Second\n   * paragraph.\n   ");
+    second.set_kind(CommentKind::Block);
+    assert_eq!(
+        paragraphs(&[first, second]),
+        ["First paragraph.", "Second\nparagraph."]
+    );
+    Ok(())
+}
+
+#[test]
+fn ignores_comment_markers_in_strings() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(
+        r#"syntax = "proto3";
+
+// Leading for url.
+option go_package = "example.com/a // not a comment";
+option java_package = "a /* neither */ b";  // Trailing for java_package.
+
+message Link {
+  string url = 1 [json_name = "//url"];  // Trailing for url.
+  // Leading for title.
+  string title = 2;
+}
+"#,
+    )?;
+    let options = proto.options();
+    assert_eq!(values(options[0].comments()), [" Leading for url."]);
+    assert!(options[0].trailing_comments().is_empty());
+    assert_eq!(
+        values(options[1].trailing_comments()),
+        [" Trailing for java_package."]
+    );
+
+    let fields = proto.messages()[0].fields();
+    let Field::Normal(url) = &fields[0] else {
+        return Err("expected a normal field".into());
+    };
+    assert_eq!(values(url.trailing_comments()), [" Trailing for url."]);
+    let Field::Normal(title) = &fields[1] else {
+        return Err("expected a normal field".into());
+    };
+    assert_eq!(values(title.comments()), [" Leading for title."]);
+    Ok(())
+}
+
+fn texts(comments: &[Comment<'_>]) -> Vec<String> {
+    comments.iter().map(Comment::text).collect()
+}
+
+#[test]
+fn attributes_comments_with_crlf() -> Result<(), Box<dyn std::error::Error>> {
+    let data = DATA.replace('\n', "\r\n");
+    let proto = parse_file(&data)?;
+    let expected = parse_file(DATA)?;
+    let message = &proto.messages()[0];
+    assert_eq!(
+        message.trailing_comments(),
+        expected.messages()[0].trailing_comments()
+    );
+    assert_eq!(values(message.trailing_comments()), [" Trailing for Foo."]);
+
+    let comments = message
+        .fields()
+        .iter()
+        .map(|field| {
+            let Field::Normal(field) = field else {
+                unreachable!();
+            };
+            (texts(field.comments()), texts(field.trailing_comments()))
+        })
+        .collect::<Vec<_>>();
+    let expected = expected.messages()[0]
+        .fields()
+        .iter()
+        .map(|field| {
+            let Field::Normal(field) = field else {
+                unreachable!();
+            };
+            (texts(field.comments()), texts(field.trailing_comments()))
+        })
+        .collect::<Vec<_>>();
+    // Block comments keep their carriage returns, which their text drops.
+    assert_eq!(comments.len(), 6);
+    assert_eq!(comments, expected);
+
+    let corge = &message.fields()[4];
+    let Field::Normal(corge) = corge else {
+        return Err("expected a normal field".into());
+    };
+    let span = corge.detached_comments()[0].span();
+    assert!(
+        span.as_str(&data)
+            .starts_with("// Detached comment for corge.")
+    );
+    assert!(!span.as_str(&data).ends_with('\r'));
+    Ok(())
+}
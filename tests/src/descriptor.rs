@@ -0,0 +1,325 @@
+use harpi::{
+    DiagnosticCode,
+    descriptor::{
+        Decode, DescriptorDecoder, DescriptorEncoder, DescriptorProto, EDITION_2023, Encode,
+        FieldDescriptorProto, FieldType, FileDescriptorProto, FileDescriptorSet, Label, WireValue,
+        decode_set, encode_file, json_name, map_entry_name,
+    },
+    model::{Constant, Field, Type},
+    parse_file,
+};
+
+#[test]
+fn encodes_wire_format() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file("syntax = \"proto3\";\nmessage A { int32 b = 1; }\n")?;
+    let (file, diagnostics) = encode_file("a.proto", &proto);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let mut expected = vec![0x0a, 0x07];
+    expected.extend(b"a.proto");
+    expected.extend([0x22, 0x11, 0x0a, 0x01, b'A', 0x12, 0x0c]);
+    expected.extend([
+        0x0a, 0x01, b'b', 0x18, 0x01, 0x20, 0x01, 0x28, 0x05, 0x52, 0x01, b'b',
+    ]);
+    expected.extend([0x62, 0x06]);
+    expected.extend(b"proto3");
+    assert_eq!(file.encode_to_vec(), expected);
+    Ok(())
+}
+
+const DATA: &str = r#"syntax = "proto3";
+
+package shop.v1;
+
+message Order {
+  optional string note = 1;
+  map<string, Item> items_by_sku = 2;
+  oneof payment {
+    string card = 3;
+    Voucher voucher = 4;
+  }
+  int64 created_at = 5 [json_name = "created"];
+  repeated Status history = 6 [packed = false];
+  reserved 10 to max;
+
+  message Item {}
+  message Voucher {}
+  enum Status {
+    option allow_alias = true;
+    STATUS_UNSPECIFIED = 0;
+    UNKNOWN = 0;
+  }
+}
+
+service Orders {
+  rpc Watch(stream Order) returns (stream Order.Item) { option deprecated = true; }
+}
+"#;
+
+#[test]
+fn synthesizes_descriptors() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let (file, diagnostics) = encode_file("shop.proto", &proto);
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    assert_eq!(file.package.as_deref(), Some("shop.v1"));
+
+    let order = &file.message_type[0];
+    let names = order
+        .oneof_decl
+        .iter()
+        .map(|oneof| oneof.name.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["payment", "_note"]);
+
+    let note = &order.field[0];
+    assert_eq!(note.proto3_optional, Some(true));
+    assert_eq!(note.oneof_index, Some(1));
+
+    let items = &order.field[1];
+    assert_eq!(items.label, Some(Label::Repeated));
+    assert_eq!(items.r#type, Some(FieldType::Message));
+    assert_eq!(
+        items.type_name.as_deref(),
+        Some(".shop.v1.Order.ItemsBySkuEntry")
+    );
+    assert_eq!(items.json_name.as_deref(), Some("itemsBySku"));
+    let entry = &order.nested_type[0];
+    assert_eq!(entry.name.as_deref(), Some("ItemsBySkuEntry"));
+    assert_eq!(
+        entry.field[1].type_name.as_deref(),
+        Some(".shop.v1.Order.Item")
+    );
+    let map_entry = entry.options.as_ref().unwrap().get(7).next();
+    assert_eq!(map_entry, Some(&WireValue::Varint(1)));
+
+    let voucher = &order.field[3];
+    assert_eq!(voucher.oneof_index, Some(0));
+    assert_eq!(voucher.r#type, Some(FieldType::Message));
+    assert_eq!(order.field[4].json_name.as_deref(), Some("created"));
+    assert_eq!(order.field[4].options, None);
+    assert_eq!(order.field[5].r#type, Some(FieldType::Enum));
+    let packed = order.field[5].options.as_ref().unwrap().get(2).next();
+    assert_eq!(packed, Some(&WireValue::Varint(0)));
+    assert_eq!(order.reserved_range[0].start, Some(10));
+    assert_eq!(order.reserved_range[0].end, Some(536870912));
+
+    let method = &file.service[0].method[0];
+    assert_eq!(method.input_type.as_deref(), Some(".shop.v1.Order"));
+    assert_eq!(method.output_type.as_deref(), Some(".shop.v1.Order.Item"));
+    assert_eq!(method.client_streaming, Some(true));
+    assert!(method.options.is_some());
+    Ok(())
+}
+
+const OPTIONS: &str = r#"syntax = "proto2";
+
+package google.protobuf;
+
+message FieldOptions {
+  extensions 1000 to max;
+}
+"#;
+
+const CUSTOM: &str = r#"syntax = "proto3";
+
+package acme;
+
+import "google/protobuf/descriptor.proto";
+
+enum Level {
+  LEVEL_UNSPECIFIED = 0;
+  HIGH = 1;
+}
+
+extend google.protobuf.FieldOptions {
+  Level level = 50001;
+  Rule rule = 50002;
+}
+
+message Alert {
+  option unknown = true;
+  string text = 1 [(level) = HIGH, (rule).max = 3];
+}
+
+message Rule {
+  int32 max = 1;
+}
+"#;
+
+#[test]
+fn resolves_custom_options() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_file(OPTIONS)?;
+    let custom = parse_file(CUSTOM)?;
+    let (set, diagnostics) = DescriptorEncoder::new()
+        .with_file("google/protobuf/descriptor.proto", &options)
+        .with_file("acme.proto", &custom)
+        .encode();
+
+    let codes = diagnostics
+        .iter()
+        .map(|diagnostic| *diagnostic.code())
+        .collect::<Vec<_>>();
+    assert_eq!(codes, [DiagnosticCode::UnknownOption]);
+
+    let file = &set.file[1];
+    assert_eq!(file.dependency, ["google/protobuf/descriptor.proto"]);
+    assert_eq!(
+        file.extension[0].extendee.as_deref(),
+        Some(".google.protobuf.FieldOptions")
+    );
+    let text = &file.message_type[0].field[0];
+    let level = text.options.as_ref().unwrap().get(50001).next();
+    assert_eq!(level, Some(&WireValue::Varint(1)));
+    let rule = text.options.as_ref().unwrap().get(50002).next();
+    assert_eq!(rule, Some(&WireValue::Bytes(vec![0x08, 3])));
+    Ok(())
+}
+
+const OVERFLOW: &str = r#"syntax = "proto2";
+
+package acme;
+
+import "google/protobuf/descriptor.proto";
+
+extend google.protobuf.FieldOptions {
+  optional int32 limit = 50001;
+}
+
+message Quota {
+  optional string name = 5000000000 [(limit) = 3000000000];
+}
+"#;
+
+#[test]
+fn reports_values_out_of_range() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_file(OPTIONS)?;
+    let overflow = parse_file(OVERFLOW)?;
+    let (set, diagnostics) = DescriptorEncoder::new()
+        .with_file("google/protobuf/descriptor.proto", &options)
+        .with_file("acme.proto", &overflow)
+        .encode();
+
+    let codes = diagnostics
+        .iter()
+        .map(|diagnostic| *diagnostic.code())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            DiagnosticCode::FieldNumberOutOfRange,
+            DiagnosticCode::InvalidOptionValue
+        ]
+    );
+    let name = &set.file[1].message_type[0].field[0];
+    assert_eq!(name.number, None);
+    assert!(name.options.is_none());
+    Ok(())
+}
+
+#[test]
+fn derives_names() {
+    assert_eq!(json_name("foo_bar_baz"), "fooBarBaz");
+    assert_eq!(json_name("_private"), "Private");
+    assert_eq!(map_entry_name("items_by_sku"), "ItemsBySkuEntry");
+}
+
+#[test]
+fn decodes_synthetic_forms() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let (file, _) = encode_file("shop.proto", &proto);
+    let bytes = FileDescriptorSet { file: vec![file] }.encode_to_vec();
+    let (files, diagnostics) = decode_set(&bytes)?;
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let (name, decoded) = &files[0];
+    assert_eq!(name, "shop.proto");
+    assert_eq!(decoded.syntax().value(), "proto3");
+    let order = &decoded.messages()[0];
+    let Field::Normal(note) = &order.fields()[0] else {
+        panic!("expected `note` to be a normal field");
+    };
+    assert!(*note.optional());
+    let Field::Map(items) = &order.fields()[1] else {
+        panic!("expected `items_by_sku` to be a map");
+    };
+    assert!(matches!(items.value_ty(), Type::Reference(name) if name == ".shop.v1.Order.Item"));
+    let Field::OneOf(payment) = &order.fields()[2] else {
+        panic!("expected `payment` to be a oneof");
+    };
+    assert_eq!(payment.fields().len(), 2);
+    let names = order
+        .messages()
+        .iter()
+        .map(|message| message.name().value())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["Item", "Voucher"]);
+    Ok(())
+}
+
+#[test]
+fn decodes_editions_groups_as_delimited_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let group = DescriptorProto {
+        name: Some("Data".to_string()),
+        ..Default::default()
+    };
+    let message = DescriptorProto {
+        name: Some("Outer".to_string()),
+        field: vec![FieldDescriptorProto {
+            name: Some("data".to_string()),
+            number: Some(1),
+            label: Some(Label::Optional),
+            r#type: Some(FieldType::Group),
+            type_name: Some(".pkg.Outer.Data".to_string()),
+            ..Default::default()
+        }],
+        nested_type: vec![group],
+        ..Default::default()
+    };
+    let set = FileDescriptorSet {
+        file: vec![FileDescriptorProto {
+            name: Some("groups.proto".to_string()),
+            package: Some("pkg".to_string()),
+            message_type: vec![message],
+            syntax: Some("editions".to_string()),
+            edition: Some(EDITION_2023),
+            ..Default::default()
+        }],
+    };
+    let (files, diagnostics) = DescriptorDecoder::new(&set).decode()?;
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+
+    let outer = &files[0].1.messages()[0];
+    let Field::Normal(data) = &outer.fields()[0] else {
+        panic!("expected `data` to be a normal field");
+    };
+    assert!(matches!(data.ty(), Type::Reference(name) if name == ".pkg.Outer.Data"));
+    let encoding = &data.options()[0];
+    assert_eq!(encoding.name().to_string(), "features.message_encoding");
+    assert!(matches!(encoding.value(), Constant::Ident(value) if value.value() == "DELIMITED"));
+    assert_eq!(outer.messages()[0].name().value(), "Data");
+    Ok(())
+}
+
+#[test]
+fn round_trips_fixtures() -> Result<(), Box<dyn std::error::Error>> {
+    for data in [
+        include_str!("../proto/unittest_proto3.proto"),
+        include_str!("../proto/unittest_proto2.proto"),
+        include_str!("../proto/unittest_editions.proto"),
+    ] {
+        let proto = parse_file(data)?;
+        let (file, _) = encode_file("fixture.proto", &proto);
+        let set = FileDescriptorSet::decode(
+            &FileDescriptorSet {
+                file: vec![file.clone()],
+            }
+            .encode_to_vec(),
+        )?;
+        assert_eq!(set.file[0], file);
+
+        let (files, _) = DescriptorDecoder::new(&set).decode()?;
+        let (reencoded, _) = encode_file("fixture.proto", &files[0].1);
+        assert_eq!(reencoded, file);
+    }
+    Ok(())
+}
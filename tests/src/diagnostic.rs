@@ -0,0 +1,42 @@
+use harpi::{DiagnosticCode, Severity, parse_file};
+
+const DATA: &str = r#"syntax = "proto3";
+
+message Person {
+  string name = ;
+}
+"#;
+
+#[test]
+fn syntax_error_diagnostic() {
+    let error = parse_file(DATA).expect_err("the field number is missing");
+    let diagnostic = error.to_diagnostic().with_file("person.proto");
+    assert_eq!(*diagnostic.severity(), Severity::Error);
+    assert_eq!(*diagnostic.code(), DiagnosticCode::Syntax);
+    assert_eq!(diagnostic.code().as_str(), "E0001");
+    assert!(!diagnostic.message().contains("LIT"));
+
+    let start = diagnostic.span().start();
+    assert_eq!(start.line(), 4);
+    assert!(diagnostic.to_string().starts_with("person.proto:4:"));
+
+    let rendered = diagnostic.render(DATA);
+    assert!(rendered.starts_with("error[E0001]: "));
+    assert!(rendered.contains(" --> person.proto:4:"));
+    assert!(rendered.contains("4 |   string name = ;"));
+    assert!(rendered.contains('^'));
+    assert!(!rendered.contains('\x1b'));
+    assert!(
+        diagnostic
+            .render_colored(DATA)
+            .contains("\x1b[1;31merror[E0001]")
+    );
+}
+
+#[test]
+fn unsupported_syntax_diagnostic() {
+    let error = parse_file("syntax = \"proto4\";\n").expect_err("proto4 is not supported");
+    let diagnostic = error.to_diagnostic();
+    assert_eq!(*diagnostic.code(), DiagnosticCode::UnsupportedSyntax);
+    assert!(diagnostic.message().contains("proto4"));
+}
@@ -0,0 +1,89 @@
+use harpi::{
+    DiagnosticCode,
+    model::{Field, ReservedData, Visibility},
+    parse_file, parse_file_recovering,
+};
+
+#[test]
+fn edition_2023() -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string("./proto/unittest_editions.proto")?;
+    let proto = parse_file(data.as_str())?;
+    assert!(*proto.syntax().edition());
+    assert_eq!(proto.syntax().value(), "2023");
+    assert_eq!(proto.options()[0].feature(), Some("field_presence"));
+    assert_eq!(proto.options()[1].feature(), None);
+
+    let message = &proto.messages()[0];
+    let Field::Normal(field) = &message.fields()[1] else {
+        return Err("expected a normal field".into());
+    };
+    assert_eq!(field.options()[0].feature(), Some("field_presence"));
+    assert!(
+        message.reserved()[1]
+            .items()
+            .iter()
+            .any(|item| matches!(item, ReservedData::Field(name) if name.value() == "other_field"))
+    );
+    assert_eq!(proto.extends().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn edition_2024() -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string("./proto/unittest_edition_2024.proto")?;
+    let proto = parse_file(data.as_str())?;
+    assert_eq!(proto.syntax().value(), "2024");
+    assert!(*proto.imports()[0].option());
+    assert_eq!(*proto.messages()[0].visibility(), Visibility::Export);
+    assert_eq!(
+        *proto.messages()[0].messages()[0].visibility(),
+        Visibility::Local
+    );
+    assert_eq!(*proto.enums()[0].visibility(), Visibility::Local);
+    Ok(())
+}
+
+#[test]
+fn edition_2023_rejects_2024_keywords() {
+    let cases = [
+        ("import option \"a.proto\";", "import option", (2, 8)),
+        ("export message A {}", "export", (2, 1)),
+        (
+            "message A { local enum B { B_UNSPECIFIED = 0; } }",
+            "local",
+            (2, 13),
+        ),
+    ];
+    for (declaration, keyword, position) in cases {
+        let data = format!("edition = \"2023\";\n{declaration}\n");
+        let error = parse_file(&data).expect_err(declaration).to_diagnostic();
+        assert_eq!(*error.code(), DiagnosticCode::Syntax);
+        assert_eq!(
+            *error.message(),
+            format!("`{keyword}` is not available in edition 2023")
+        );
+        let start = error.span().start();
+        assert_eq!((start.line(), start.column()), position);
+
+        let (proto, diagnostics) = parse_file_recovering(&data);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span(), error.span());
+        assert!(proto.imports().is_empty() && proto.messages().is_empty());
+
+        let data = data.replace("2023", "2024");
+        assert!(parse_file(&data).is_ok());
+    }
+}
+
+#[test]
+fn edition_unsupported() {
+    assert!(parse_file("edition = \"2099\";\n").is_err());
+    assert!(parse_file("edition = \"2023\";\nmessage A { optional int32 a = 1; }\n").is_err());
+}
+
+#[test]
+fn trailing_comment() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file("edition = \"2023\";\nmessage A {} // trailing\n")?;
+    assert_eq!(proto.messages()[0].name().value(), "A");
+    Ok(())
+}
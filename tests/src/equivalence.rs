@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+use harpi::{
+    equivalent,
+    model::{Ident, MessageReference, Type},
+    parse_file,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+package shop;
+
+// An order.
+message Order {
+  option deprecated = true;
+  option (shop.version) = 2;
+  Item item = 1 [json_name = "Item", deprecated = true];
+  message Item {}
+}
+"#;
+
+const REFORMATTED: &str = r#"syntax = "proto3";
+package shop;
+message Order {
+    option (shop.version) = 2;
+    option deprecated = true;
+
+    .shop.Order.Item item = 1 [deprecated = true, json_name = "Item"];  // The item.
+    message Item {
+    }
+}
+"#;
+
+#[test]
+fn compares_structurally() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(parse_file(DATA)?, parse_file(DATA)?);
+    assert_ne!(parse_file(DATA)?, parse_file(REFORMATTED)?);
+
+    let idents = HashSet::from([Ident::new(false, "Order"), Ident::new(false, "Order")]);
+    assert_eq!(idents.len(), 1);
+    let types = HashSet::from([Type::Int32, Type::Reference("Order".into()), Type::Int32]);
+    assert_eq!(types.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn ignores_spans_of_names_and_comments() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let reformatted = parse_file(REFORMATTED)?;
+    let (order, other) = (&proto.messages()[0], &reformatted.messages()[0]);
+    assert_ne!(order.name().span(), other.name().span());
+    assert_eq!(order.name(), other.name());
+    let names = HashSet::from([order.name(), other.name(), order.messages()[0].name()]);
+    assert_eq!(names.len(), 2);
+
+    assert_ne!(
+        order.options()[0].name().span(),
+        other.options()[1].name().span()
+    );
+    assert_eq!(order.options()[0].name(), other.options()[1].name());
+
+    let data = DATA.replace("// An order.", "\n\n// An order.");
+    let moved = parse_file(&data)?;
+    let comment = &moved.messages()[0].comments()[0];
+    assert_ne!(comment.span(), order.comments()[0].span());
+    assert_eq!(comment, &order.comments()[0]);
+
+    let mut reference = MessageReference::new("shop.Order");
+    reference.set_span(order.name().span());
+    assert_eq!(reference, MessageReference::new("shop.Order"));
+    let references = HashSet::from([reference, MessageReference::new("shop.Order")]);
+    assert_eq!(references.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn compares_semantically() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    assert!(equivalent(&proto, &parse_file(REFORMATTED)?));
+
+    let renamed = REFORMATTED.replace(".shop.Order.Item item", "Order item");
+    assert!(!equivalent(&proto, &parse_file(&renamed)?));
+    let changed = DATA.replace("(shop.version) = 2", "(shop.version) = 3");
+    assert!(!equivalent(&proto, &parse_file(&changed)?));
+
+    let tags = |first: &str, second: &str| {
+        DATA.replace(
+            "option deprecated = true;",
+            &format!("option (shop.tag) = \"{first}\";\n  option (shop.tag) = \"{second}\";"),
+        )
+    };
+    let (repeated, swapped) = (tags("a", "b"), tags("b", "a"));
+    assert!(equivalent(&parse_file(&repeated)?, &parse_file(&repeated)?));
+    assert!(!equivalent(&parse_file(&repeated)?, &parse_file(&swapped)?));
+    Ok(())
+}
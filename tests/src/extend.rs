@@ -0,0 +1,81 @@
+use harpi::{
+    Node, ProtoParser, ProtoVisitor,
+    model::{Extend, Field, Type},
+    proto3::Proto3,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+package my.options;
+
+import "google/protobuf/descriptor.proto";
+
+// Custom field options.
+extend google.protobuf.FieldOptions {
+  optional string validation_rule = 50001;
+  repeated Rule rules = 50002;
+}
+
+message Rule {
+  string expression = 1;
+
+  extend .google.protobuf.MessageOptions {
+    Rule message_rule = 50003;
+  }
+}
+
+extend Rule {
+  int32 not_an_option = 100;
+}
+"#;
+
+#[derive(Default)]
+struct ExtendVisitor<'a> {
+    extends: Vec<Extend<'a>>,
+}
+
+impl<'a> ProtoVisitor<'a> for ExtendVisitor<'a> {
+    fn on(&mut self, node: Node<'a>) {
+        if let Node::Extend(extend) = node {
+            self.extends.push(extend);
+        }
+    }
+}
+
+#[test]
+fn proto3_extend() -> Result<(), Box<dyn std::error::Error>> {
+    let mut visitor = ExtendVisitor::default();
+    Proto3::parse(DATA, &mut visitor)?;
+    assert_eq!(visitor.extends.len(), 2);
+
+    let options = &visitor.extends[0];
+    assert!(options.is_option_declaration());
+    assert_eq!(options.extendee().value(), "google.protobuf.FieldOptions");
+    let fields = options
+        .fields()
+        .iter()
+        .filter_map(|field| match field {
+            Field::Normal(field) => Some((field.name().value(), *field.number(), field.ty())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        fields.as_slice(),
+        [
+            ("validation_rule", 50001, Type::String),
+            ("rules", 50002, Type::Reference(name)),
+        ] if name == "Rule"
+    ));
+    assert_eq!(options.comments().len(), 1);
+    assert!(!visitor.extends[1].is_option_declaration());
+    Ok(())
+}
+
+#[test]
+fn proto3_nested_extend() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = harpi::parse_file(DATA)?;
+    let message = &proto.messages()[0];
+    assert_eq!(message.extends().len(), 1);
+    assert!(message.extends()[0].is_option_declaration());
+    Ok(())
+}
@@ -1,2 +1,50 @@
 #[cfg(test)]
+mod aggregate;
+#[cfg(test)]
+mod collect;
+#[cfg(test)]
+mod comments;
+#[cfg(test)]
+mod descriptor;
+#[cfg(test)]
+mod diagnostic;
+#[cfg(test)]
+mod editions;
+#[cfg(test)]
+mod equivalence;
+#[cfg(test)]
+mod extend;
+#[cfg(test)]
+mod linker;
+#[cfg(test)]
+mod literals;
+#[cfg(test)]
+mod loader;
+#[cfg(test)]
+mod option_name;
+#[cfg(test)]
+mod options;
+#[cfg(test)]
+mod owned;
+#[cfg(test)]
+mod pool;
+#[cfg(test)]
+mod printer;
+#[cfg(test)]
+mod proto2;
+#[cfg(test)]
+mod recovery;
+#[cfg(test)]
+mod scope;
+#[cfg(test)]
+mod serialize;
+#[cfg(test)]
 mod simple;
+#[cfg(test)]
+mod span;
+#[cfg(test)]
+mod try_visitor;
+#[cfg(test)]
+mod validate;
+#[cfg(test)]
+mod visit;
@@ -0,0 +1,237 @@
+use harpi::{
+    DiagnosticCode, Linker, Resolution, SymbolKind,
+    descriptor::{Encode, FileDescriptorSet, decode_set, encode_file},
+    link,
+    model::{Field, Span},
+    parse_file,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+package shop.v1;
+
+message Order {
+  message Item {
+    string sku = 1;
+  }
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+  }
+  repeated Item items = 1;
+  Status status = 2;
+  .shop.v1.Customer customer = 3;
+  v1.Customer buyer = 4;
+  map<string, Order.Item> by_sku = 5;
+  Missing missing = 6;
+}
+
+message Customer {
+  Order.Item favorite = 1;
+}
+
+service Orders {
+  rpc Get(Customer) returns (Order.Status);
+}
+"#;
+
+#[test]
+fn resolves_scoped_names() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let linked = link(&proto);
+
+    let order = &proto.messages()[0];
+    let targets = order
+        .fields()
+        .iter()
+        .map(|field| {
+            let span = match field {
+                Field::Normal(field) => *field.ty_span(),
+                Field::Map(field) => *field.value_ty_span(),
+                _ => unreachable!(),
+            };
+            linked
+                .target("", span)
+                .map(|symbol| (symbol.full_name().as_str(), *symbol.kind()))
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        targets,
+        [
+            Some(("shop.v1.Order.Item", SymbolKind::Message)),
+            Some(("shop.v1.Order.Status", SymbolKind::Enum)),
+            Some(("shop.v1.Customer", SymbolKind::Message)),
+            Some(("shop.v1.Customer", SymbolKind::Message)),
+            Some(("shop.v1.Order.Item", SymbolKind::Message)),
+            None,
+        ]
+    );
+
+    let codes = linked
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| (*diagnostic.code(), diagnostic.span().start().line()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            (DiagnosticCode::UnresolvedReference, 17),
+            (DiagnosticCode::InvalidReference, 25),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reports_shadowed_and_duplicate_names() -> Result<(), Box<dyn std::error::Error>> {
+    let first = parse_file(
+        r#"syntax = "proto3";
+package a;
+message Outer {
+  message b {}
+  b.Target shadowed = 1;
+}
+"#,
+    )?;
+    let second = parse_file(
+        r#"syntax = "proto3";
+package a.b;
+message Target {}
+message Outer {}
+"#,
+    )?;
+    let linked = Linker::new()
+        .with_file("first.proto", &first)
+        .with_file("second.proto", &second)
+        .link();
+    let symbols = linked.symbols();
+    assert!(matches!(
+        symbols.resolve("a.Outer", "b.Target"),
+        Resolution::Ambiguous(_)
+    ));
+    assert!(matches!(
+        symbols.resolve("a", ".a.b.Target"),
+        Resolution::Resolved(symbol) if *symbol.kind() == SymbolKind::Message
+    ));
+    assert_eq!(linked.diagnostics().len(), 1);
+    let diagnostic = &linked.diagnostics()[0];
+    assert_eq!(*diagnostic.code(), DiagnosticCode::AmbiguousReference);
+    assert_eq!(diagnostic.file().as_deref(), Some("first.proto"));
+    assert!(diagnostic.notes()[0].contains(".a.b.Target"));
+    Ok(())
+}
+
+#[test]
+fn respects_imports() -> Result<(), Box<dyn std::error::Error>> {
+    let base = parse_file("syntax = \"proto3\";\nmessage Base {}\n")?;
+    let user = parse_file("syntax = \"proto3\";\nmessage User { Base base = 1; }\n")?;
+    let linked = Linker::new()
+        .with_file("base.proto", &base)
+        .with_file("user.proto", &user)
+        .with_imports("user.proto", Vec::new())
+        .link();
+    assert_eq!(linked.diagnostics().len(), 1);
+    assert_eq!(
+        *linked.diagnostics()[0].code(),
+        DiagnosticCode::UnresolvedReference
+    );
+    Ok(())
+}
+
+#[test]
+fn links_enum_values() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_file(
+        r#"syntax = "proto2";
+package opts;
+import "google/protobuf/descriptor.proto";
+enum Level {
+  LEVEL_LOW = 0;
+  LEVEL_HIGH = 1;
+}
+extend google.protobuf.MessageOptions {
+  optional Level level = 50000;
+}
+"#,
+    )?;
+    let shop = parse_file(
+        r#"syntax = "proto2";
+package shop;
+import "opts.proto";
+message Order {
+  option (opts.level) = LEVEL_HIGH;
+  enum Status {
+    STATUS_UNKNOWN = 0;
+    STATUS_PAID = 1;
+  }
+  optional Status status = 1 [default = STATUS_PAID];
+  optional Status missing = 2 [default = LEVEL_LOW];
+}
+"#,
+    )?;
+    let linked = Linker::new()
+        .with_file("opts.proto", &options)
+        .with_file("shop.proto", &shop)
+        .link();
+    let symbols = linked.symbols();
+    assert_eq!(
+        symbols.get("shop.Order.STATUS_PAID")[0].kind(),
+        &SymbolKind::EnumValue
+    );
+    assert!(symbols.get("shop.Order.Status.STATUS_PAID").is_empty());
+    assert!(
+        symbols
+            .enum_value("shop.Order.Status", "STATUS_PAID")
+            .is_some()
+    );
+    assert!(symbols.enum_value("opts.Level", "STATUS_PAID").is_none());
+    assert!(matches!(
+        symbols.resolve("shop.Order", "STATUS_PAID"),
+        Resolution::Unresolved
+    ));
+
+    let order = &shop.messages()[0];
+    let level = linked
+        .target("shop.proto", *order.options()[0].value_span())
+        .ok_or("level")?;
+    assert_eq!(level.full_name(), "opts.LEVEL_HIGH");
+    let Field::Normal(status) = &order.fields()[0] else {
+        return Err("expected a normal field".into());
+    };
+    let paid = linked
+        .target("shop.proto", *status.options()[0].value_span())
+        .ok_or("paid")?;
+    assert_eq!(paid.full_name(), "shop.Order.STATUS_PAID");
+
+    // The extendee is unresolved too, since descriptor.proto is not linked.
+    let diagnostics = linked
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| diagnostic.file().as_deref() == Some("shop.proto"))
+        .collect::<Vec<_>>();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(*diagnostics[0].code(), DiagnosticCode::UnresolvedReference);
+    assert_eq!(diagnostics[0].span().start().line(), 11);
+    assert_eq!(
+        diagnostics[0].message(),
+        "`LEVEL_LOW` is not a value of enum `.shop.Order.Status`"
+    );
+    Ok(())
+}
+
+#[test]
+fn finds_references_by_unique_span() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let linked = link(&proto);
+    for reference in linked.references() {
+        let found = linked.reference("", *reference.span()).ok_or("reference")?;
+        assert_eq!(found.name(), reference.name());
+    }
+
+    // Files decoded from descriptors have no spans, so their references all share one.
+    let (file, _) = encode_file("shop.proto", &proto);
+    let bytes = FileDescriptorSet { file: vec![file] }.encode_to_vec();
+    let (files, _) = decode_set(&bytes)?;
+    let linked = link(&files[0].1);
+    assert!(linked.references().len() > 1);
+    assert!(linked.reference("", Span::default()).is_none());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use harpi::{
+    model::{Constant, Field},
+    parse_file,
+};
+
+/// The characters before the first escape are sliced from the source, so characters of several
+/// bytes must advance the slice by their full length.
+#[test]
+fn slices_multi_byte_strings() -> Result<(), Box<dyn std::error::Error>> {
+    for header in [
+        "syntax = \"proto3\";",
+        "syntax = \"proto2\";",
+        "edition = \"2023\";",
+    ] {
+        let data = format!("{header}\n\noption java_package = \"héllo wörld ✓\\t!\";\n");
+        let proto = parse_file(&data)?;
+        let Constant::String(value) = proto.options()[0].value() else {
+            panic!("expected a string");
+        };
+        assert_eq!(value, "héllo wörld ✓\t!");
+    }
+    Ok(())
+}
+
+#[test]
+fn parses_option_lists_with_or_without_spaces() -> Result<(), Box<dyn std::error::Error>> {
+    let cases = [
+        ("syntax = \"proto3\";", "string"),
+        ("syntax = \"proto2\";", "optional string"),
+        ("edition = \"2023\";", "string"),
+    ];
+    for (header, label) in cases {
+        let data = format!(
+            r#"{header}
+
+message Order {{
+  {label} id = 1 [deprecated = true, json_name = "ID"];
+  {label} name = 2 [deprecated = true,json_name = "NAME"];
+}}
+
+enum Status {{
+  STATUS_UNKNOWN = 0 [deprecated = true, debug_redact = true];
+  STATUS_PAID = 1 [deprecated = true,debug_redact = true];
+}}
+"#
+        );
+        let proto = parse_file(&data)?;
+        for field in proto.messages()[0].fields().iter() {
+            let Field::Normal(field) = field else {
+                panic!("expected a normal field");
+            };
+            assert_eq!(
+                field.options().len(),
+                2,
+                "{header} {}",
+                field.name().value()
+            );
+        }
+        for item in proto.enums()[0].fields().iter() {
+            assert_eq!(item.options().len(), 2, "{header} {}", item.name().value());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use harpi::{DiagnosticCode, Error, IncludePaths, Loader, MemorySource};
+
+fn sources() -> MemorySource {
+    MemorySource::new()
+        .with_file(
+            "app/main.proto",
+            r#"syntax = "proto3";
+import "app/api.proto";
+import "common/base.proto";
+message Main { Api api = 1; }
+"#,
+        )
+        .with_file(
+            "app/api.proto",
+            r#"syntax = "proto3";
+import public "common/types.proto";
+message Api {}
+"#,
+        )
+        .with_file(
+            "common/types.proto",
+            r#"syntax = "proto3";
+import public "common/base.proto";
+message Type {}
+"#,
+        )
+        .with_file(
+            "common/base.proto",
+            "syntax = \"proto3\";\nmessage Base {}\n",
+        )
+}
+
+#[test]
+fn loads_transitive_imports_once() -> Result<(), Box<dyn std::error::Error>> {
+    let set = Loader::new(sources()).load("app/main.proto")?;
+    let paths = set
+        .files()
+        .iter()
+        .map(|file| file.path().as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        [
+            "common/base.proto",
+            "common/types.proto",
+            "app/api.proto",
+            "app/main.proto"
+        ]
+    );
+    assert_eq!(
+        set.visible_imports("app/main.proto"),
+        ["app/api.proto", "common/types.proto", "common/base.proto"]
+    );
+    assert_eq!(set.visible_imports("common/base.proto"), Vec::<&str>::new());
+
+    let (path, main) = set.protos().last().ok_or("no files")?;
+    assert_eq!(path, "app/main.proto");
+    assert_eq!(main.messages()[0].name().value(), "Main");
+    let base = set.get("common/base.proto").ok_or("no base")?;
+    assert_eq!(base.proto().messages()[0].name().value(), "Base");
+    assert!(set.get("missing.proto").is_none());
+    Ok(())
+}
+
+#[test]
+fn reports_missing_import_chain() {
+    let sources = sources().with_file(
+        "common/base.proto",
+        "syntax = \"proto3\";\nimport \"missing.proto\";\n",
+    );
+    let error = Loader::new(&sources)
+        .load("app/main.proto")
+        .expect_err("missing.proto does not exist");
+    let Error::MissingImport(chain) = &error else {
+        panic!("unexpected error {error}");
+    };
+    assert_eq!(
+        chain,
+        &[
+            "app/main.proto",
+            "app/api.proto",
+            "common/types.proto",
+            "common/base.proto",
+            "missing.proto"
+        ]
+    );
+    assert_eq!(*error.to_diagnostic().code(), DiagnosticCode::MissingImport);
+}
+
+#[test]
+fn reports_import_cycle() {
+    let sources = MemorySource::from_iter([
+        ("a.proto", "syntax = \"proto3\";\nimport \"b.proto\";\n"),
+        ("b.proto", "syntax = \"proto3\";\nimport \"a.proto\";\n"),
+    ]);
+    let error = Loader::new(sources)
+        .load("a.proto")
+        .expect_err("a.proto imports itself through b.proto");
+    assert!(
+        matches!(&error, Error::ImportCycle(chain) if chain == &["a.proto", "b.proto", "a.proto"])
+    );
+    assert_eq!(
+        error.to_string(),
+        "import cycle a.proto -> b.proto -> a.proto"
+    );
+}
+
+#[test]
+fn loads_from_include_paths() {
+    let root = concat!(env!("CARGO_MANIFEST_DIR"), "/proto");
+    let loader = Loader::new(
+        IncludePaths::new()
+            .with_root("/nonexistent")
+            .with_root(root),
+    );
+    let error = loader
+        .load("unittest_proto3.proto")
+        .expect_err("the imports of unittest_proto3.proto are not vendored");
+    assert!(matches!(
+        &error,
+        Error::MissingImport(chain)
+            if chain == &["unittest_proto3.proto", "google/protobuf/unittest_import.proto"]
+    ));
+
+    let set = loader
+        .load("unittest_editions.proto")
+        .expect("unittest_editions.proto has no imports");
+    assert_eq!(set.files().len(), 1);
+}
@@ -0,0 +1,73 @@
+use harpi::{
+    model::{Field, OptionName, OptionNamePart},
+    parse_file,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+option java_package = "shop.v1";
+option (google.api.http).post = "/v1/orders";
+
+message Order {
+  string id = 1 [(.my.ext).a.(b.c) = 1, deprecated = true];
+}
+"#;
+
+/// The parts of `name`, with extensions marked by whether they start with a dot.
+fn parts<'a>(name: &'a OptionName<'a>) -> Vec<(&'a str, std::option::Option<bool>)> {
+    name.parts()
+        .iter()
+        .map(|part| match part {
+            OptionNamePart::Ident(ident) => (ident.value(), None),
+            OptionNamePart::Extension(ident) => (ident.value(), Some(ident.relative())),
+        })
+        .collect()
+}
+
+#[test]
+fn parses_parts() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let options = proto.options();
+    assert_eq!(parts(options[0].name()), [("java_package", None)]);
+    assert!(options[0].name().is("java_package"));
+    assert_eq!(
+        parts(options[1].name()),
+        [("google.api.http", Some(false)), ("post", None)]
+    );
+    assert!(!options[1].name().is("google.api.http.post"));
+    assert_eq!(options[1].name().to_string(), "(google.api.http).post");
+    assert_eq!(
+        options[1].name().span().as_str(DATA),
+        "(google.api.http).post"
+    );
+
+    let Field::Normal(id) = &proto.messages()[0].fields()[0] else {
+        panic!("expected a normal field");
+    };
+    let name = id.options()[0].name();
+    assert_eq!(
+        parts(name),
+        [("my.ext", Some(true)), ("a", None), ("b.c", Some(false))]
+    );
+    assert_eq!(name.to_string(), "(.my.ext).a.(b.c)");
+    assert!(id.options()[1].name().is("deprecated"));
+    Ok(())
+}
+
+#[test]
+fn prints_parts() -> Result<(), Box<dyn std::error::Error>> {
+    let printed = parse_file(DATA)?.to_string();
+    assert!(printed.contains("option (google.api.http).post = \"/v1/orders\";"));
+    assert!(printed.contains("[(.my.ext).a.(b.c) = 1, deprecated = true]"));
+    assert_eq!(parse_file(&printed)?.to_string(), printed);
+
+    assert_eq!(
+        OptionName::plain("features.field_presence").parts().len(),
+        2
+    );
+    assert_eq!(
+        OptionName::extension(true, "my.ext").to_string(),
+        "(.my.ext)"
+    );
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use harpi::{
+    DiagnosticCode,
+    model::{Field, IdempotencyLevel, OptimizeMode},
+    parse_file, validate,
+};
+
+const DATA: &str = r#"syntax = "proto2";
+
+package shop.v1;
+
+option go_package = "example.com/shop/v1;shopv1";
+option java_multiple_files = true;
+option optimize_for = CODE_SIZE;
+
+message Order {
+  option deprecated = true;
+  repeated int32 counts = 1 [packed = true, json_name = "Counts"];
+  optional string id = 2;
+}
+
+enum Status {
+  option allow_alias = true;
+  STATUS_UNKNOWN = 0;
+  STATUS_NONE = 0 [deprecated = true];
+}
+
+service Orders {
+  rpc Get(Order) returns (Order) {
+    option idempotency_level = NO_SIDE_EFFECTS;
+  }
+}
+"#;
+
+#[test]
+fn reads_standard_options() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    assert_eq!(proto.go_package()?, Some("example.com/shop/v1;shopv1"));
+    assert_eq!(proto.java_package()?, None);
+    assert!(proto.java_multiple_files()?);
+    assert_eq!(proto.optimize_for()?, OptimizeMode::CodeSize);
+    assert_eq!(proto.optimize_for()?.number(), 2);
+    assert!(!proto.is_deprecated()?);
+
+    let order = &proto.messages()[0];
+    assert!(order.is_deprecated()?);
+    let fields = order
+        .fields()
+        .iter()
+        .map(|field| match field {
+            Field::Normal(field) => field,
+            _ => panic!("expected normal fields"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(fields[0].packed()?, Some(true));
+    assert_eq!(fields[0].json_name()?, Some("Counts"));
+    assert_eq!(fields[1].packed()?, None);
+    assert!(!fields[1].is_deprecated()?);
+
+    let status = &proto.enums()[0];
+    assert!(status.allow_alias()?);
+    assert!(status.fields()[1].is_deprecated()?);
+
+    let rpc = &proto.services()[0].rpcs()[0];
+    assert!(!proto.services()[0].is_deprecated()?);
+    assert_eq!(rpc.idempotency_level()?, IdempotencyLevel::NoSideEffects);
+    assert_eq!(rpc.idempotency_level()?.as_str(), "NO_SIDE_EFFECTS");
+    assert_eq!(rpc.idempotency_level()?.number(), 1);
+    Ok(())
+}
+
+#[test]
+fn reports_mismatched_types() -> Result<(), Box<dyn std::error::Error>> {
+    let data = r#"syntax = "proto3";
+
+option go_package = true;
+option optimize_for = FAST;
+
+enum Status {
+  option allow_alias = "yes";
+  STATUS_UNKNOWN = 0;
+  STATUS_NONE = 0;
+}
+"#;
+    let proto = parse_file(data)?;
+    let error = proto.go_package().unwrap_err();
+    assert_eq!(*error.code(), DiagnosticCode::InvalidOptionValue);
+    assert_eq!(error.span().as_str(data), "true");
+    assert_eq!(
+        error.message(),
+        "invalid value for option `go_package`: expected a string"
+    );
+    let error = proto.optimize_for().unwrap_err();
+    assert_eq!(
+        error.message(),
+        "invalid value for option `optimize_for`: expected one of SPEED, CODE_SIZE, LITE_RUNTIME"
+    );
+
+    let codes = validate(&proto)
+        .iter()
+        .map(|diagnostic| *diagnostic.code())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        [
+            DiagnosticCode::InvalidOptionValue,
+            DiagnosticCode::DuplicateEnumValue
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use harpi::{
+    model::{Constant, Field, IntoOwned, Proto, Type},
+    parse_file,
+};
+
+fn parse_owned(data: String) -> Result<Proto<'static>, Box<dyn std::error::Error>> {
+    let proto = parse_file(&data)?.into_owned();
+    drop(data);
+    Ok(proto)
+}
+
+#[test]
+fn outlives_source() -> Result<(), Box<dyn std::error::Error>> {
+    let data = r#"syntax = "proto3";
+
+package shop.v1;
+
+// An order.
+message Order {
+  string id = 1 [(shop.meta) = { label: "Id" tags: ["a", "b"] }];
+  shop.v1.Order parent = 2;
+  map<string, int32> counts = 3;
+}
+"#;
+    let proto = parse_owned(data.to_owned())?;
+    let printed = std::thread::spawn(move || proto.to_string())
+        .join()
+        .unwrap();
+    assert_eq!(parse_file(&printed)?.to_string(), printed);
+
+    let proto = parse_owned(data.to_owned())?;
+    let order = &proto.messages()[0];
+    assert_eq!(order.comments()[0].value(), " An order.");
+    let Field::Normal(id) = &order.fields()[0] else {
+        panic!("expected a normal field");
+    };
+    let Constant::Aggregate(meta) = id.options()[0].value() else {
+        panic!("expected an aggregate");
+    };
+    assert!(meta.get("tags").is_some());
+    let Field::Normal(parent) = IntoOwned::into_owned(order.fields()[1].clone()) else {
+        panic!("expected a normal field");
+    };
+    assert!(matches!(parent.ty(), Type::Reference(name) if name == "shop.v1.Order"));
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use harpi::{Descriptor, DescriptorPool, Linker, model::Field, parse_file};
+
+const COMMON: &str = r#"syntax = "proto2";
+
+package common;
+
+import "google/protobuf/descriptor.proto";
+
+enum Currency {
+  CURRENCY_UNKNOWN = 0;
+  EUR = 1;
+}
+
+extend google.protobuf.FieldOptions {
+  optional bool sensitive = 50000;
+  optional group Audit = 50001 {
+    optional string owner = 1;
+  }
+}
+"#;
+
+const SHOP: &str = r#"syntax = "proto2";
+
+package foo.v1;
+
+import "common.proto";
+
+message Order {
+  message Item {
+    optional string sku = 1;
+    optional common.Currency currency = 2;
+  }
+  repeated Item items = 1;
+  optional group Note = 2 {
+    optional string text = 1;
+  }
+  enum Status {
+    STATUS_UNKNOWN = 0;
+    STATUS_PAID = 1;
+  }
+}
+
+service Orders {
+  rpc Get(Order) returns (Order.Item);
+}
+"#;
+
+fn pool() -> Result<DescriptorPool, Box<dyn std::error::Error>> {
+    let common = parse_file(COMMON)?;
+    let shop = parse_file(SHOP)?;
+    Ok(DescriptorPool::new(
+        &Linker::new()
+            .with_file("common.proto", &common)
+            .with_file("foo/v1/shop.proto", &shop),
+    ))
+}
+
+#[test]
+fn looks_up_full_names() -> Result<(), Box<dyn std::error::Error>> {
+    let pool = pool()?;
+    let item = pool.message("foo.v1.Order.Item").unwrap();
+    assert_eq!(item.name().value(), "Item");
+    assert_eq!(
+        pool.message(".foo.v1.Order.Note").unwrap().fields().len(),
+        1
+    );
+    assert!(pool.message("foo.v1.Order.Status").is_none());
+
+    let status = pool.enumeration("foo.v1.Order.Status").unwrap();
+    assert_eq!(status.fields().len(), 2);
+    assert_eq!(
+        *pool
+            .enum_value("foo.v1.Order.STATUS_PAID")
+            .unwrap()
+            .number(),
+        1
+    );
+    assert_eq!(pool.enum_value("common.EUR").unwrap().name().value(), "EUR");
+
+    assert_eq!(pool.service("foo.v1.Orders").unwrap().rpcs().len(), 1);
+    let get = pool.method("foo.v1.Orders.Get").unwrap();
+    assert_eq!(get.name().value(), "Get");
+
+    let Field::Normal(sensitive) = pool.extension("common.sensitive").unwrap() else {
+        panic!("expected a normal field");
+    };
+    assert_eq!(*sensitive.number(), 50000);
+    assert!(matches!(
+        pool.extension("common.audit"),
+        Some(Field::Group(_))
+    ));
+    assert_eq!(pool.message("common.Audit").unwrap().fields().len(), 1);
+    assert!(pool.get("foo.v1.Missing").is_none());
+    Ok(())
+}
+
+#[test]
+fn groups_by_package_and_file() -> Result<(), Box<dyn std::error::Error>> {
+    let pool = pool()?;
+    assert_eq!(pool.packages().collect::<Vec<_>>(), ["common", "foo.v1"]);
+    let names = pool
+        .package("foo.v1")
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "foo.v1.Order",
+            "foo.v1.Order.Item",
+            "foo.v1.Order.Note",
+            "foo.v1.Order.Status",
+            "foo.v1.Order.STATUS_UNKNOWN",
+            "foo.v1.Order.STATUS_PAID",
+            "foo.v1.Orders",
+            "foo.v1.Orders.Get",
+        ]
+    );
+    assert!(matches!(
+        pool.package("common").next(),
+        Some(("common.Currency", Descriptor::Enum(_)))
+    ));
+    assert_eq!(pool.package("missing").count(), 0);
+
+    assert_eq!(pool.file_of("foo.v1.Order.Item"), Some("foo/v1/shop.proto"));
+    assert_eq!(pool.file_of(".common.Currency"), Some("common.proto"));
+    assert_eq!(pool.file_of("foo.v1"), None);
+    let shop = pool.file("foo/v1/shop.proto").unwrap();
+    assert_eq!(shop.package().value(), "foo.v1");
+    assert_eq!(
+        pool.files().map(|(name, _)| name).collect::<Vec<_>>(),
+        ["common.proto", "foo/v1/shop.proto"]
+    );
+    Ok(())
+}
+
+#[test]
+fn shares_across_threads() -> Result<(), Box<dyn std::error::Error>> {
+    let pool = Arc::new(pool()?);
+    let linked = pool.linked();
+    let currency = linked
+        .references()
+        .iter()
+        .find(|reference| reference.name() == "common.Currency")
+        .and_then(|reference| reference.target().as_ref());
+    assert_eq!(
+        currency.map(|symbol| symbol.full_name().as_str()),
+        Some("common.Currency")
+    );
+
+    let handles = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || pool.message("foo.v1.Order.Item").is_some())
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        assert!(handle.join().unwrap());
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use harpi::{CommentStyle, Printer, QuoteStyle, descriptor::encode_file, parse_file};
+
+const DATA: &str = r#"syntax = "proto3";
+package shop.v1;
+import   "google/protobuf/any.proto";
+option java_package="com.shop";
+// An order.
+message Order { string id=1; map<string,Item> items = 2;
+  oneof payment { string card = 3; bytes token = 4 [ctype = CORD]; }
+  reserved 8, 10 to max; reserved "old";
+  message Item { repeated int64 counts = 1 [packed=false, json_name="c\"s"]; }
+}
+enum Status { option allow_alias=true; STATUS_UNSPECIFIED=0; DONE=1; }
+service Orders { rpc Get(Order) returns (Order); rpc Watch(stream Order) returns (stream Order) { option deprecated = true; } }
+"#;
+
+const ENUM: &str = r#"enum Status {
+  option allow_alias = true;
+
+  STATUS_UNSPECIFIED = 0;
+  DONE = 1;
+}
+"#;
+
+#[test]
+fn formats_declarations() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let expected = format!(
+        r#"syntax = "proto3";
+
+package shop.v1;
+
+import "google/protobuf/any.proto";
+
+option java_package = "com.shop";
+
+// An order.
+message Order {{
+  string id = 1;
+  map<string, Item> items = 2;
+  oneof payment {{
+    string card = 3;
+    bytes token = 4 [ctype = CORD];
+  }}
+
+  reserved 8, 10 to max;
+  reserved "old";
+
+  message Item {{
+    repeated int64 counts = 1 [packed = false, json_name = "c\"s"];
+  }}
+}}
+
+{ENUM}
+service Orders {{
+  rpc Get(Order) returns (Order);
+  rpc Watch(stream Order) returns (stream Order) {{
+    option deprecated = true;
+  }}
+}}
+"#
+    );
+    assert_eq!(proto.to_string(), expected);
+    assert_eq!(proto.enums()[0].to_string(), ENUM);
+    Ok(())
+}
+
+const PROTO2: &str = r#"syntax = "proto2";
+// Retry settings.
+message Config {
+  optional int32 retries = 1 [default = 3];
+  repeated string hosts = 2;
+  optional group Limits = 3 { required double ratio = 4 [default = 0.5]; }
+  extensions 100 to 199;
+  option (acme.meta) = { owner: "ops" tags: ["a", "b"] limits { max: 10 } };
+}
+"#;
+
+#[test]
+fn applies_printer_options() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(PROTO2)?;
+    let printer = Printer::new()
+        .with_indent(4)
+        .with_alignment(true)
+        .with_max_width(48)
+        .with_comments(CommentStyle::Block)
+        .with_quotes(QuoteStyle::Single);
+    let expected = r#"syntax = 'proto2';
+
+/* Retry settings.*/
+message Config {
+    option (acme.meta) = {
+        owner: 'ops'
+        tags: ['a', 'b']
+        limits: { max: 10 }
+    };
+
+    optional int32 retries = 1 [default = 3];
+    repeated string hosts  = 2;
+    optional group Limits = 3 {
+        required double ratio = 4 [
+            default = 0.5
+        ];
+    }
+
+    extensions 100 to 199;
+}
+"#;
+    let printed = printer.print(&proto);
+    assert_eq!(printed, expected);
+    assert_eq!(printer.print(&parse_file(&printed)?), expected);
+    Ok(())
+}
+
+#[test]
+fn round_trips_fixtures() -> Result<(), Box<dyn std::error::Error>> {
+    let printer = Printer::new();
+    for data in [
+        include_str!("../proto/unittest_proto3.proto"),
+        include_str!("../proto/unittest_proto2.proto"),
+        include_str!("../proto/unittest_editions.proto"),
+        include_str!("../proto/unittest_edition_2024.proto"),
+        include_str!("../proto/test_messages_proto3.proto"),
+    ] {
+        let proto = parse_file(data)?;
+        let printed = printer.print(&proto);
+        let reparsed = parse_file(&printed)?;
+        assert_eq!(printer.print(&reparsed), printed);
+        assert_eq!(
+            encode_file("fixture.proto", &reparsed).0,
+            encode_file("fixture.proto", &proto).0
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use harpi::{
+    header,
+    model::{Constant, Field, Span},
+    parse_file,
+};
+
+#[test]
+fn proto2_constructs() -> Result<(), Box<dyn std::error::Error>> {
+    let data = std::fs::read_to_string("./proto/unittest_proto2.proto")?;
+    let proto = parse_file(data.as_str())?;
+    assert_eq!(proto.syntax().value(), "proto2");
+
+    let message = &proto.messages()[1];
+    assert_eq!(message.name().value(), "FileDescriptorProto");
+    let weak_count = message
+        .fields()
+        .iter()
+        .find_map(|field| match field {
+            Field::Normal(field) if field.name().value() == "weak_count" => Some(field),
+            _ => None,
+        })
+        .ok_or("missing weak_count")?;
+    assert!(*weak_count.required());
+    assert!(matches!(weak_count.default_value(), Some(Constant::Int(0))));
+
+    let groups = message
+        .fields()
+        .iter()
+        .filter_map(|field| match field {
+            Field::Group(group) => Some(group),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[1].name().value(), "RepeatedGroup");
+    assert!(*groups[1].repeated());
+    assert_eq!(*groups[1].number(), 46);
+    assert_eq!(groups[1].message().fields().len(), 2);
+
+    let ranges = message
+        .extensions()
+        .iter()
+        .flat_map(|extensions| extensions.ranges().iter())
+        .map(|range| (*range.start(), *range.end()))
+        .collect::<Vec<_>>();
+    assert_eq!(ranges, vec![(100, 199), (1000, i64::MAX)]);
+    assert_eq!(message.extends().len(), 1);
+
+    assert_eq!(proto.extends().len(), 1);
+    let extend = &proto.extends()[0];
+    assert_eq!(extend.extendee().value(), "FileDescriptorProto");
+    assert_eq!(extend.fields().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn trailing_comment() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file("syntax = \"proto2\";\nmessage A {}\n// trailing\n")?;
+    assert_eq!(proto.messages()[0].name().value(), "A");
+    let proto = parse_file("syntax = \"proto2\";\nmessage A {} /* trailing */")?;
+    assert_eq!(proto.messages().len(), 1);
+    Ok(())
+}
+
+#[test]
+fn without_syntax() -> Result<(), Box<dyn std::error::Error>> {
+    let data = "message A {\n  required int32 a = 1;\n}\n";
+    let (syntax, body) = header::parse_header(data)?;
+    assert_eq!(syntax.value(), "proto2");
+    assert!(!*syntax.edition());
+    assert_eq!(*syntax.span(), Span::default());
+    assert_eq!(body, data);
+
+    let proto = parse_file(data)?;
+    let Field::Normal(field) = &proto.messages()[0].fields()[0] else {
+        return Err("expected a normal field".into());
+    };
+    assert!(*field.required());
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use harpi::{DiagnosticCode, parse_file, parse_file_recovering};
+
+const DATA: &str = r#"syntax = "proto3";
+
+package my.recovery;
+
+message Broken {
+  string name = ;
+  message Nested { int32 id = 1; }
+}
+
+message Person {
+  string name = 1;
+}
+
+messag Typo {}
+
+enum Kind {
+  KIND_UNSPECIFIED = 0;
+}
+
+option java_package = ;
+"#;
+
+#[test]
+fn recovers_after_errors() {
+    assert!(parse_file(DATA).is_err());
+
+    let (proto, diagnostics) = parse_file_recovering(DATA);
+    assert_eq!(proto.package().value(), "my.recovery");
+    let messages = proto
+        .messages()
+        .iter()
+        .map(|message| message.name().value())
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["Person"]);
+    assert_eq!(proto.enums().len(), 1);
+
+    let lines = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(*diagnostic.code(), DiagnosticCode::Syntax);
+            let start = diagnostic.span().start();
+            (start.line(), start.column())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(6, 17), (14, 1), (20, 23)]);
+}
+
+#[test]
+fn recovering_valid_file() {
+    let (proto, diagnostics) = parse_file_recovering("syntax = \"proto3\";\nmessage A {}\n");
+    assert!(diagnostics.is_empty());
+    assert_eq!(proto.messages().len(), 1);
+}
+
+const PROTO2: &str = r#"syntax = "proto2";
+
+message Broken {
+  required string name = 1
+}
+
+message Person {
+  optional string name = 1;
+  repeated group Address = 2 {
+    required string city = 3;
+  }
+}
+
+extend Person {
+  optional int32 age = ;
+}
+"#;
+
+#[test]
+fn recovers_proto2_after_errors() {
+    assert!(parse_file(PROTO2).is_err());
+
+    let (proto, diagnostics) = parse_file_recovering(PROTO2);
+    let messages = proto
+        .messages()
+        .iter()
+        .map(|message| message.name().value())
+        .collect::<Vec<_>>();
+    assert_eq!(messages, ["Person"]);
+    assert_eq!(proto.messages()[0].fields().len(), 2);
+    assert!(proto.extends().is_empty());
+
+    let lines = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            assert_eq!(*diagnostic.code(), DiagnosticCode::Syntax);
+            let start = diagnostic.span().start();
+            (start.line(), start.column())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(lines, [(4, 27), (15, 24)]);
+}
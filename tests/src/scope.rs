@@ -0,0 +1,115 @@
+use harpi::{
+    DefaultParser, ProtoParser, Scope, ScopeVisitor, ScopeWalker,
+    model::{
+        Enum, EnumItem, Extend, GroupField, Message, NormalField, OneOfField, OneOfFieldItem,
+        Option, ServiceRpc,
+    },
+    parse_file, walk,
+};
+
+const DATA: &str = r#"syntax = "proto2";
+
+package shop.v1;
+
+message Order {
+  option deprecated = true;
+  optional string id = 1 [json_name = "ID"];
+  oneof payment {
+    string card = 2;
+  }
+  optional group Line = 3 {
+    optional int32 count = 4;
+  }
+  enum Status {
+    NEW = 0;
+  }
+  extend Order {
+    optional int32 note = 100;
+  }
+  extensions 100 to 199;
+}
+
+service Orders {
+  rpc Get(Order) returns (Order);
+}
+"#;
+
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl Events {
+    fn push(&mut self, event: &str, scope: &Scope) {
+        self.0.push(format!("{event} {}", scope.full_name()));
+    }
+}
+
+impl<'a> ScopeVisitor<'a> for Events {
+    fn visit_option(&mut self, scope: &Scope, _: &Option<'a>) {
+        self.push("option", scope);
+    }
+    fn enter_message(&mut self, scope: &Scope, _: &Message<'a>) {
+        self.push("enter message", scope);
+    }
+    fn exit_message(&mut self, scope: &Scope, _: &Message<'a>) {
+        self.push("exit message", scope);
+    }
+    fn enter_field(&mut self, scope: &Scope, _: &NormalField<'a>) {
+        self.push("field", scope);
+    }
+    fn enter_group(&mut self, scope: &Scope, _: &GroupField<'a>) {
+        self.push("enter group", scope);
+    }
+    fn exit_group(&mut self, scope: &Scope, _: &GroupField<'a>) {
+        self.push("exit group", scope);
+    }
+    fn enter_oneof(&mut self, scope: &Scope, _: &OneOfField<'a>) {
+        self.push("oneof", scope);
+    }
+    fn enter_oneof_field(&mut self, scope: &Scope, _: &OneOfFieldItem<'a>) {
+        self.push("oneof field", scope);
+    }
+    fn enter_enum(&mut self, scope: &Scope, _: &Enum<'a>) {
+        self.push("enum", scope);
+    }
+    fn enter_enum_value(&mut self, scope: &Scope, _: &EnumItem<'a>) {
+        self.push("enum value", scope);
+    }
+    fn enter_extend(&mut self, scope: &Scope, _: &Extend<'a>) {
+        self.push("extend", scope);
+    }
+    fn enter_rpc(&mut self, scope: &Scope, _: &ServiceRpc<'a>) {
+        self.push("rpc", scope);
+    }
+}
+
+#[test]
+fn walks_nested_declarations() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let mut events = Events::default();
+    walk(&proto, &mut events);
+    assert_eq!(
+        events.0,
+        [
+            "enter message shop.v1.Order",
+            "option shop.v1.Order",
+            "field shop.v1.Order.id",
+            "option shop.v1.Order.id",
+            "oneof shop.v1.Order.payment",
+            "oneof field shop.v1.Order.card",
+            "enter group shop.v1.Order.Line",
+            "field shop.v1.Order.Line.count",
+            "exit group shop.v1.Order.Line",
+            "enum shop.v1.Order.Status",
+            "enum value shop.v1.Order.NEW",
+            "extend shop.v1.Order",
+            "field shop.v1.Order.note",
+            "exit message shop.v1.Order",
+            "rpc shop.v1.Orders.Get",
+        ]
+    );
+
+    let mut streamed = Events::default();
+    DefaultParser::parse(DATA, &mut ScopeWalker::new(&mut streamed))?;
+    assert_eq!(streamed.0, events.0);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use harpi::{
+    model::{Field, Proto},
+    parse_file,
+};
+use serde_json::json;
+
+const DATA: &str = r#"syntax = "proto2";
+
+package shop.v1;
+
+// An order.
+message Order {
+  optional uint32 id = 1 [default = 7];
+  optional shop.v1.Order parent = 2 [(shop.meta) = { tags: ["a", "b"] }];
+  oneof payment {
+    string card = 3;
+  }
+  map<string, int32> counts = 4;
+  reserved 10 to 12, 15;
+  reserved "old";
+}
+"#;
+
+#[test]
+fn serializes_model() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let value = serde_json::to_value(&proto)?;
+    let order = &value["messages"][0];
+    assert_eq!(order["name"]["value"], "Order");
+    assert_eq!(order["visibility"], "default");
+
+    let fields = order["fields"].as_array().unwrap();
+    assert_eq!(fields[0]["kind"], "normal");
+    assert_eq!(fields[0]["value"]["ty"], json!({ "kind": "uint32" }));
+    assert_eq!(
+        fields[0]["value"]["options"][0]["value"],
+        json!({ "kind": "int", "value": 7 })
+    );
+    assert_eq!(
+        fields[1]["value"]["ty"],
+        json!({ "kind": "reference", "value": "shop.v1.Order" })
+    );
+    let meta = &fields[1]["value"]["options"][0]["value"];
+    assert_eq!(meta["kind"], "aggregate");
+    assert_eq!(
+        meta["value"]["fields"][0]["value"],
+        json!({
+            "kind": "list",
+            "value": [
+                { "kind": "constant", "value": { "kind": "string", "value": "a" } },
+                { "kind": "constant", "value": { "kind": "string", "value": "b" } },
+            ],
+        })
+    );
+    assert_eq!(fields[2]["kind"], "oneof");
+    assert_eq!(fields[3]["kind"], "map");
+    assert_eq!(fields[3]["value"]["key_ty"], "string");
+
+    let reserved = order["reserved"].as_array().unwrap();
+    assert_eq!(
+        reserved[0]["items"][0],
+        json!({ "kind": "range", "value": [10, 12] })
+    );
+    assert_eq!(reserved[1]["items"][0]["kind"], "field");
+
+    let comment = &order["comments"][0];
+    assert_eq!(comment[0], " An order.");
+    assert_eq!(comment[2], "line");
+    Ok(())
+}
+
+#[test]
+fn deserializes_model() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let json = serde_json::to_string(&proto)?;
+    let decoded: Proto<'static> = serde_json::from_str(&json)?;
+    assert_eq!(decoded.to_string(), proto.to_string());
+    let (Field::Normal(decoded), Field::Normal(id)) = (
+        &decoded.messages()[0].fields()[0],
+        &proto.messages()[0].fields()[0],
+    ) else {
+        panic!("expected normal fields");
+    };
+    assert_eq!(decoded.span(), id.span());
+    Ok(())
+}
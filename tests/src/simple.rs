@@ -1,15 +1,17 @@
+use harpi::editions::Editions;
+use harpi::proto2::Proto2;
 use harpi::proto3::Proto3;
 use harpi::{ProtoParser, ProtoVisitor};
 
 #[derive(ProtoParser)]
-#[parser(Proto3)]
+#[parser(Proto3, Proto2, Editions)]
 struct MyParser;
 
 #[derive(Default)]
 struct NoOpVisitor;
 
-impl ProtoVisitor for NoOpVisitor {
-    fn on<'a>(&mut self, _: harpi::Node<'a>) {}
+impl<'a> ProtoVisitor<'a> for NoOpVisitor {
+    fn on(&mut self, _: harpi::Node<'a>) {}
 }
 #[test]
 fn simple_proto() -> Result<(), Box<dyn std::error::Error>> {
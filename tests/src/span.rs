@@ -0,0 +1,100 @@
+use harpi::{
+    DefaultParser, Error, ProtoCollector, ProtoParser,
+    model::{Field, Syntax},
+    parse_file,
+    proto3::Proto3,
+};
+
+const DATA: &str = r#"syntax = "proto3";
+
+package my.spans;
+
+// A message with a comment.
+message Person {
+  option deprecated = true;
+  string name = 1;
+  map<string, int32> scores = 2;
+}
+"#;
+
+#[test]
+fn spans() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    assert_eq!(proto.syntax().span().as_str(DATA), r#"syntax = "proto3";"#);
+    assert_eq!(proto.syntax().value_span().as_str(DATA), "proto3");
+
+    let package = proto.package();
+    assert_eq!(package.value_span().as_str(DATA), "my.spans");
+    assert_eq!(package.span().start().line(), 3);
+
+    let message = &proto.messages()[0];
+    let span = message.span();
+    assert!(span.as_str(DATA).starts_with("message Person {"));
+    assert!(span.as_str(DATA).ends_with('}'));
+    assert_eq!((span.start().line(), span.start().column()), (6, 1));
+    assert_eq!(
+        message.comments()[0].span().as_str(DATA),
+        "// A message with a comment."
+    );
+
+    let name = message.name().span();
+    assert_eq!((name.start().line(), name.start().column()), (6, 9));
+    assert_eq!(name.as_str(DATA), "Person");
+
+    let option = &message.options()[0];
+    assert_eq!(option.span().as_str(DATA), "option deprecated = true;");
+    assert_eq!(option.name().span().as_str(DATA), "deprecated");
+    assert_eq!(option.value_span().as_str(DATA), "true");
+
+    let Field::Normal(field) = &message.fields()[0] else {
+        return Err("expected a normal field".into());
+    };
+    assert_eq!(field.span().as_str(DATA), "string name = 1;");
+    assert_eq!(field.ty_span().as_str(DATA), "string");
+    assert_eq!(field.number_span().as_str(DATA), "1");
+    assert_eq!(
+        (
+            field.number_span().start().line(),
+            field.number_span().start().column()
+        ),
+        (8, 17)
+    );
+
+    let Field::Map(field) = &message.fields()[1] else {
+        return Err("expected a map field".into());
+    };
+    assert_eq!(field.key_ty_span().as_str(DATA), "string");
+    assert_eq!(field.value_ty_span().as_str(DATA), "int32");
+    assert_eq!(field.number_span().as_str(DATA), "2");
+    Ok(())
+}
+
+#[test]
+fn spans_with_syntax() -> Result<(), Box<dyn std::error::Error>> {
+    let mut syntax = Syntax::builder();
+    syntax.set_value("proto3");
+    let syntax = syntax.build();
+    let mut collector = ProtoCollector::new();
+    DefaultParser::parse_with_syntax(DATA, syntax, &mut collector)?;
+    let proto = collector.finish()?;
+    assert_eq!(proto, parse_file(DATA)?);
+    let name = proto.messages()[0].name().span();
+    assert_eq!((name.start().line(), name.start().column()), (6, 9));
+
+    let mut collector = ProtoCollector::new();
+    Proto3::parse_with_syntax(DATA, proto.syntax().clone(), &mut collector)?;
+    assert_eq!(collector.finish()?, proto);
+
+    let data = "edition = \"2023\";\nmessage A {}\n";
+    let mut syntax = Syntax::builder();
+    syntax.set_value("2024");
+    syntax.set_edition(true);
+    let syntax = syntax.build();
+    let mut collector = ProtoCollector::new();
+    let result = DefaultParser::parse_with_syntax(data, syntax, &mut collector);
+    assert!(matches!(
+        result,
+        Err(Error::InvalidSyntax(expected, found)) if expected == "2024" && found == "2023"
+    ));
+    Ok(())
+}
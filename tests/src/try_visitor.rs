@@ -0,0 +1,85 @@
+use std::{fmt, ops::ControlFlow};
+
+use harpi::{DefaultParser, DiagnosticCode, Error, Node, ProtoParser, TryProtoVisitor};
+
+const DATA: &str = r#"syntax = "proto3";
+
+message Allowed {}
+
+message Banned {}
+
+message Unreachable {}
+"#;
+
+#[derive(Debug, PartialEq)]
+struct BannedType(String);
+
+impl fmt::Display for BannedType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "type `{}` is banned", self.0)
+    }
+}
+
+impl std::error::Error for BannedType {}
+
+#[derive(Default)]
+struct BanVisitor {
+    seen: Vec<String>,
+}
+
+impl<'a> TryProtoVisitor<'a> for BanVisitor {
+    type Error = BannedType;
+
+    fn try_on(&mut self, node: Node<'a>) -> Result<ControlFlow<()>, BannedType> {
+        if let Node::Message(message) = node {
+            let name = message.name().value().to_string();
+            if name == "Banned" {
+                return Err(BannedType(name));
+            }
+            self.seen.push(name);
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+#[test]
+fn surfaces_visitor_errors() {
+    let mut visitor = BanVisitor::default();
+    let error = DefaultParser::try_parse(DATA, &mut visitor).unwrap_err();
+    assert!(matches!(error, Error::Visitor(_)));
+    assert_eq!(
+        error.visitor_error::<BannedType>(),
+        Some(&BannedType("Banned".to_string()))
+    );
+    assert_eq!(error.to_string(), "type `Banned` is banned");
+    assert_eq!(*error.to_diagnostic().code(), DiagnosticCode::Visitor);
+    assert_eq!(visitor.seen, ["Allowed"]);
+}
+
+struct FirstMessage(Option<String>);
+
+impl<'a> TryProtoVisitor<'a> for FirstMessage {
+    type Error = std::convert::Infallible;
+
+    fn try_on(&mut self, node: Node<'a>) -> Result<ControlFlow<()>, Self::Error> {
+        match node {
+            Node::Message(message) => {
+                self.0 = Some(message.name().value().to_string());
+                Ok(ControlFlow::Break(()))
+            }
+            Node::End => panic!("parsing should have stopped"),
+            _ => Ok(ControlFlow::Continue(())),
+        }
+    }
+}
+
+#[test]
+fn stops_on_break() -> Result<(), Box<dyn std::error::Error>> {
+    let mut visitor = FirstMessage(None);
+    DefaultParser::try_parse(
+        "syntax = \"proto2\";\nmessage A {}\nmessage B {}\n",
+        &mut visitor,
+    )?;
+    assert_eq!(visitor.0.as_deref(), Some("A"));
+    Ok(())
+}
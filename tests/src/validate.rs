@@ -0,0 +1,153 @@
+use harpi::{DiagnosticCode, parse_file, validate};
+
+const PROTO3: &str = r#"syntax = "proto3";
+
+message Invalid {
+  reserved 4, 10 to 12;
+  reserved "legacy";
+  string name = 1;
+  string alias = 1;
+  int32 internal = 19000;
+  int32 huge = 536870912;
+  int32 collides = 11;
+  int32 legacy = 13;
+  oneof choice {
+    string other = 13;
+  }
+
+  enum Status {
+    STATUS_ACTIVE = 1;
+    STATUS_ENABLED = 1;
+  }
+}
+
+enum Alias {
+  option allow_alias = true;
+  ALIAS_UNSPECIFIED = 0;
+  ALIAS_DEFAULT = 0;
+}
+"#;
+
+#[test]
+fn reports_proto3_violations() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(PROTO3)?;
+    let diagnostics = validate(&proto);
+    let found = diagnostics
+        .iter()
+        .map(|diagnostic| (*diagnostic.code(), diagnostic.span().start().line()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (DiagnosticCode::DuplicateFieldNumber, 7),
+            (DiagnosticCode::ReservedFieldNumber, 8),
+            (DiagnosticCode::FieldNumberOutOfRange, 9),
+            (DiagnosticCode::ReservedFieldCollision, 10),
+            (DiagnosticCode::ReservedFieldCollision, 11),
+            (DiagnosticCode::DuplicateFieldNumber, 13),
+            (DiagnosticCode::EnumFirstValueNotZero, 17),
+            (DiagnosticCode::DuplicateEnumValue, 18),
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].message(),
+        "field number 1 of `alias` is already used by `name`"
+    );
+    Ok(())
+}
+
+#[test]
+fn reports_required_extensions() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(
+        r#"syntax = "proto2";
+message Base {
+  required int32 id = 1;
+  extensions 100 to 200;
+}
+extend Base {
+  required int32 extra = 100;
+}
+"#,
+    )?;
+    let diagnostics = validate(&proto);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(*diagnostics[0].code(), DiagnosticCode::RequiredMisuse);
+    assert_eq!(diagnostics[0].span().start().line(), 7);
+    Ok(())
+}
+
+#[test]
+fn accepts_valid_files() -> Result<(), Box<dyn std::error::Error>> {
+    for source in [
+        include_str!("../proto/unittest_proto3.proto"),
+        include_str!("../proto/unittest_editions.proto"),
+    ] {
+        let proto = parse_file(source)?;
+        let diagnostics = validate(&proto);
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn accepts_closed_editions_enums() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(
+        r#"edition = "2023";
+
+enum Open {
+  OPEN_ONE = 1;
+}
+
+enum Closed {
+  option features.enum_type = CLOSED;
+  CLOSED_ONE = 1;
+}
+
+message Legacy {
+  option features.enum_type = CLOSED;
+  enum Kind {
+    KIND_ONE = 1;
+  }
+  enum Reopened {
+    option features.enum_type = OPEN;
+    REOPENED_ONE = 1;
+  }
+}
+"#,
+    )?;
+    let found = validate(&proto)
+        .iter()
+        .map(|diagnostic| (*diagnostic.code(), diagnostic.span().start().line()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (DiagnosticCode::EnumFirstValueNotZero, 19),
+            (DiagnosticCode::EnumFirstValueNotZero, 4),
+        ]
+    );
+
+    let closed = parse_file(
+        "edition = \"2023\";\noption features.enum_type = CLOSED;\nenum Kind {\n  KIND_ONE = 1;\n}\n",
+    )?;
+    assert!(validate(&closed).is_empty());
+    Ok(())
+}
+
+#[test]
+fn reports_numbers_beyond_i64() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(
+        r#"syntax = "proto3";
+message Huge {
+  reserved 1 to max;
+  int32 huge = 18446744073709551615;
+}
+"#,
+    )?;
+    let codes = validate(&proto)
+        .iter()
+        .map(|diagnostic| *diagnostic.code())
+        .collect::<Vec<_>>();
+    assert_eq!(codes, [DiagnosticCode::FieldNumberOutOfRange]);
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use harpi::{
+    fold::{self, Fold},
+    model::{Field, Ident, Message, MessageReference, Option, Package, Type},
+    parse_file,
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+};
+
+const DATA: &str = r#"syntax = "proto2";
+
+package shop.v1;
+
+message Order {
+  option deprecated = true;
+  optional string id = 1 [deprecated = true, json_name = "ID"];
+  optional shop.v1.Order parent = 2;
+  oneof payment {
+    string card = 3 [deprecated = true];
+  }
+  optional group Line = 4 {
+    optional int32 count = 5;
+  }
+  message Item {
+    map<string, shop.v1.Order> orders = 1;
+  }
+}
+
+service Orders {
+  rpc Get(shop.v1.Order) returns (shop.v1.Order);
+}
+"#;
+
+#[derive(Default)]
+struct Names(Vec<String>);
+impl<'ast> Visit<'ast> for Names {
+    fn visit_message(&mut self, node: &'ast Message<'ast>) {
+        self.0.push(node.name().value().to_owned());
+        visit::visit_message(self, node);
+    }
+    fn visit_option(&mut self, node: &'ast Option<'ast>) {
+        self.0.push(format!("option {}", node.name()));
+    }
+}
+
+#[test]
+fn visits_nested_declarations() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = parse_file(DATA)?;
+    let mut names = Names::default();
+    names.visit_proto(&proto);
+    assert_eq!(
+        names.0,
+        [
+            "Order",
+            "option deprecated",
+            "option deprecated",
+            "option json_name",
+            "option deprecated",
+            "Line",
+            "Item",
+        ]
+    );
+    Ok(())
+}
+
+struct Rename;
+impl Rename {
+    fn rename(value: &str) -> std::option::Option<String> {
+        value
+            .strip_prefix("shop.v1")
+            .map(|rest| format!("store.v2{rest}"))
+    }
+}
+impl<'a> VisitMut<'a> for Rename {
+    fn visit_package_mut(&mut self, node: &mut Package<'a>) {
+        if let Some(value) = Self::rename(node.value()) {
+            node.set_value(value);
+        }
+    }
+    fn visit_type_mut(&mut self, node: &mut Type<'a>) {
+        if let Type::Reference(value) = node
+            && let Some(renamed) = Self::rename(value)
+        {
+            *value = renamed.into();
+        }
+    }
+    fn visit_message_reference_mut(&mut self, node: &mut MessageReference<'a>) {
+        if let Some(value) = Self::rename(node.value()) {
+            node.set_value(value);
+        }
+    }
+}
+
+#[test]
+fn renames_package() -> Result<(), Box<dyn std::error::Error>> {
+    let mut proto = parse_file(DATA)?;
+    Rename.visit_proto_mut(&mut proto);
+    assert_eq!(proto.package().value(), "store.v2");
+    let printed = proto.to_string();
+    assert!(printed.contains("package store.v2;"));
+    assert!(printed.contains("optional store.v2.Order parent = 2;"));
+    assert!(printed.contains("map<string, store.v2.Order> orders = 1;"));
+    assert!(printed.contains("rpc Get(store.v2.Order) returns (store.v2.Order)"));
+    assert!(!printed.contains("shop.v1"));
+    Ok(())
+}
+
+struct StripDeprecated;
+impl<'a> Fold<'a> for StripDeprecated {
+    fn fold_options(&mut self, node: Vec<Option<'a>>) -> Vec<Option<'a>> {
+        fold::fold_options(self, node)
+            .into_iter()
+            .filter(|option| !option.name().is("deprecated"))
+            .collect()
+    }
+    fn fold_ident(&mut self, mut node: Ident<'a>) -> Ident<'a> {
+        if node.value() == "Line" {
+            node.set_value("Entry");
+        }
+        node
+    }
+}
+
+#[test]
+fn strips_options() -> Result<(), Box<dyn std::error::Error>> {
+    let proto = StripDeprecated.fold_proto(parse_file(DATA)?);
+    let order = &proto.messages()[0];
+    assert!(order.options().is_empty());
+    let Field::Group(line) = &order.fields()[3] else {
+        panic!("expected a group");
+    };
+    assert_eq!(line.name().value(), "Entry");
+    let printed = proto.to_string();
+    assert!(!printed.contains("deprecated"));
+    assert!(printed.contains("optional string id = 1 [json_name = \"ID\"];"));
+    assert!(printed.contains("string card = 3;"));
+    Ok(())
+}
+
+#[test]
+fn mutates_in_place() -> Result<(), Box<dyn std::error::Error>> {
+    struct Strip;
+    impl<'a> VisitMut<'a> for Strip {
+        fn visit_options_mut(&mut self, node: &mut Vec<Option<'a>>) {
+            node.retain(|option| !option.name().is("deprecated"));
+            visit_mut::visit_options_mut(self, node);
+        }
+    }
+    let mut proto = parse_file(DATA)?;
+    Strip.visit_proto_mut(&mut proto);
+    let mut names = Names::default();
+    names.visit_proto(&proto);
+    assert_eq!(names.0, ["Order", "option json_name", "Line", "Item"]);
+    Ok(())
+}